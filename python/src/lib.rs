@@ -0,0 +1,446 @@
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use verlet_integration::rand::Rng;
+use verlet_integration::{
+	generate_chain_links, generate_objects, vec2, Attractor, Bomb, ChainLink, DampingCurve, Flock, KillZone, Magnet, Master, Motor, NBody, Pole, Spring, StaticSegment, Vec2, VectorField, VerletObject,
+	EXPLOSION_RADIUS, EXPLOSION_STRENGTH, FLOCK_ALIGNMENT, FLOCK_COHESION, FLOCK_SEPARATION, FLOCK_SPEED, FLOCK_VISION, HIERARCHY_ITERATIONS, HIERARCHY_STRIDE,
+	N_BODY_SOFTENING, N_BODY_STRENGTH, N_BODY_THETA,
+};
+use verlet_integration::pool::Pool;
+
+// (a, b, point, normal, depth), b is None for the container
+type Contact = (usize, Option<usize>, (f32, f32), (f32, f32), f32);
+
+// the headless solver, one World per simulation:
+//
+//     import verlet
+//     world = verlet.World()
+//     a = world.add_particle(400, 300, 10)
+//     b = world.add_particle(440, 300, 10)
+//     world.add_link(a, b)
+//     world.step(steps=60)
+//     world.positions()  # numpy array, one (x, y) row per particle
+#[pyclass]
+pub struct World {
+	pub master: Master,
+}
+
+impl World {
+	fn push_link(&mut self, a: usize, b: usize, target_distance: Option<f32>, spring: Option<Spring>, max_strain: Option<f32>, group: Option<&str>) -> PyResult<()> {
+		self.check_id(a)?;
+		self.check_id(b)?;
+		if a == b {
+			return Err(PyValueError::new_err(format!("can't link particle {} to itself", a)));
+		}
+
+		let target_distance = target_distance.unwrap_or_else(||
+			self.master.objects[a].position.distance(self.master.objects[b].position)
+		);
+		let group = group.map(|name| self.master.group(name));
+		self.master.chain_links.push(ChainLink {
+			a,
+			b,
+			target_distance,
+			priority: 0,
+			spring,
+			max_strain,
+			group,
+		});
+
+		Ok(())
+	}
+
+	fn check_id(&self, id: usize) -> PyResult<()> {
+		if self.master.objects.contains_index(id) {
+			Ok(())
+		} else {
+			Err(PyIndexError::new_err(format!("no particle with id {}", id)))
+		}
+	}
+}
+
+#[pymethods]
+impl World {
+	// a seed makes everything it picks at random come out the same every run
+	#[new]
+	#[pyo3(signature = (seed=None))]
+	fn new(seed: Option<u64>) -> Self {
+		let mut master = Master::new(vec![], vec![]);
+		master.rng = seed.map(Rng::new);
+		Self { master }
+	}
+
+	// the rope bridge the demo starts with
+	#[staticmethod]
+	fn bridge() -> Self {
+		Self {
+			master: Master::new(generate_objects(), generate_chain_links()),
+		}
+	}
+
+	// returns the new particle's id
+	fn add_particle(&mut self, x: f32, y: f32, radius: f32) -> usize {
+		self.master.spawn(VerletObject::new(vec2(x, y), radius)).index
+	}
+
+	// along with its links, and its id can go to the next particle added. a hinge or soft
+	// body it was part of stops doing anything
+	fn remove_particle(&mut self, id: usize) -> PyResult<()> {
+		self.check_id(id)?;
+		self.master.remove_object(id);
+		Ok(())
+	}
+
+	// the target distance defaults to how far apart the particles are right now. with a
+	// max_strain it breaks once it's that much of it (0.1 is 10%) too long or short, and with a
+	// group it's switched off and on along with every other link in the group of that name
+	#[pyo3(signature = (a, b, target_distance=None, max_strain=None, group=None))]
+	fn add_link(&mut self, a: usize, b: usize, target_distance: Option<f32>, max_strain: Option<f32>, group: Option<&str>) -> PyResult<()> {
+		self.push_link(a, b, target_distance, None, max_strain, group)
+	}
+
+	// a link that stretches and bounces, stiffness and damping go from 0 to 1
+	#[pyo3(signature = (a, b, stiffness, damping, target_distance=None, max_strain=None, group=None))]
+	#[allow(clippy::too_many_arguments)]
+	fn add_spring(&mut self, a: usize, b: usize, stiffness: f32, damping: f32, target_distance: Option<f32>, max_strain: Option<f32>, group: Option<&str>) -> PyResult<()> {
+		self.push_link(a, b, target_distance, Some(Spring { stiffness, damping }), max_strain, group)
+	}
+
+	// (name, enabled) for every group
+	#[getter]
+	fn groups(&self) -> Vec<(String, bool)> {
+		self.master.groups.iter().map(|group| (group.name.clone(), group.enabled)).collect()
+	}
+
+	// the links in a group that's switched off don't hold anything together until it's back on
+	fn set_group(&mut self, name: &str, enabled: bool) -> PyResult<()> {
+		let group = self.master.groups.iter().position(|group| group.name == name)
+			.ok_or_else(|| PyValueError::new_err(format!("no group called {:?}", name)))?;
+		self.master.set_group_enabled(group, enabled);
+		Ok(())
+	}
+
+	// a and b turning around the pivot (an empty a is the world), limits are (min, max) in
+	// radians from how they are now and motor is (speed, torque)
+	#[pyo3(signature = (pivot, a, b, limits=None, motor=None))]
+	fn add_hinge(&mut self, pivot: usize, a: Vec<usize>, b: Vec<usize>, limits: Option<(f32, f32)>, motor: Option<(f32, f32)>) -> PyResult<usize> {
+		let motor = motor.map(|(speed, torque)| Motor { speed, torque });
+		self.master.add_hinge(pivot, a, b, limits, motor)
+			.ok_or_else(|| PyIndexError::new_err("a hinge needs a pivot and particles that are all there, and b can't be empty"))
+	}
+
+	// fills a ring of particles, listed in order around it and already linked, so it keeps the
+	// area it has now. pressure is how much of it comes back each step, from 0 to 1
+	#[pyo3(signature = (ids, pressure=0.5))]
+	fn add_soft_body(&mut self, ids: Vec<usize>, pressure: f32) -> PyResult<usize> {
+		self.master.add_soft_body(ids, pressure)
+			.ok_or_else(|| PyIndexError::new_err("a soft body needs at least 3 particles that are all there"))
+	}
+
+	// solves the particles (a big cloth or a long chain, already linked) coarsely before their
+	// own links, with proxies about stride links apart, so they don't stretch. build it while
+	// they're at rest, it keeps the proxies from getting further apart than they are now
+	#[pyo3(signature = (ids, stride=HIERARCHY_STRIDE, iterations=HIERARCHY_ITERATIONS))]
+	fn add_hierarchy(&mut self, ids: Vec<usize>, stride: usize, iterations: usize) -> PyResult<usize> {
+		self.master.add_hierarchy(ids, stride, iterations)
+			.ok_or_else(|| PyIndexError::new_err("a hierarchy needs particles that are all there and a stride of at least 2"))
+	}
+
+	// a line that never moves, for floors and ramps
+	fn add_segment(&mut self, a: (f32, f32), b: (f32, f32), thickness: f32) {
+		self.master.segments.push(StaticSegment::new(Vec2::from(a), Vec2::from(b), thickness));
+	}
+
+	// a box between two corners that removes any particle that isn't pinned once it's in, or
+	// with only below, everything further down than that
+	#[pyo3(signature = (a=None, b=None, below=None))]
+	fn add_kill_zone(&mut self, a: Option<(f32, f32)>, b: Option<(f32, f32)>, below: Option<f32>) -> PyResult<()> {
+		let zone = match (a, b, below) {
+			(Some(a), Some(b), None) => KillZone::new(Vec2::from(a), Vec2::from(b)),
+			(None, None, Some(y)) => KillZone::below(y),
+			_ => return Err(PyValueError::new_err("a kill zone needs either two corners or below")),
+		};
+		self.master.kill_zones.push(zone);
+		Ok(())
+	}
+
+	// delta is in the same units as the demo, 1.0 is one frame at 60 fps
+	#[pyo3(signature = (delta=1.0, steps=1))]
+	fn step(&mut self, delta: f32, steps: usize) {
+		for _ in 0..steps {
+			self.master.update(delta);
+		}
+	}
+
+	// row i is particle i, a removed one's row is NaN until its id's given out again
+	fn positions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+		rows(&self.master.objects, |object| object.position).into_pyarray(py)
+	}
+
+	// implied velocity per step, laid out like positions()
+	fn velocities<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+		rows(&self.master.objects, |object| object.position - object.last_position).into_pyarray(py)
+	}
+
+	fn set_position(&mut self, id: usize, x: f32, y: f32) -> PyResult<()> {
+		self.check_id(id)?;
+
+		// moves last_position along too, so the particle doesn't get flung
+		let object = &mut self.master.objects[id];
+		let velocity = object.position - object.last_position;
+		object.position = vec2(x, y);
+		object.last_position = object.position - velocity;
+
+		Ok(())
+	}
+
+	// defaults to the radius squared, only how it compares to other particles' matters
+	fn set_mass(&mut self, id: usize, mass: f32) -> PyResult<()> {
+		self.check_id(id)?;
+		self.master.objects[id].mass = mass;
+		Ok(())
+	}
+
+	// a pinned particle stays where it is, links and collisions only move the other end
+	fn set_pinned(&mut self, id: usize, pinned: bool) -> PyResult<()> {
+		self.check_id(id)?;
+
+		let object = &mut self.master.objects[id];
+		object.kinematic = pinned;
+		object.last_position = object.position;
+
+		Ok(())
+	}
+
+	// it's removed like remove_particle() once this many steps of delta 1.0 have gone by, and
+	// None keeps it around for good
+	fn set_lifetime(&mut self, id: usize, lifetime: Option<f32>) -> PyResult<()> {
+		self.check_id(id)?;
+		self.master.objects[id].lifetime = lifetime;
+		Ok(())
+	}
+
+	// makes it go off after fuse steps of delta 1.0, or with contact the first time it touches
+	// anything (both for whichever comes first), like explode() where it is. it's removed
+	// then, leaving debris particles behind, and breaks_links snaps every link within radius
+	#[pyo3(signature = (id, fuse=None, contact=false, strength=EXPLOSION_STRENGTH, radius=EXPLOSION_RADIUS, debris=0, breaks_links=false))]
+	#[allow(clippy::too_many_arguments)]
+	fn set_bomb(&mut self, id: usize, fuse: Option<f32>, contact: bool, strength: f32, radius: f32, debris: usize, breaks_links: bool) -> PyResult<()> {
+		self.check_id(id)?;
+		if fuse.is_none() && !contact {
+			return Err(PyValueError::new_err("a bomb needs a fuse or contact, or it never goes off"));
+		}
+		let mut bomb = Bomb::new(fuse, contact);
+		bomb.strength = strength;
+		bomb.radius = radius;
+		bomb.debris = debris;
+		bomb.breaks_links = breaks_links;
+		self.master.objects[id].bomb = Some(bomb);
+		Ok(())
+	}
+
+	// sets it off right away, False if it isn't a bomb
+	fn detonate(&mut self, id: usize) -> bool {
+		self.master.detonate(id)
+	}
+
+	// (id, (x, y)) for every bomb that went off in the last step
+	#[getter]
+	fn detonations(&self) -> Vec<(usize, (f32, f32))> {
+		self.master.detonations.iter().map(|detonation| (detonation.object.index, detonation.position.into())).collect()
+	}
+
+	// only games look at it, and flocks
+	fn set_tag(&mut self, id: usize, tag: u32) -> PyResult<()> {
+		self.check_id(id)?;
+		self.master.objects[id].tag = tag;
+		Ok(())
+	}
+
+	// every particle tagged tag flies around together like a flock of birds from now on
+	#[pyo3(signature = (tag, vision=FLOCK_VISION, separation=FLOCK_SEPARATION, alignment=FLOCK_ALIGNMENT, cohesion=FLOCK_COHESION, speed=FLOCK_SPEED))]
+	fn add_flock(&mut self, tag: u32, vision: f32, separation: f32, alignment: f32, cohesion: f32, speed: f32) {
+		self.master.flocks.push(Flock { tag, vision, separation, alignment, cohesion, speed });
+	}
+
+	// pole is "north" or "south", and None makes it not a magnet any more. only other magnets
+	// closer than reach feel it
+	#[pyo3(signature = (id, pole, strength=1000.0, reach=200.0))]
+	fn set_magnet(&mut self, id: usize, pole: Option<&str>, strength: f32, reach: f32) -> PyResult<()> {
+		self.check_id(id)?;
+		let pole = match pole {
+			Some("north") => Some(Pole::North),
+			Some("south") => Some(Pole::South),
+			Some(other) => return Err(PyValueError::new_err(format!("{:?} isn't north or south", other))),
+			None => None,
+		};
+		self.master.objects[id].magnet = pole.map(|pole| Magnet { pole, strength, reach });
+		Ok(())
+	}
+
+	// (speed, damping) pairs from slowest to fastest, up to 4, for how much of its speed the
+	// particle loses every substep instead of the usual damping. None takes it off again
+	fn set_damping_curve(&mut self, id: usize, points: Option<Vec<(f32, f32)>>) -> PyResult<()> {
+		self.check_id(id)?;
+		self.master.objects[id].damping_curve = match points {
+			Some(points) => {
+				let points: Vec<Vec2> = points.into_iter().map(Vec2::from).collect();
+				Some(DampingCurve::new(&points).ok_or_else(|| PyValueError::new_err("a damping curve needs 1 to 4 points from slowest to fastest"))?)
+			}
+			None => None,
+		};
+		Ok(())
+	}
+
+	// pulls every particle within reach towards x y from now on, by strength over the distance
+	// squared, and a negative strength pushes them away
+	#[pyo3(signature = (x, y, strength, reach=f32::INFINITY))]
+	fn add_attractor(&mut self, x: f32, y: f32, strength: f32, reach: f32) {
+		self.master.forces.push(Box::new(Attractor::new(vec2(x, y), strength, reach)));
+	}
+
+	// every particle pulling on every other like planets, or None to stop. softening and theta
+	// as in NBody
+	#[pyo3(signature = (strength=Some(N_BODY_STRENGTH), softening=N_BODY_SOFTENING, theta=N_BODY_THETA))]
+	fn set_n_body(&mut self, strength: Option<f32>, softening: f32, theta: f32) {
+		self.master.n_body = strength.map(|strength| NBody { strength, softening, theta });
+	}
+
+	// a grid of columns by rows squares cell_size across from x y, pushing nothing until
+	// set_field_cell says which way, replacing whatever field there was
+	fn set_vector_field(&mut self, x: f32, y: f32, cell_size: f32, columns: usize, rows: usize) {
+		self.master.vector_field = Some(VectorField::new(vec2(x, y), cell_size, columns, rows));
+	}
+
+	fn set_field_cell(&mut self, column: usize, row: usize, x: f32, y: f32) -> PyResult<()> {
+		let field = self.master.vector_field.as_mut().ok_or_else(|| PyValueError::new_err("there isn't a vector field"))?;
+		if column >= field.columns || row >= field.rows {
+			return Err(PyIndexError::new_err(format!("no cell at {column} {row}")));
+		}
+		field.cells[row * field.columns + column] = vec2(x, y);
+		Ok(())
+	}
+
+	#[pyo3(signature = (x, y, strength=EXPLOSION_STRENGTH, radius=EXPLOSION_RADIUS))]
+	fn explode(&mut self, x: f32, y: f32, strength: f32, radius: f32) {
+		self.master.explode(vec2(x, y), strength, radius);
+	}
+
+	#[getter]
+	fn gravity(&self) -> (f32, f32) {
+		self.master.gravity.into()
+	}
+
+	#[setter]
+	fn set_gravity(&mut self, gravity: (f32, f32)) {
+		self.master.gravity = Vec2::from(gravity);
+	}
+
+	#[getter]
+	fn wind(&self) -> (f32, f32) {
+		self.master.wind.into()
+	}
+
+	#[setter]
+	fn set_wind(&mut self, wind: (f32, f32)) {
+		self.master.wind = Vec2::from(wind);
+	}
+
+	// for every particle, from 0 to 1
+	#[getter]
+	fn friction(&self) -> f32 {
+		self.master.friction
+	}
+
+	#[setter]
+	fn set_friction(&mut self, friction: f32) {
+		self.master.friction = friction;
+	}
+
+	#[getter]
+	fn damping(&self) -> f32 {
+		self.master.damping
+	}
+
+	#[setter]
+	fn set_damping(&mut self, damping: f32) {
+		self.master.damping = damping;
+	}
+
+	// bit-identical results on any machine, at the cost of dragging and the watchdog
+	#[getter]
+	fn fixed_point(&self) -> bool {
+		self.master.fixed_point
+	}
+
+	#[setter]
+	fn set_fixed_point(&mut self, fixed_point: bool) {
+		self.master.fixed_point = fixed_point;
+	}
+
+	#[getter]
+	fn time(&self) -> f32 {
+		self.master.stats.time
+	}
+
+	// everything overlapping in the last step
+	fn contacts(&self) -> Vec<Contact> {
+		self.master.contacts()
+			.map(|contact| (contact.a, contact.b, contact.point.into(), contact.normal.into(), contact.depth))
+			.collect()
+	}
+
+	#[getter]
+	fn link_count(&self) -> usize {
+		self.master.chain_links.len()
+	}
+
+	// (a, b) for every link that's broken so far, oldest first
+	#[getter]
+	fn broken_links(&self) -> Vec<(usize, usize)> {
+		self.master.broken_links.iter().map(|chain_link| (chain_link.a, chain_link.b)).collect()
+	}
+
+	// puts broken_links[index] back, False if either end is gone
+	fn repair_link(&mut self, index: usize) -> bool {
+		self.master.repair_link(index).is_some()
+	}
+
+	// how many rows positions() has, removed particles included
+	fn __len__(&self) -> usize {
+		self.master.objects.slot_count()
+	}
+}
+
+// one (x, y) row per slot, so the row number is always the id
+fn rows(objects: &Pool<VerletObject>, value: impl Fn(&VerletObject) -> Vec2) -> Array2<f32> {
+	Array2::from_shape_fn((objects.slot_count(), 2), |(i, axis)| objects.get(i).map_or(f32::NAN, |object| value(object)[axis]))
+}
+
+#[pymodule]
+fn verlet(module: &Bound<'_, PyModule>) -> PyResult<()> {
+	module.add_class::<World>()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn removed_particles_leave_a_hole_in_the_positions() {
+		let mut world = World::new(Some(1));
+		let a = world.add_particle(100.0, 100.0, 10.0);
+		let b = world.add_particle(200.0, 100.0, 10.0);
+		// the same as remove_particle() without going through python's errors
+		world.master.remove_object(a);
+		world.step(1.0, 1);
+
+		let positions = rows(&world.master.objects, |object| object.position);
+		assert_eq!(positions.nrows(), world.__len__());
+		assert!(positions[(a, 0)].is_nan() && positions[(a, 1)].is_nan());
+		assert_eq!(positions[(b, 0)], world.master.objects[b].position.x);
+	}
+}
@@ -0,0 +1,146 @@
+use macroquad::prelude::*;
+use verlet_integration::{Master, ObjectId};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+	// straight lines from point to point, at a steady speed the whole way
+	Waypoints,
+	// cubic bezier segments sharing their ends: start, control, control, end, control, control, end...
+	Bezier,
+}
+
+impl Curve {
+	// what it's called in a scene file
+	pub fn name(&self) -> &'static str {
+		match self {
+			Curve::Waypoints => "waypoints",
+			Curve::Bezier => "bezier",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+	Linear,
+	EaseIn,
+	EaseOut,
+	EaseInOut,
+}
+
+impl Easing {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Easing::Linear => "linear",
+			Easing::EaseIn => "ease_in",
+			Easing::EaseOut => "ease_out",
+			Easing::EaseInOut => "ease_in_out",
+		}
+	}
+
+	pub fn apply(&self, t: f32) -> f32 {
+		match self {
+			Easing::Linear => t,
+			Easing::EaseIn => t * t,
+			Easing::EaseOut => t * (2.0 - t),
+			Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repeat {
+	// stops at the end
+	Once,
+	// jumps back to the start
+	Loop,
+	// goes back the way it came, then forward again
+	PingPong,
+}
+
+impl Repeat {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Repeat::Once => "once",
+			Repeat::Loop => "loop",
+			Repeat::PingPong => "ping_pong",
+		}
+	}
+
+	// how far through it is after t times through, 0 to 1
+	pub fn apply(&self, t: f32) -> f32 {
+		match self {
+			Repeat::Once => t.min(1.0),
+			Repeat::Loop => t.fract(),
+			Repeat::PingPong => 1.0 - (t % 2.0 - 1.0).abs(),
+		}
+	}
+}
+
+// moves a kinematic object along a path over and over (or once). it holds onto the object's
+// handle, so once that's gone it can't pick up whatever spawns into the same slot
+#[derive(Clone, Debug)]
+pub struct Animation {
+	pub id: ObjectId,
+	pub curve: Curve,
+	pub points: Vec<Vec2>,
+	// seconds to get from one end to the other
+	pub duration: f32,
+	pub easing: Easing,
+	pub repeat: Repeat,
+}
+
+impl Animation {
+	// whether there are enough points for the curve, bezier needs 3 more for every segment
+	pub fn is_valid(&self) -> bool {
+		match self.curve {
+			Curve::Waypoints => self.points.len() >= 2,
+			Curve::Bezier => self.points.len() >= 4 && (self.points.len() - 1).is_multiple_of(3),
+		}
+	}
+
+	// how far along the path it is this many seconds in, 0 to 1
+	pub fn progress(&self, seconds: f32) -> f32 {
+		let t = self.repeat.apply(seconds / self.duration.max(f32::EPSILON));
+		self.easing.apply(t.clamp(0.0, 1.0))
+	}
+
+	// the point that far along the path
+	pub fn point(&self, t: f32) -> Vec2 {
+		match self.curve {
+			Curve::Waypoints => {
+				let lengths: Vec<f32> = self.points.windows(2).map(|pair| pair[0].distance(pair[1])).collect();
+				let mut along = t * lengths.iter().sum::<f32>();
+				for (i, &length) in lengths.iter().enumerate() {
+					if along <= length {
+						return self.points[i].lerp(self.points[i + 1], along / length.max(f32::EPSILON));
+					}
+					along -= length;
+				}
+				self.points[self.points.len() - 1]
+			}
+			Curve::Bezier => {
+				let segments = (self.points.len() - 1) / 3;
+				let scaled = t * segments as f32;
+				let segment = (scaled as usize).min(segments - 1);
+				let t = scaled - segment as f32;
+				let [a, b, c, d] = [0, 1, 2, 3].map(|i| self.points[segment * 3 + i]);
+				let u = 1.0 - t;
+				u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+			}
+		}
+	}
+
+	pub fn position(&self, seconds: f32) -> Vec2 {
+		self.point(self.progress(seconds))
+	}
+
+	// puts the object where it should be by now, with last_position where it was so everything
+	// it runs into sees it moving
+	pub fn apply(&self, master: &mut Master, seconds: f32) {
+		let position = self.position(seconds);
+		if let Some(object) = master.object_mut(self.id) {
+			object.last_position = object.position;
+			object.position = position;
+		}
+	}
+}
@@ -0,0 +1,264 @@
+use std::fmt::Write;
+
+use macroquad::prelude::*;
+use verlet_integration::Master;
+
+use crate::animation::Repeat;
+use crate::input::{Action, InputMap};
+
+// what an automation can turn. angles are degrees clockwise from pointing right, like a
+// cannon's, so gravity straight down is 90. the strengths keep the direction it was going (and
+// below 0 go the other way) and the angles keep the strength
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parameter {
+	Gravity,
+	GravityAngle,
+	Wind,
+	WindAngle,
+	TimeScale,
+	Damping,
+	Friction,
+}
+
+impl Parameter {
+	pub const ALL: [Parameter; 7] = [
+		Parameter::Gravity,
+		Parameter::GravityAngle,
+		Parameter::Wind,
+		Parameter::WindAngle,
+		Parameter::TimeScale,
+		Parameter::Damping,
+		Parameter::Friction,
+	];
+
+	// what it's called in a scene file
+	pub fn name(&self) -> &'static str {
+		match self {
+			Parameter::Gravity => "gravity",
+			Parameter::GravityAngle => "gravity_angle",
+			Parameter::Wind => "wind",
+			Parameter::WindAngle => "wind_angle",
+			Parameter::TimeScale => "time_scale",
+			Parameter::Damping => "damping",
+			Parameter::Friction => "friction",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|parameter| parameter.name() == name)
+	}
+
+	// what the panel starts one off with: a slow wobble either side of where it usually is
+	pub fn default_shape(&self) -> Shape {
+		let (center, amplitude) = match self {
+			Parameter::Gravity => (1.0, 0.5),
+			Parameter::GravityAngle => (90.0, 30.0),
+			Parameter::Wind => (0.0, 0.3),
+			Parameter::WindAngle => (0.0, 180.0),
+			Parameter::TimeScale => (0.75, 0.25),
+			Parameter::Damping => (0.01, 0.01),
+			Parameter::Friction => (0.25, 0.25),
+		};
+		Shape::Sine { center, amplitude, period: 8.0, phase: 0.0 }
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+	// up and down either side of the center every period seconds, phase is how far through
+	// one (0 to 1) it starts
+	Sine { center: f32, amplitude: f32, period: f32, phase: f32 },
+	// from one to the other over the duration in seconds, repeating the way a path does
+	Ramp { from: f32, to: f32, duration: f32, repeat: Repeat },
+	// each value held for so many seconds, then back to the first
+	Steps { step: f32, values: Vec<f32> },
+}
+
+impl Shape {
+	pub fn value(&self, seconds: f32) -> f32 {
+		match self {
+			Shape::Sine { center, amplitude, period, phase } => {
+				let turns = seconds / period.max(f32::EPSILON) + phase;
+				center + amplitude * (turns * std::f32::consts::TAU).sin()
+			}
+			Shape::Ramp { from, to, duration, repeat } => {
+				let t = repeat.apply(seconds / duration.max(f32::EPSILON));
+				from + (to - from) * t.clamp(0.0, 1.0)
+			}
+			Shape::Steps { step, values } => {
+				let index = (seconds / step.max(f32::EPSILON)) as usize % values.len().max(1);
+				values.get(index).copied().unwrap_or(0.0)
+			}
+		}
+	}
+
+	// twice as slow or fast, whichever shape it is
+	pub fn stretch(&mut self, factor: f32) {
+		match self {
+			Shape::Sine { period, .. } => *period *= factor,
+			Shape::Ramp { duration, .. } => *duration *= factor,
+			Shape::Steps { step, .. } => *step *= factor,
+		}
+	}
+
+	// everything after the parameter's name in a scene file
+	pub fn to_text(&self) -> String {
+		match self {
+			Shape::Sine { center, amplitude, period, phase } => {
+				let mut text = format!("sine {} {} {}", center, amplitude, period);
+				if *phase != 0.0 {
+					let _ = write!(text, " phase {}", phase);
+				}
+				text
+			}
+			Shape::Ramp { from, to, duration, repeat } => format!("ramp {} {} {} {}", from, to, duration, repeat.name()),
+			Shape::Steps { step, values } => {
+				let mut text = format!("steps {}", step);
+				for value in values.iter() {
+					let _ = write!(text, " {}", value);
+				}
+				text
+			}
+		}
+	}
+}
+
+// a world setting driven by the scene's clock, so a recording or a replay sees it the same
+// way every time. one switched off leaves the setting wherever it got to
+#[derive(Clone, Debug, PartialEq)]
+pub struct Automation {
+	pub parameter: Parameter,
+	pub shape: Shape,
+	pub enabled: bool,
+	// what it last set gravity or the wind to, with the direction and strength that came
+	// from. while nothing else has changed it since, those carry on as they were instead of
+	// being worked out from it again, so a strength can go through 0 and below without
+	// losing which way it was pointing, and an angle going round doesn't wear the strength
+	// down a rounding error at a time
+	kept: Option<(Vec2, Vec2, f32)>,
+}
+
+impl Automation {
+	pub fn new(parameter: Parameter, shape: Shape) -> Self {
+		Self { parameter, shape, enabled: true, kept: None }
+	}
+
+	// time scale isn't in the master, it's only passed back for the window to slow itself
+	// down by
+	pub fn apply(&mut self, master: &mut Master, seconds: f32, time_scale: &mut Option<f32>) {
+		if !self.enabled {
+			return;
+		}
+		let value = self.shape.value(seconds);
+		let (vector, default) = match self.parameter {
+			Parameter::Gravity | Parameter::GravityAngle => (&mut master.gravity, Vec2::Y),
+			Parameter::Wind | Parameter::WindAngle => (&mut master.wind, Vec2::X),
+			Parameter::TimeScale => {
+				*time_scale = Some(value.max(0.0));
+				return;
+			}
+			Parameter::Damping => {
+				master.damping = value.clamp(0.0, 1.0);
+				return;
+			}
+			Parameter::Friction => {
+				master.friction = value.clamp(0.0, 1.0);
+				return;
+			}
+		};
+
+		let (mut direction, mut strength) = match self.kept {
+			Some((written, direction, strength)) if written == *vector => (direction, strength),
+			_ => (vector.try_normalize().unwrap_or(default), vector.length()),
+		};
+		match self.parameter {
+			Parameter::GravityAngle | Parameter::WindAngle => direction = Vec2::from_angle(value.to_radians()),
+			_ => strength = value,
+		}
+		*vector = direction * strength;
+		self.kept = Some((*vector, direction, strength));
+	}
+
+	// for after "automate"
+	pub fn to_text(&self) -> String {
+		format!("{} {}{}", self.parameter.name(), self.shape.to_text(), if self.enabled { "" } else { " off" })
+	}
+}
+
+// a row for every parameter, to put automations on the running scene without a scene file.
+// up and down pick one, enter adds a wobble to it or switches the one it has off and on,
+// left and right make it slower or faster, backspace takes it off and escape closes the list
+#[derive(Clone, Debug, Default)]
+pub struct AutomationPanel {
+	pub open: bool,
+	selected: usize,
+}
+
+impl AutomationPanel {
+	pub fn update(&mut self, input: &mut InputMap, automations: &mut Vec<Automation>) {
+		if !self.open {
+			if input.pressed(Action::Automations) {
+				self.open = true;
+			}
+			input.suspended |= self.open;
+			return;
+		}
+
+		let count = Parameter::ALL.len();
+		let parameter = Parameter::ALL[self.selected];
+		let existing = automations.iter().position(|automation| automation.parameter == parameter);
+		if is_key_pressed(KeyCode::Escape) || input.binding(Action::Automations).is_pressed() {
+			self.open = false;
+		} else if is_key_pressed(KeyCode::Up) {
+			self.selected = (self.selected + count - 1) % count;
+		} else if is_key_pressed(KeyCode::Down) {
+			self.selected = (self.selected + 1) % count;
+		} else if is_key_pressed(KeyCode::Enter) {
+			match existing {
+				Some(index) => automations[index].enabled = !automations[index].enabled,
+				None => automations.push(Automation::new(parameter, parameter.default_shape())),
+			}
+		} else if let Some(index) = existing {
+			if is_key_pressed(KeyCode::Left) {
+				automations[index].shape.stretch(2.0);
+			} else if is_key_pressed(KeyCode::Right) {
+				automations[index].shape.stretch(0.5);
+			} else if is_key_pressed(KeyCode::Backspace) {
+				automations.remove(index);
+			}
+		}
+
+		input.suspended |= self.open;
+	}
+
+	// across the top like the link groups, with what each one's up to right now
+	pub fn draw(&self, master: &Master, automations: &[Automation]) {
+		if !self.open {
+			return;
+		}
+
+		let seconds = master.stats.time / 60.0;
+		draw_rectangle(0.0, 0.0, screen_width(), 50.0 + Parameter::ALL.len() as f32 * 34.0, Color::new(0.0, 0.0, 0.0, 0.8));
+		draw_text("AUTOMATIONS (ENTER ADDS OR SWITCHES, LEFT/RIGHT SLOWER/FASTER, BACKSPACE REMOVES)", 20.0, 30.0, 24.0, WHITE);
+
+		for (i, parameter) in Parameter::ALL.iter().enumerate() {
+			let automation = automations.iter().find(|automation| automation.parameter == *parameter);
+			let color = match (i == self.selected, automation.is_some_and(|automation| automation.enabled)) {
+				(true, _) => YELLOW,
+				(false, true) => WHITE,
+				(false, false) => GRAY,
+			};
+			let text = match automation {
+				Some(automation) => format!(
+					"{}  {}  {}  {:.2}",
+					parameter.name(),
+					automation.shape.to_text(),
+					if automation.enabled { "ON" } else { "OFF" },
+					automation.shape.value(seconds),
+				),
+				None => format!("{}  -", parameter.name()),
+			};
+			draw_text(&text, 20.0, 70.0 + i as f32 * 34.0, 28.0, color);
+		}
+	}
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+use macroquad::prelude::*;
+use verlet_integration::rand::Rng;
+use verlet_integration::{Constraint, Master, VerletObject, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::scene;
+
+pub const BENCH_STEPS: usize = 1000;
+pub const BENCH_OBJECTS: usize = 1000;
+// the gap between objects when they're dropped in, which shrinks when there are too many to
+// fit in the window that far apart
+pub const BENCH_SPACING: f32 = 12.0;
+
+// the window as a box, full of objects in a grid nudged a little out of line so they don't
+// stack up in perfect columns, the same every run
+pub fn bench_scene(objects: usize) -> Master {
+	let mut master = Master::new(Vec::new(), Vec::new());
+	master.constraint = Constraint::Aabb { min: Vec2::ZERO, max: vec2(WINDOW_WIDTH, WINDOW_HEIGHT) };
+
+	let spacing = BENCH_SPACING.min((WINDOW_WIDTH * WINDOW_HEIGHT / objects.max(1) as f32).sqrt());
+	let columns = ((WINDOW_WIDTH / spacing) as usize).max(1);
+	let mut rng = Rng::new(1);
+	for i in 0..objects {
+		let jitter = vec2(rng.range(-0.1, 0.1), rng.range(-0.1, 0.1)) * spacing;
+		let position = vec2((i % columns) as f32 + 0.5, (i / columns) as f32 + 0.5) * spacing + jitter;
+		master.objects.push(VerletObject::new(position, spacing * 0.45));
+	}
+
+	master
+}
+
+// --bench [steps] [objects] steps the solver as fast as it'll go with no window and nothing
+// drawn, then says how many steps a second that was and where the time went. with --scene it
+// runs that scene instead, with the objects (if there's a count) dropped in on top
+pub fn run(args: &[String]) -> Result<(), String> {
+	let index = args.iter().position(|arg| arg == "--bench").unwrap_or(0);
+	let number = |offset: usize| args.get(index + offset).and_then(|arg| arg.parse::<usize>().ok());
+	let steps = number(1).unwrap_or(BENCH_STEPS);
+	let objects = number(2);
+
+	let mut master = match args.iter().position(|arg| arg == "--scene").and_then(|index| args.get(index + 1)) {
+		Some(path) => {
+			let (mut master, _) = scene::open(path).map_err(|error| format!("couldn't load the scene {}: {}", path, error))?;
+			if let Some(objects) = objects {
+				master.objects.extend(bench_scene(objects).objects.iter().cloned());
+			}
+			master
+		}
+		None => bench_scene(objects.unwrap_or(BENCH_OBJECTS)),
+	};
+	// the same amount of work every step, so the numbers only change when the solver does
+	master.watchdog.enabled = false;
+	master.frame_budget = None;
+	master.profile = true;
+
+	println!("{} steps with {} objects and {} links", steps, master.objects.len(), master.chain_links.len());
+	let start = Instant::now();
+	for _ in 0..steps {
+		master.update(1.0);
+	}
+	let elapsed = start.elapsed();
+
+	let phases = master.stats.phases;
+	println!("{:.1} steps a second, {:.3} ms a step", steps as f64 / elapsed.as_secs_f64(), milliseconds(elapsed) / steps.max(1) as f64);
+	for (name, time) in [
+		("gravity", phases.gravity),
+		("constraint", phases.constraint),
+		("collisions", phases.collisions),
+		("links", phases.links),
+		("positions", phases.positions),
+		("rest", phases.rest),
+	] {
+		println!(
+			"{:>12} {:8.3} ms a step {:5.1}%",
+			name,
+			milliseconds(time) / steps.max(1) as f64,
+			100.0 * time.as_secs_f64() / phases.total().as_secs_f64().max(f64::EPSILON),
+		);
+	}
+	Ok(())
+}
+
+fn milliseconds(time: Duration) -> f64 {
+	time.as_secs_f64() * 1000.0
+}
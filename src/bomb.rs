@@ -0,0 +1,53 @@
+use glam::Vec2;
+
+use crate::object::ObjectId;
+use crate::{EXPLOSION_RADIUS, EXPLOSION_STRENGTH};
+
+// makes an object go off like Master::explode() where it is, once its fuse runs out or (with
+// on_contact) the first time it touches anything. it's gone afterwards, leaving debris
+// objects flying out of where it was and a Detonation in Master::detonations
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bomb {
+	// frames left, counting down by the delta every update. None waits for a hit
+	pub fuse: Option<f32>,
+	pub on_contact: bool,
+	pub strength: f32,
+	pub radius: f32,
+	// every link with an end within the radius snaps too, into Master::broken_links
+	pub breaks_links: bool,
+	// how many bits it flies apart into
+	pub debris: usize,
+}
+
+impl Bomb {
+	// with as big a bang as Master::explode() gets from the demo, and nothing else
+	pub fn new(fuse: Option<f32>, on_contact: bool) -> Self {
+		Self {
+			fuse,
+			on_contact,
+			strength: EXPLOSION_STRENGTH,
+			radius: EXPLOSION_RADIUS,
+			breaks_links: false,
+			debris: 0,
+		}
+	}
+
+	// whether the fuse has burned down, hits are up to the master
+	pub fn is_burned_down(&self) -> bool {
+		self.fuse.is_some_and(|fuse| fuse <= 0.0)
+	}
+}
+
+// a bomb going off during the last update. the object's already gone by the time anyone sees
+// it, so this is all that's left of it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Detonation {
+	// the bomb as it was, its slot can already have some of the debris in it
+	pub object: ObjectId,
+	pub position: Vec2,
+	pub strength: f32,
+	pub radius: f32,
+	// how many links it snapped
+	pub links: usize,
+}
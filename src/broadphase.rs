@@ -0,0 +1,216 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use glam::Vec2;
+
+// the end of a bucket's list
+const NONE: usize = usize::MAX;
+
+// a uniform grid hashed into a fixed number of buckets, so it covers any area without
+// knowing the bounds up front. each entry sits in the bucket for the cell its center is in,
+// and moving one only touches the table when it crosses into another cell, so a scene that's
+// mostly sitting still costs next to nothing to keep up to date
+#[derive(Clone, Debug)]
+pub struct SpatialHash {
+	pub cell_size: f32,
+	// each bucket is a list threaded through links, this is the first id in it
+	buckets: Vec<usize>,
+	// the ids before and after each one in its bucket, by id. moving between buckets only
+	// rewires these, so it never needs more room than there are ids
+	links: Vec<(usize, usize)>,
+	// the cell each id is in right now, by id
+	cells: Vec<Option<(i32, i32)>>,
+	// the radius each id had when it went in, by id
+	radii: Vec<f32>,
+	// how far past its own cell anything could reach, so queries know how far to look. it
+	// goes stale (too big, which only makes queries look further) when the biggest one leaves
+	// or shrinks, and largest_radius() works it out again
+	largest_radius: f32,
+	stale: bool,
+	visited: Vec<usize>,
+}
+
+impl SpatialHash {
+	pub fn new(cell_size: f32, bucket_count: usize) -> Self {
+		Self {
+			cell_size,
+			buckets: vec![NONE; bucket_count.max(1)],
+			links: vec![],
+			cells: vec![],
+			radii: vec![],
+			largest_radius: 0.0,
+			stale: false,
+			visited: vec![],
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.cells.iter().filter(|cell| cell.is_some()).count()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cells.iter().all(|cell| cell.is_none())
+	}
+
+	pub fn contains(&self, id: usize) -> bool {
+		self.cells.get(id).is_some_and(|cell| cell.is_some())
+	}
+
+	// every id in it with the cell it's in
+	pub fn entries(&self) -> impl Iterator<Item = (usize, (i32, i32))> + '_ {
+		self.cells.iter().enumerate().filter_map(|(id, cell)| Some((id, (*cell)?)))
+	}
+
+	// makes room for ids up to this many, after that nothing it does allocates
+	pub fn reserve(&mut self, ids: usize) {
+		self.cells.reserve(ids.saturating_sub(self.cells.len()));
+		self.links.reserve(ids.saturating_sub(self.links.len()));
+		self.radii.reserve(ids.saturating_sub(self.radii.len()));
+		self.visited.reserve(self.buckets.len().saturating_sub(self.visited.len()));
+	}
+
+	pub fn cell(&self, position: Vec2) -> (i32, i32) {
+		let cell = (position / self.cell_size).floor();
+		(cell.x as i32, cell.y as i32)
+	}
+
+	fn bucket(&self, cell: (i32, i32)) -> usize {
+		// the usual pair of large primes, xor'd together
+		let hash = (cell.0 as u32).wrapping_mul(73856093) ^ (cell.1 as u32).wrapping_mul(19349663);
+		hash as usize % self.buckets.len()
+	}
+
+	fn link(&mut self, id: usize, bucket: usize) {
+		let first = self.buckets[bucket];
+		if first != NONE {
+			self.links[first].0 = id;
+		}
+		self.links[id] = (NONE, first);
+		self.buckets[bucket] = id;
+	}
+
+	fn unlink(&mut self, id: usize, bucket: usize) {
+		let (before, after) = self.links[id];
+		match before {
+			NONE => self.buckets[bucket] = after,
+			before => self.links[before].1 = after,
+		}
+		if after != NONE {
+			self.links[after].0 = before;
+		}
+	}
+
+	// adds the entry, or moves it if it's already in
+	pub fn insert(&mut self, id: usize, position: Vec2, radius: f32) {
+		self.update(id, position, radius);
+	}
+
+	pub fn remove(&mut self, id: usize) {
+		if let Some(cell) = self.cells.get_mut(id).and_then(Option::take) {
+			let bucket = self.bucket(cell);
+			self.unlink(id, bucket);
+			self.stale |= self.radii[id] >= self.largest_radius;
+		}
+	}
+
+	// the biggest radius of anything in it, only going through them all again when the
+	// biggest one has gone
+	pub fn largest_radius(&mut self) -> f32 {
+		if self.stale {
+			self.largest_radius = self.cells.iter().zip(self.radii.iter())
+				.filter(|(cell, _)| cell.is_some())
+				.map(|(_, &radius)| radius)
+				.fold(0.0, f32::max);
+			self.stale = false;
+		}
+		self.largest_radius
+	}
+
+	// whether it's already in the cell for this position with this radius, so update()
+	// would have nothing to do
+	pub fn is_current(&self, id: usize, position: Vec2, radius: f32) -> bool {
+		self.cells.get(id).is_some_and(|&cell| cell == Some(self.cell(position))) && self.radii[id] == radius
+	}
+
+	// returns whether the entry had to change cells (or wasn't in yet)
+	pub fn update(&mut self, id: usize, position: Vec2, radius: f32) -> bool {
+		let cell = self.cell(position);
+		if id >= self.cells.len() {
+			self.cells.resize(id + 1, None);
+			self.links.resize(id + 1, (NONE, NONE));
+			self.radii.resize(id + 1, 0.0);
+		}
+
+		if self.cells[id].is_some() && radius < self.radii[id] {
+			self.stale |= self.radii[id] >= self.largest_radius;
+		}
+		self.radii[id] = radius;
+		self.largest_radius = self.largest_radius.max(radius);
+
+		match self.cells[id] {
+			Some(old_cell) if old_cell == cell => false,
+			old_cell => {
+				if let Some(old_cell) = old_cell {
+					let bucket = self.bucket(old_cell);
+					self.unlink(id, bucket);
+				}
+
+				let bucket = self.bucket(cell);
+				self.link(id, bucket);
+				self.cells[id] = Some(cell);
+				true
+			}
+		}
+	}
+
+	// empties it and starts over with cells this big, everything has to go back in after
+	pub fn resize(&mut self, cell_size: f32) {
+		self.clear();
+		self.cell_size = cell_size;
+	}
+
+	pub fn clear(&mut self) {
+		self.buckets.fill(NONE);
+		self.links.clear();
+		self.cells.clear();
+		self.radii.clear();
+		self.largest_radius = 0.0;
+		self.stale = false;
+	}
+
+	// everything that might touch a circle here, in id order. it can hand back a few that
+	// are too far away (neighbouring cells, or other cells that hash to the same bucket), so
+	// the caller still has to check the distance
+	pub fn query(&mut self, position: Vec2, radius: f32, result: &mut Vec<usize>) {
+		result.clear();
+
+		let reach = radius + self.largest_radius;
+		let min = self.cell(position - reach);
+		let max = self.cell(position + reach);
+
+		// different cells can land in the same bucket, only go through each bucket once. with
+		// more cells than buckets that's just all of them
+		self.visited.clear();
+		let cells = (max.0 as i64 - min.0 as i64 + 1) * (max.1 as i64 - min.1 as i64 + 1);
+		if cells >= self.buckets.len() as i64 {
+			self.visited.extend(0..self.buckets.len());
+		} else {
+			for y in min.1..=max.1 {
+				for x in min.0..=max.0 {
+					self.visited.push(self.bucket((x, y)));
+				}
+			}
+			self.visited.sort_unstable();
+			self.visited.dedup();
+		}
+
+		for &bucket in self.visited.iter() {
+			let mut id = self.buckets[bucket];
+			while id != NONE {
+				result.push(id);
+				id = self.links[id].1;
+			}
+		}
+		result.sort_unstable();
+	}
+}
@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+
+use verlet_integration::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+// how much one notch of the mouse wheel zooms in or out, and how far it can go
+pub const ZOOM_STEP: f32 = 1.1;
+pub const MIN_ZOOM: f32 = 0.1;
+pub const MAX_ZOOM: f32 = 20.0;
+
+// where the world is looked at from. the world is in pixels with the origin in the top left,
+// so with no pan or zoom it lines up with the screen exactly
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldCamera {
+	// the world point in the middle of the screen
+	pub center: Vec2,
+	// screen pixels per world unit
+	pub zoom: f32,
+	// the mouse while panning, to see how far it's moved each frame
+	pan_from: Option<Vec2>,
+}
+
+impl Default for WorldCamera {
+	fn default() -> Self {
+		Self {
+			center: vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5),
+			zoom: 1.0,
+			pan_from: None,
+		}
+	}
+}
+
+impl WorldCamera {
+	// drags the world along with the mouse while panning, and zooms in or out around the mouse
+	// with the wheel so whatever's under it stays put
+	pub fn update(&mut self, mouse: Vec2, panning: bool, wheel: f32) {
+		match (panning, self.pan_from) {
+			(true, Some(from)) => self.center -= (mouse - from) / self.zoom,
+			(false, _) => self.pan_from = None,
+			_ => {}
+		}
+		if panning {
+			self.pan_from = Some(mouse);
+		}
+
+		if wheel != 0.0 {
+			let under_mouse = self.screen_to_world(mouse);
+			self.zoom = (self.zoom * ZOOM_STEP.powf(wheel.signum())).clamp(MIN_ZOOM, MAX_ZOOM);
+			self.center = under_mouse - (mouse - screen_center()) / self.zoom;
+		}
+	}
+
+	pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
+		self.center + (point - screen_center()) / self.zoom
+	}
+
+	pub fn world_to_screen(&self, point: Vec2) -> Vec2 {
+		screen_center() + (point - self.center) * self.zoom
+	}
+
+	// the part of the world that's on the screen
+	pub fn visible(&self) -> Rect {
+		let size = vec2(screen_width(), screen_height()) / self.zoom;
+		Rect::new(self.center.x - size.x * 0.5, self.center.y - size.y * 0.5, size.x, size.y)
+	}
+
+	// for drawing the world straight to the screen, moved over by the shake
+	pub fn camera(&self, shake: Vec2) -> Camera2D {
+		let visible = self.visible();
+		Camera2D::from_display_rect(Rect::new(visible.x + shake.x, visible.y + shake.y, visible.w, visible.h))
+	}
+}
+
+fn screen_center() -> Vec2 {
+	vec2(screen_width(), screen_height()) * 0.5
+}
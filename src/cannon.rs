@@ -0,0 +1,206 @@
+use macroquad::prelude::*;
+
+use verlet_integration::{Master, ObjectId, VerletObject};
+
+use crate::input::{Action, InputMap};
+use crate::Command;
+
+pub const CANNON_LENGTH: f32 = 30.0;
+pub const CANNON_WIDTH: f32 = 12.0;
+pub const CANNON_RADIUS: f32 = 10.0;
+// pixels a frame, and how much faster or slower it gets each frame a power key is held
+pub const CANNON_POWER: f32 = 5.0;
+pub const CANNON_MAX_POWER: f32 = 12.0;
+pub const CANNON_POWER_STEP: f32 = 0.05;
+// degrees a frame an aim key turns it
+pub const CANNON_AIM_STEP: f32 = 1.0;
+// how fast a shot goes for every pixel the mouse is dragged out from one being put down
+pub const CANNON_DRAG_POWER: f32 = 0.04;
+// how far ahead the preview looks, in frames, and how many frames apart its dots are
+pub const CANNON_PREVIEW_FRAMES: usize = 120;
+pub const CANNON_PREVIEW_EVERY: usize = 3;
+
+const CANNON_COLOR: Color = Color::new(0.3, 0.3, 0.35, 1.0);
+
+// somewhere that fires a shot the same way every time, for knocking into whatever's been
+// built in front of it. it isn't in the simulation itself, nothing hits it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cannon {
+	pub position: Vec2,
+	// radians clockwise from pointing right, so straight up is -90 degrees
+	pub angle: f32,
+	// how fast the shot leaves the end of the barrel, in pixels a frame
+	pub power: f32,
+	pub radius: f32,
+	// degrees a second clockwise, see fire()
+	pub spin: f32,
+}
+
+impl Cannon {
+	// pointing up and to the right
+	pub fn new(position: Vec2) -> Self {
+		Self {
+			position,
+			angle: -45.0_f32.to_radians(),
+			power: CANNON_POWER,
+			radius: CANNON_RADIUS,
+			spin: 0.0,
+		}
+	}
+
+	pub fn direction(&self) -> Vec2 {
+		Vec2::from_angle(self.angle)
+	}
+
+	pub fn muzzle(&self) -> Vec2 {
+		self.position + self.direction() * CANNON_LENGTH
+	}
+
+	pub fn command(&self) -> Command {
+		Command::Fire {
+			position: self.muzzle(),
+			velocity: self.direction() * self.power,
+			radius: self.radius,
+			spin: self.spin,
+		}
+	}
+
+	// pointing at the point, harder the further off it is
+	pub fn aim_at(&mut self, point: Vec2) {
+		let offset = point - self.position;
+		if offset == Vec2::ZERO {
+			return;
+		}
+		self.angle = offset.y.atan2(offset.x);
+		self.power = (offset.length() * CANNON_DRAG_POWER).clamp(1.0, CANNON_MAX_POWER);
+	}
+
+	// towards straight up from either side, or away from it
+	pub fn raise(&mut self, degrees: f32) {
+		let side = if self.direction().x >= 0.0 { -1.0 } else { 1.0 };
+		self.angle += side * degrees.to_radians();
+	}
+
+	// where the shot would go if it were fired now, worked out on a copy of the whole scene
+	// like a launch's
+	pub fn trajectory(&self, master: &Master) -> Vec<Vec2> {
+		let Command::Fire { position, velocity, radius, spin } = self.command() else {
+			return vec![];
+		};
+		let mut preview = master.clone();
+		let id = fire(&mut preview, position, velocity, radius, spin);
+
+		let mut points = vec![];
+		for frame in 1..=CANNON_PREVIEW_FRAMES {
+			preview.update(1.0);
+			let Some(object) = preview.object(id) else {
+				break;
+			};
+			if frame % CANNON_PREVIEW_EVERY == 0 {
+				points.push(object.position);
+			}
+		}
+		points
+	}
+
+	pub fn draw(&self, selected: bool) {
+		let (base, muzzle) = (self.position, self.muzzle());
+		draw_line(base.x, base.y, muzzle.x, muzzle.y, CANNON_WIDTH, CANNON_COLOR);
+		draw_circle(base.x, base.y, CANNON_WIDTH, CANNON_COLOR);
+		if selected {
+			draw_circle_lines(base.x, base.y, CANNON_WIDTH + 3.0, 2.0, YELLOW);
+			draw_text(&format!("{:.0} deg  {:.1}", -self.angle.to_degrees(), self.power), base.x - 30.0, base.y + 32.0, 20.0, WHITE);
+		}
+	}
+}
+
+// a ball, or with some spin two half as big linked side by side and turning about the middle
+// (one object on its own has nothing to turn). returns the first one
+pub fn fire(master: &mut Master, position: Vec2, velocity: Vec2, radius: f32, spin: f32) -> ObjectId {
+	let color = master.random_color();
+	if spin == 0.0 {
+		let mut object = VerletObject::new(position, radius).with_color(color);
+		object.last_position = position - velocity;
+		return master.spawn(object);
+	}
+
+	let half = radius * 0.5;
+	let across = match velocity.try_normalize() {
+		Some(direction) => direction.perp() * half,
+		None => vec2(half, 0.0),
+	};
+	// degrees a second to radians a frame
+	let turn = spin.to_radians() / 60.0;
+	let ids = [1.0, -1.0].map(|side| {
+		let offset = across * side;
+		let mut object = VerletObject::new(position + offset, half).with_color(color);
+		object.last_position = object.position - velocity - offset.perp() * turn;
+		master.spawn(object)
+	});
+	master.link(ids[0], ids[1]);
+	ids[0]
+}
+
+// the aim keys turn and power up whichever cannon's picked, fire sends a shot out of it and
+// fire with alternate picks the next one. the newest one gets picked when one's added
+#[derive(Clone, Debug, Default)]
+pub struct CannonControls {
+	pub selected: usize,
+	count: usize,
+	// for the cannon as it was when it was worked out, it's only worked out again once that's
+	// changed or a shot's gone
+	preview: Vec<Vec2>,
+	previewed: Option<Cannon>,
+}
+
+impl CannonControls {
+	pub fn update(&mut self, input: &InputMap, cannons: &mut [Cannon], commands: &mut Vec<Command>) {
+		if cannons.len() > self.count {
+			self.selected = cannons.len() - 1;
+		}
+		self.count = cannons.len();
+		let Some(cannon) = cannons.get_mut(self.selected) else {
+			self.selected = 0;
+			return;
+		};
+
+		if input.down(Action::RaiseAim) {
+			cannon.raise(CANNON_AIM_STEP);
+		}
+		if input.down(Action::LowerAim) {
+			cannon.raise(-CANNON_AIM_STEP);
+		}
+		if input.down(Action::MorePower) {
+			cannon.power = (cannon.power + CANNON_POWER_STEP).min(CANNON_MAX_POWER);
+		}
+		if input.down(Action::LessPower) {
+			cannon.power = (cannon.power - CANNON_POWER_STEP).max(1.0);
+		}
+		if input.pressed(Action::Fire) {
+			if input.down(Action::Alternate) {
+				self.selected = (self.selected + 1) % cannons.len();
+			} else {
+				commands.push(cannon.command());
+				self.previewed = None;
+			}
+		}
+	}
+
+	pub fn draw(&mut self, master: &Master, cannons: &[Cannon]) {
+		for (i, cannon) in cannons.iter().enumerate() {
+			cannon.draw(i == self.selected);
+		}
+
+		let Some(&cannon) = cannons.get(self.selected) else {
+			return;
+		};
+		if self.previewed != Some(cannon) {
+			self.preview = cannon.trajectory(master);
+			self.previewed = Some(cannon);
+		}
+		for (i, point) in self.preview.iter().enumerate() {
+			let alpha = 1.0 - i as f32 / self.preview.len() as f32;
+			draw_circle(point.x, point.y, 3.0, Color::new(1.0, 0.9, 0.4, alpha * 0.8));
+		}
+	}
+}
@@ -0,0 +1,177 @@
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+
+use verlet_integration::rand::Rng;
+use verlet_integration::{Constraint, Master, CONSTRAINT_RADIUS, EXPLOSION_STRENGTH, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::Command;
+
+// seconds between events unless --chaos-interval says otherwise
+pub const CHAOS_INTERVAL: f32 = 3.0;
+// spawn bursts stop once there's this many objects, so it can run for hours without grinding
+// to a halt
+pub const CHAOS_MAX_OBJECTS: usize = 600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChaosEvent {
+	GravityFlip,
+	Explode,
+	SpawnBurst,
+	BreakLink,
+}
+
+impl ChaosEvent {
+	pub const ALL: [ChaosEvent; 4] = [
+		ChaosEvent::GravityFlip,
+		ChaosEvent::Explode,
+		ChaosEvent::SpawnBurst,
+		ChaosEvent::BreakLink,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			ChaosEvent::GravityFlip => "gravity_flip",
+			ChaosEvent::Explode => "explode",
+			ChaosEvent::SpawnBurst => "spawn_burst",
+			ChaosEvent::BreakLink => "break_link",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<ChaosEvent> {
+		ChaosEvent::ALL.into_iter().find(|event| event.name() == name)
+	}
+}
+
+// every so often picks an event out of the pool and does it to the world. it has its own
+// generator, so the same seed always gives the same events however much else is random
+#[derive(Clone, Debug)]
+pub struct Chaos {
+	pub enabled: bool,
+	pub seed: u64,
+	// picked from evenly, so an event in here twice comes up twice as often
+	pub pool: Vec<ChaosEvent>,
+	pub interval: f32,
+	pub timer: f32,
+	pub events: usize,
+	pub last: Option<ChaosEvent>,
+	rng: Rng,
+	// the master's time at the last update, it goes by simulated time so a run with the same
+	// seed gets the same events at the same moments
+	last_time: Option<f32>,
+}
+
+impl Chaos {
+	pub fn new(seed: u64, pool: Vec<ChaosEvent>, interval: f32) -> Self {
+		Self {
+			enabled: true,
+			seed,
+			pool,
+			interval,
+			timer: interval,
+			events: 0,
+			last: None,
+			rng: Rng::new(seed),
+			last_time: None,
+		}
+	}
+
+	// --chaos [seed] turns it on from the start, with --chaos-events explode,break_link,...
+	// for the pool and --chaos-interval <seconds>
+	pub fn from_args(args: &[String]) -> Option<Chaos> {
+		let index = args.iter().position(|arg| arg == "--chaos")?;
+		let seed = args.get(index + 1)
+			.and_then(|seed| seed.parse().ok())
+			.unwrap_or_else(|| macroquad::miniquad::date::now() as u64);
+
+		Some(Chaos::with_args(seed, args))
+	}
+
+	// everything from_args() reads apart from the seed
+	pub fn with_args(seed: u64, args: &[String]) -> Chaos {
+		let value = |flag: &str| args.iter().position(|arg| arg == flag).and_then(|index| args.get(index + 1));
+
+		let pool = match value("--chaos-events") {
+			Some(names) => names.split(',')
+				.filter_map(|name| {
+					let event = ChaosEvent::from_name(name.trim());
+					if event.is_none() {
+						eprintln!("there's no chaos event called {:?}", name);
+					}
+					event
+				})
+				.collect(),
+			None => ChaosEvent::ALL.to_vec(),
+		};
+		let interval = value("--chaos-interval").and_then(|interval| interval.parse().ok()).unwrap_or(CHAOS_INTERVAL);
+
+		Chaos::new(seed, pool, interval)
+	}
+
+	// somewhere inside the container, evenly over its area and not too close to the edge
+	fn point(&mut self, constraint: &Constraint) -> Vec2 {
+		let (center, radius) = match constraint {
+			Constraint::Circle { center, radius } => (*center, *radius),
+			// an open world gets the same spot the default container would've
+			Constraint::None => (vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5), CONSTRAINT_RADIUS),
+			_ => {
+				let Some((min, max)) = constraint.bounds() else {
+					return vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5);
+				};
+				let middle = (min + max) * 0.5;
+				// a few goes at a spot in the box around it, the middle if they all miss
+				for _ in 0..16 {
+					let point = middle + (vec2(self.rng.range(min.x, max.x), self.rng.range(min.y, max.y)) - middle) * 0.8;
+					if constraint.contains(point) {
+						return point;
+					}
+				}
+				return middle;
+			}
+		};
+
+		let angle = self.rng.range(0.0, TAU);
+		let distance = self.rng.range(0.0, 1.0).sqrt() * radius * 0.8;
+		center + vec2(angle.cos(), angle.sin()) * distance
+	}
+
+	pub fn update(&mut self, master: &Master, commands: &mut Vec<Command>) {
+		// stats.time counts 60ths of a second
+		let time = master.stats.time;
+		let seconds = self.last_time.map_or(0.0, |last| (time - last).max(0.0) / 60.0);
+		self.last_time = Some(time);
+		if !self.enabled || self.pool.is_empty() {
+			return;
+		}
+
+		self.timer -= seconds;
+		while self.timer <= 0.0 {
+			self.timer += self.interval.max(0.01);
+
+			let pick = self.rng.index(self.pool.len());
+			let event = self.pool[pick];
+			let command = match event {
+				ChaosEvent::GravityFlip => Some(Command::SetGravity { gravity: -master.gravity }),
+				ChaosEvent::Explode => Some(Command::Explode {
+					position: self.point(&master.constraint),
+					strength: self.rng.range(0.5, 1.5) * EXPLOSION_STRENGTH,
+				}),
+				ChaosEvent::SpawnBurst if master.objects.len() < CHAOS_MAX_OBJECTS => Some(Command::SpawnBurst {
+					position: self.point(&master.constraint),
+					count: 5 + self.rng.index(20),
+					radius: self.rng.range(5.0, 15.0),
+				}),
+				ChaosEvent::SpawnBurst => None,
+				ChaosEvent::BreakLink => {
+					let ids: Vec<usize> = master.chain_links.ids().collect();
+					let pick = self.rng.index(ids.len());
+					ids.get(pick).map(|&id| Command::BreakLink { id })
+				}
+			};
+
+			commands.extend(command);
+			self.events += 1;
+			self.last = Some(event);
+		}
+	}
+}
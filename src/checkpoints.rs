@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+
+use verlet_integration::Master;
+
+use crate::input::{Action, InputMap};
+use crate::scene::Timeline;
+
+// names longer than this stop taking letters
+pub const MAX_NAME_LENGTH: usize = 32;
+
+// the whole simulation as it was when it was made, scripted events and all
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+	pub name: String,
+	pub master: Master,
+	pub timeline: Timeline,
+}
+
+// any number of named snapshots kept for the rest of the run, restorable in any order and as
+// many times as wanted. n names a new one, up and down pick one, enter restores it, delete
+// throws it away and escape closes the list
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointPanel {
+	pub open: bool,
+	pub checkpoints: Vec<Checkpoint>,
+	selected: usize,
+	// the name being typed for a new checkpoint
+	naming: Option<String>,
+}
+
+impl CheckpointPanel {
+	pub fn update(&mut self, input: &mut InputMap, master: &mut Master, timeline: &mut Timeline) {
+		if !self.open {
+			if input.pressed(Action::Checkpoints) {
+				self.open = true;
+			}
+			input.suspended |= self.open;
+			return;
+		}
+
+		if let Some(name) = &mut self.naming {
+			while let Some(character) = get_char_pressed() {
+				if !character.is_control() && name.chars().count() < MAX_NAME_LENGTH {
+					name.push(character);
+				}
+			}
+
+			if is_key_pressed(KeyCode::Backspace) {
+				name.pop();
+			} else if is_key_pressed(KeyCode::Escape) {
+				self.naming = None;
+			} else if is_key_pressed(KeyCode::Enter) {
+				let name = match name.trim() {
+					"" => format!("checkpoint {}", self.checkpoints.len() + 1),
+					name => name.to_string(),
+				};
+				self.checkpoints.push(Checkpoint {
+					name,
+					master: master.clone(),
+					timeline: timeline.clone(),
+				});
+				self.selected = self.checkpoints.len() - 1;
+				self.naming = None;
+			}
+		} else if is_key_pressed(KeyCode::Escape) || input.binding(Action::Checkpoints).is_pressed() {
+			self.open = false;
+		} else if is_key_pressed(KeyCode::N) {
+			// whatever was typed before now isn't part of the name
+			while get_char_pressed().is_some() {}
+			self.naming = Some(String::new());
+		} else if !self.checkpoints.is_empty() {
+			let count = self.checkpoints.len();
+			if is_key_pressed(KeyCode::Up) {
+				self.selected = (self.selected + count - 1) % count;
+			} else if is_key_pressed(KeyCode::Down) {
+				self.selected = (self.selected + 1) % count;
+			} else if is_key_pressed(KeyCode::Enter) {
+				let checkpoint = &self.checkpoints[self.selected];
+				*master = checkpoint.master.clone();
+				*timeline = checkpoint.timeline.clone();
+			} else if is_key_pressed(KeyCode::Delete) {
+				self.checkpoints.remove(self.selected);
+				self.selected = self.selected.min(self.checkpoints.len().saturating_sub(1));
+			}
+		}
+
+		input.suspended |= self.open;
+	}
+
+	pub fn draw(&self) {
+		if !self.open {
+			return;
+		}
+
+		draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+		draw_text("CHECKPOINTS (N FOR A NEW ONE, ENTER TO RESTORE, DELETE TO REMOVE)", 20.0, 30.0, 24.0, WHITE);
+
+		let mut y = 70.0;
+		if let Some(name) = &self.naming {
+			draw_text(&format!("NAME: {}_", name), 20.0, y, 28.0, YELLOW);
+			y += 40.0;
+		} else if self.checkpoints.is_empty() {
+			draw_text("NONE YET", 20.0, y, 28.0, GRAY);
+		}
+
+		for (i, checkpoint) in self.checkpoints.iter().enumerate() {
+			let color = if i == self.selected && self.naming.is_none() { YELLOW } else { WHITE };
+			draw_text(
+				&format!("{}  ({} OBJECTS, {:.1}S IN)", checkpoint.name, checkpoint.master.objects.len(), checkpoint.master.stats.time / 60.0),
+				20.0,
+				y + i as f32 * 34.0,
+				28.0,
+				color,
+			);
+		}
+	}
+}
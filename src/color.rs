@@ -0,0 +1,35 @@
+// the solver's own color, so it doesn't need macroquad. laid out the same as macroquad's,
+// and converts into it when the app feature is on
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+	pub r: f32,
+	pub g: f32,
+	pub b: f32,
+	pub a: f32,
+}
+
+impl Color {
+	pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+		Self {
+			r,
+			g,
+			b,
+			a,
+		}
+	}
+}
+
+#[cfg(feature = "app")]
+impl From<Color> for macroquad::color::Color {
+	fn from(color: Color) -> Self {
+		macroquad::color::Color::new(color.r, color.g, color.b, color.a)
+	}
+}
+
+#[cfg(feature = "app")]
+impl From<macroquad::color::Color> for Color {
+	fn from(color: macroquad::color::Color) -> Self {
+		Color::new(color.r, color.g, color.b, color.a)
+	}
+}
@@ -0,0 +1,154 @@
+use alloc::vec::Vec;
+
+use glam::{vec2, Vec2};
+
+use crate::{CONSTRAINT_RADIUS, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+// what keeps everything in. anything poking out gets pushed back inside at the start of
+// every substep, kinematic objects are left wherever they're put
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Constraint {
+	Circle { center: Vec2, radius: f32 },
+	// an axis aligned box, min being the top left corner
+	Aabb { min: Vec2, max: Vec2 },
+	// the corners in order, either way around. fewer than three holds nothing in
+	ConvexPolygon(Vec<Vec2>),
+	// an open world
+	None,
+}
+
+// a box that takes out any object that isn't pinned once its middle goes in, like a drain under
+// the container or the bottom of an open world. see Master::kill_zones
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KillZone {
+	pub min: Vec2,
+	pub max: Vec2,
+}
+
+impl KillZone {
+	// any two opposite corners
+	pub fn new(a: Vec2, b: Vec2) -> Self {
+		Self { min: a.min(b), max: a.max(b) }
+	}
+
+	// everything further down than y, all the way across
+	pub fn below(y: f32) -> Self {
+		Self { min: vec2(f32::MIN, y), max: Vec2::splat(f32::MAX) }
+	}
+
+	pub fn is_below(&self) -> bool {
+		self.min.x == f32::MIN && self.max == Vec2::splat(f32::MAX)
+	}
+
+	pub fn contains(&self, point: Vec2) -> bool {
+		point.cmpge(self.min).all() && point.cmple(self.max).all()
+	}
+}
+
+impl Default for Constraint {
+	fn default() -> Self {
+		Constraint::Circle {
+			center: vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5),
+			radius: CONSTRAINT_RADIUS,
+		}
+	}
+}
+
+// how far out of the constraint something is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Overlap {
+	// where it has to go to be all the way inside
+	pub position: Vec2,
+	// pointing back inside, from the wall it went through
+	pub normal: Vec2,
+	pub depth: f32,
+}
+
+impl Constraint {
+	// for a circle this big, None when it's already inside
+	pub fn overlap(&self, position: Vec2, radius: f32) -> Option<Overlap> {
+		match self {
+			Constraint::Circle { center, radius: outer } => {
+				let to_object = position - *center;
+				let distance = to_object.length();
+				if distance <= outer - radius || distance == 0.0 {
+					return None;
+				}
+
+				let n = to_object / distance;
+				Some(Overlap {
+					position: *center + n * (outer - radius),
+					normal: -n,
+					depth: distance - (outer - radius),
+				})
+			}
+			Constraint::None => None,
+			_ => {
+				// one side at a time, which in a convex shape ends up in the right place even
+				// in a corner
+				let mut moved = position;
+				for i in 0..self.edge_count() {
+					let (point, normal) = self.edge(i);
+					let distance = (moved - point).dot(normal);
+					if distance < radius {
+						moved += normal * (radius - distance);
+					}
+				}
+
+				let offset = moved - position;
+				let depth = offset.length();
+				(depth > 0.0).then(|| Overlap { position: moved, normal: offset / depth, depth })
+			}
+		}
+	}
+
+	pub fn contains(&self, point: Vec2) -> bool {
+		self.overlap(point, 0.0).is_none()
+	}
+
+	// the box around it, None for an open world
+	pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+		match self {
+			Constraint::Circle { center, radius } => Some((*center - *radius, *center + *radius)),
+			Constraint::Aabb { min, max } => Some((*min, *max)),
+			Constraint::ConvexPolygon(points) if !points.is_empty() => Some((
+				points.iter().copied().fold(Vec2::splat(f32::INFINITY), Vec2::min),
+				points.iter().copied().fold(Vec2::splat(f32::NEG_INFINITY), Vec2::max),
+			)),
+			_ => None,
+		}
+	}
+
+	// how many straight sides it has, circles and open worlds have none
+	pub fn edge_count(&self) -> usize {
+		match self {
+			Constraint::Aabb { .. } => 4,
+			Constraint::ConvexPolygon(points) if points.len() >= 3 => points.len(),
+			_ => 0,
+		}
+	}
+
+	// a point on the side and which way inside is from it
+	pub fn edge(&self, i: usize) -> (Vec2, Vec2) {
+		match self {
+			Constraint::Aabb { min, max } => match i {
+				0 => (*min, Vec2::X),
+				1 => (*min, Vec2::Y),
+				2 => (*max, -Vec2::X),
+				_ => (*max, -Vec2::Y),
+			},
+			Constraint::ConvexPolygon(points) => {
+				let a = points[i];
+				let b = points[(i + 1) % points.len()];
+				let along = (b - a).normalize_or_zero();
+				// which side is inside depends on which way around the corners go
+				let turn = (points[1] - points[0]).perp_dot(points[2] - points[1]);
+				let normal = if turn >= 0.0 { along.perp() } else { -along.perp() };
+				(a, normal)
+			}
+			_ => (Vec2::ZERO, Vec2::ZERO),
+		}
+	}
+}
@@ -0,0 +1,46 @@
+use glam::Vec2;
+
+// the most points a curve can have
+pub const DAMPING_CURVE_POINTS: usize = 4;
+
+// the fraction of its speed an object loses every substep, going by how fast it's going: a
+// few (speed, damping) points from slowest to fastest, in pixels a frame, with straight lines
+// between them. slower than the first it's the first's damping and faster than the last it's
+// the last's. one that starts at 0 leaves slow things alone, and rising after that
+// (damping going up with the speed is drag going up with the speed squared, like air) gives
+// fast ones a terminal velocity, where what gravity adds each substep is what's taken away
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DampingCurve {
+	pub points: [Vec2; DAMPING_CURVE_POINTS],
+	pub count: usize,
+}
+
+impl DampingCurve {
+	// None if there aren't any points or there are too many, or they don't go from slowest
+	// to fastest
+	pub fn new(points: &[Vec2]) -> Option<Self> {
+		if points.is_empty() || points.len() > DAMPING_CURVE_POINTS || points.windows(2).any(|pair| pair[1].x <= pair[0].x) {
+			return None;
+		}
+		let mut curve = Self { points: [Vec2::ZERO; DAMPING_CURVE_POINTS], count: points.len() };
+		curve.points[..points.len()].copy_from_slice(points);
+		Some(curve)
+	}
+
+	pub fn points(&self) -> &[Vec2] {
+		&self.points[..self.count.min(DAMPING_CURVE_POINTS)]
+	}
+
+	pub fn damping(&self, speed: f32) -> f32 {
+		let points = self.points();
+		let Some(after) = points.iter().position(|point| point.x > speed) else {
+			return points.last().map_or(0.0, |point| point.y);
+		};
+		if after == 0 {
+			return points[0].y;
+		}
+		let (a, b) = (points[after - 1], points[after]);
+		a.y + (b.y - a.y) * (speed - a.x) / (b.x - a.x)
+	}
+}
@@ -0,0 +1,354 @@
+use std::io;
+use std::path::Path;
+
+use macroquad::prelude::*;
+
+use verlet_integration::{Master, ObjectId, StaticSegment, VectorField, VerletObject, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::cannon::{Cannon, CANNON_WIDTH};
+use crate::parts::{spaced_along, Part, BLOB_PRESSURE, BUTTON_RADIUS};
+use crate::scene::{packed_ids, Scene, Timeline, Trigger};
+use crate::trails::Trail;
+use crate::Command;
+
+pub const RAMP_THICKNESS: f32 = 8.0;
+pub const SEESAW_LENGTH: f32 = 160.0;
+// the short arm and the long one, and how far it turns either way
+pub const LEVER_ARMS: (f32, f32) = (40.0, 120.0);
+pub const LEVER_LIMIT: f32 = 25.0;
+pub const DOMINO_HEIGHT: f32 = 70.0;
+// between the dominos in a row, a little over half as tall so each one reaches the next
+pub const DOMINO_SPACING: f32 = 40.0;
+pub const BALL_RADIUS: f32 = 12.0;
+pub const BLOB_SIZE: f32 = 40.0;
+// characters in a typed link distance
+pub const MAX_DISTANCE_LENGTH: usize = 8;
+// a new field covers the window in squares this big. painting adds on how far the mouse went
+// times the strength (in pixels a frame squared, gravity's 1) to every square in the brush,
+// up to the most any one can push
+pub const FIELD_CELL_SIZE: f32 = 40.0;
+pub const FIELD_BRUSH_RADIUS: f32 = 40.0;
+pub const FIELD_PAINT_STRENGTH: f32 = 0.02;
+pub const FIELD_MAX_STRENGTH: f32 = 2.0;
+
+const EDITOR_COLOR: Color = Color::new(0.4, 0.9, 1.0, 0.8);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+	// drag from one end to the other
+	Ramp,
+	// click where the pivot goes
+	Seesaw,
+	// click where the pivot goes, the long arm is on the right
+	Lever,
+	// click where it stands
+	Domino,
+	// drag along where the row goes
+	DominoRow,
+	// click where the button goes and then where it drops a ball from the first time it's
+	// pressed
+	Button,
+	Ball,
+	// click where the middle of it goes
+	Blob,
+	// click two objects to link them, as far apart as they are unless a distance gets typed
+	// in after the first. puts a broken one back if there was one between them
+	Link,
+	// click where it stands and drag out which way and how hard it fires
+	Cannon,
+	// drag over the vector field the way it should push things, with alternate held to rub
+	// it out
+	Field,
+}
+
+impl Tool {
+	// off, then each tool in turn
+	pub fn next(tool: Option<Tool>) -> Option<Tool> {
+		match tool {
+			None => Some(Tool::Ramp),
+			Some(Tool::Ramp) => Some(Tool::Seesaw),
+			Some(Tool::Seesaw) => Some(Tool::Lever),
+			Some(Tool::Lever) => Some(Tool::Domino),
+			Some(Tool::Domino) => Some(Tool::DominoRow),
+			Some(Tool::DominoRow) => Some(Tool::Button),
+			Some(Tool::Button) => Some(Tool::Ball),
+			Some(Tool::Ball) => Some(Tool::Blob),
+			Some(Tool::Blob) => Some(Tool::Link),
+			Some(Tool::Link) => Some(Tool::Cannon),
+			Some(Tool::Cannon) => Some(Tool::Field),
+			Some(Tool::Field) => None,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Tool::Ramp => "RAMP",
+			Tool::Seesaw => "SEESAW",
+			Tool::Lever => "LEVER",
+			Tool::Domino => "DOMINO",
+			Tool::DominoRow => "DOMINO ROW",
+			Tool::Button => "BUTTON",
+			Tool::Ball => "BALL",
+			Tool::Blob => "BLOB",
+			Tool::Link => "LINK",
+			Tool::Cannon => "CANNON",
+			Tool::Field => "FIELD",
+		}
+	}
+}
+
+// for building chain reaction machines in the running simulation, a piece at a time. like the
+// measuring tools it takes over the spawn button while a tool is picked
+#[derive(Clone, Debug, Default)]
+pub struct Editor {
+	pub tool: Option<Tool>,
+	ramp_start: Option<Vec2>,
+	// where the mouse has been since the button went down, for a row of dominos
+	path: Vec<Vec2>,
+	// a button that's been put down and doesn't have a drop point yet
+	button: Option<usize>,
+	// the first end of a link and the distance typed for it so far
+	link_start: Option<ObjectId>,
+	distance: String,
+	// the cannon being aimed while the button's still down
+	cannon: Option<usize>,
+	// where the mouse was last frame while painting the vector field
+	field_last: Option<Vec2>,
+}
+
+impl Editor {
+	pub fn set_tool(&mut self, tool: Option<Tool>) {
+		*self = Editor {
+			tool,
+			..Default::default()
+		};
+	}
+
+	// while it's waiting on the second end of a link, the number keys are for the distance
+	pub fn typing(&self) -> bool {
+		self.link_start.is_some()
+	}
+
+	pub fn update(&mut self, master: &mut Master, timeline: &mut Timeline, mouse: Vec2, pressed: bool, down: bool, alternate: bool) {
+		match self.tool {
+			Some(Tool::Ramp) => {
+				if pressed {
+					self.ramp_start = Some(mouse);
+				} else if !down {
+					// a click without a drag doesn't make anything
+					if let Some(start) = self.ramp_start.take().filter(|start| start.distance(mouse) > RAMP_THICKNESS) {
+						master.segments.push(StaticSegment::new(start, mouse, RAMP_THICKNESS));
+					}
+				}
+			}
+			Some(Tool::Seesaw) if pressed => {
+				Part::seesaw(mouse, SEESAW_LENGTH, 0.0).add_to_master(master);
+			}
+			Some(Tool::Lever) if pressed => {
+				let (left, right) = LEVER_ARMS;
+				let limit = LEVER_LIMIT.to_radians();
+				Part::lever(mouse, left, right, 0.0, Some((-limit, limit))).add_to_master(master);
+			}
+			Some(Tool::Domino) if pressed => {
+				Part::domino(mouse, DOMINO_HEIGHT).add_to_master(master);
+			}
+			Some(Tool::DominoRow) => {
+				if pressed {
+					self.path = vec![mouse];
+				} else if down {
+					// a point every few pixels is plenty to follow a curve
+					if self.path.last().is_some_and(|&last| last.distance(mouse) > 4.0) {
+						self.path.push(mouse);
+					}
+				} else if !self.path.is_empty() {
+					Part::domino_row(&std::mem::take(&mut self.path), DOMINO_HEIGHT, DOMINO_SPACING).add_to_master(master);
+				}
+			}
+			Some(Tool::Button) if pressed => match self.button.take() {
+				Some(id) if master.objects.contains_index(id) => {
+					let command = Command::Spawn { position: mouse, radius: Some(BALL_RADIUS), tag: 0, velocity: Vec2::ZERO };
+					timeline.triggers.push(Trigger { sensor: id, command, once: true, fired: false });
+				}
+				_ => self.button = Part::button(mouse).add_to_master(master).first().copied(),
+			},
+			Some(Tool::Ball) if pressed => {
+				let color = master.random_color();
+				master.objects.push(VerletObject::new(mouse, BALL_RADIUS).with_color(color));
+			}
+			Some(Tool::Blob) if pressed => {
+				let ids = Part::blob(mouse, BLOB_SIZE).add_to_master(master);
+				master.add_soft_body(ids, BLOB_PRESSURE);
+			}
+			Some(Tool::Link) => self.update_link(master, mouse, pressed),
+			Some(Tool::Cannon) => {
+				if pressed {
+					timeline.cannons.push(Cannon::new(mouse));
+					self.cannon = Some(timeline.cannons.len() - 1);
+				} else if !down {
+					self.cannon = None;
+				}
+				// a click without a drag leaves it pointing the way it started
+				if let Some(cannon) = self.cannon.and_then(|index| timeline.cannons.get_mut(index)) {
+					if cannon.position.distance(mouse) > CANNON_WIDTH {
+						cannon.aim_at(mouse);
+					}
+				}
+			}
+			Some(Tool::Field) => {
+				let last = self.field_last.take();
+				if down {
+					paint_field(master, last.unwrap_or(mouse), mouse, alternate);
+					self.field_last = Some(mouse);
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn update_link(&mut self, master: &mut Master, mouse: Vec2, pressed: bool) {
+		// the first end can go away while it's waiting
+		if self.link_start.is_some_and(|id| master.object(id).is_none()) {
+			self.link_start = None;
+		}
+
+		if self.link_start.is_some() {
+			while let Some(character) = get_char_pressed() {
+				if (character.is_ascii_digit() || character == '.') && self.distance.len() < MAX_DISTANCE_LENGTH {
+					self.distance.push(character);
+				}
+			}
+			if is_key_pressed(KeyCode::Backspace) {
+				self.distance.pop();
+			}
+		}
+
+		if !pressed {
+			return;
+		}
+		let clicked = master.object_at(mouse).and_then(|index| master.object_id(index));
+		let Some(start) = self.link_start.take() else {
+			// whatever was typed before now isn't the distance
+			while get_char_pressed().is_some() {}
+			self.link_start = clicked;
+			self.distance.clear();
+			return;
+		};
+		// clicking empty space or the same one again gives up on it
+		let Some(end) = clicked.filter(|&end| end != start) else {
+			return;
+		};
+
+		let (a, b) = (start.index, end.index);
+		let broken = master.broken_links.iter()
+			.position(|chain_link| (chain_link.a, chain_link.b) == (a, b) || (chain_link.a, chain_link.b) == (b, a));
+		let Some(id) = broken.and_then(|index| master.repair_link(index)).or_else(|| master.link(start, end)) else {
+			return;
+		};
+		if let Some(distance) = self.distance.parse::<f32>().ok().filter(|&distance| distance > 0.0) {
+			master.chain_links[id].target_distance = distance;
+		}
+	}
+
+	// zoom is the camera's, so the lines stay the same size on screen
+	pub fn draw(&self, master: &Master, mouse: Vec2, zoom: f32) {
+		let thickness = 2.0 / zoom;
+		match self.tool {
+			Some(Tool::Ramp) => match self.ramp_start {
+				Some(start) => draw_line(start.x, start.y, mouse.x, mouse.y, RAMP_THICKNESS, EDITOR_COLOR),
+				None => draw_circle_lines(mouse.x, mouse.y, RAMP_THICKNESS * 0.5, thickness, EDITOR_COLOR),
+			},
+			Some(Tool::Seesaw) => {
+				let half = SEESAW_LENGTH * 0.5;
+				draw_line(mouse.x - half, mouse.y, mouse.x + half, mouse.y, thickness, EDITOR_COLOR);
+				draw_circle_lines(mouse.x, mouse.y, 6.0 / zoom, thickness, EDITOR_COLOR);
+			}
+			Some(Tool::Lever) => {
+				let (left, right) = LEVER_ARMS;
+				draw_line(mouse.x - left, mouse.y, mouse.x + right, mouse.y, thickness, EDITOR_COLOR);
+				draw_circle_lines(mouse.x, mouse.y, 6.0 / zoom, thickness, EDITOR_COLOR);
+			}
+			Some(Tool::Domino) => draw_line(mouse.x, mouse.y, mouse.x, mouse.y - DOMINO_HEIGHT, thickness, EDITOR_COLOR),
+			Some(Tool::DominoRow) => {
+				for pair in self.path.windows(2) {
+					draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, thickness, EDITOR_COLOR);
+				}
+				let path = if self.path.is_empty() { vec![mouse] } else { self.path.clone() };
+				for bottom in spaced_along(&path, DOMINO_SPACING) {
+					draw_line(bottom.x, bottom.y, bottom.x, bottom.y - DOMINO_HEIGHT, thickness, EDITOR_COLOR);
+				}
+			}
+			Some(Tool::Button) => {
+				// where the ball would drop from, back to the button it's for
+				if let Some(button) = self.button.and_then(|id| master.objects.get(id)) {
+					draw_line(button.position.x, button.position.y, mouse.x, mouse.y, thickness, EDITOR_COLOR);
+				}
+				let radius = if self.button.is_some() { BALL_RADIUS } else { BUTTON_RADIUS };
+				draw_circle_lines(mouse.x, mouse.y, radius, thickness, EDITOR_COLOR);
+			}
+			Some(Tool::Ball) => draw_circle_lines(mouse.x, mouse.y, BALL_RADIUS, thickness, EDITOR_COLOR),
+			Some(Tool::Blob) => draw_circle_lines(mouse.x, mouse.y, BLOB_SIZE, thickness, EDITOR_COLOR),
+			Some(Tool::Link) => {
+				let Some(start) = self.link_start.and_then(|id| master.object(id)) else {
+					return;
+				};
+				let start = start.position;
+				draw_line(start.x, start.y, mouse.x, mouse.y, thickness, EDITOR_COLOR);
+				let distance = match self.distance.as_str() {
+					"" => format!("{:.1}", start.distance(mouse)),
+					typed => format!("{}_", typed),
+				};
+				let middle = (start + mouse) * 0.5;
+				draw_text_ex(&distance, middle.x + 8.0 / zoom, middle.y - 8.0 / zoom, TextParams {
+					font_size: 24,
+					font_scale: 1.0 / zoom,
+					color: EDITOR_COLOR,
+					..Default::default()
+				});
+			}
+			// the cannons draw themselves once they're down
+			Some(Tool::Cannon) if self.cannon.is_none() => draw_circle_lines(mouse.x, mouse.y, CANNON_WIDTH, thickness, EDITOR_COLOR),
+			Some(Tool::Cannon) => {}
+			Some(Tool::Field) => draw_circle_lines(mouse.x, mouse.y, FIELD_BRUSH_RADIUS, thickness, EDITOR_COLOR),
+			None => {}
+		}
+	}
+}
+
+// pushing the squares under the brush the way the mouse moved from last, or with alternate
+// rubbing them out. the first stroke puts a field down over the window if there isn't one
+fn paint_field(master: &mut Master, last: Vec2, mouse: Vec2, alternate: bool) {
+	let field = master.vector_field.get_or_insert_with(|| {
+		let (columns, rows) = ((WINDOW_WIDTH / FIELD_CELL_SIZE).ceil(), (WINDOW_HEIGHT / FIELD_CELL_SIZE).ceil());
+		VectorField::new(Vec2::ZERO, FIELD_CELL_SIZE, columns as usize, rows as usize)
+	});
+	let push = (mouse - last) * FIELD_PAINT_STRENGTH;
+	for cell in 0..field.cells.len() {
+		if field.center(cell).distance(mouse) > FIELD_BRUSH_RADIUS {
+			continue;
+		}
+		field.cells[cell] = if alternate {
+			Vec2::ZERO
+		} else {
+			(field.cells[cell] + push).clamp_length_max(FIELD_MAX_STRENGTH)
+		};
+	}
+}
+
+// the machine as it stands, so it can be opened again with --scene. the buttons, cannons,
+// automations and trails come along (the vector field's in the master already), the rest of
+// the timeline doesn't
+pub fn save(master: &Master, timeline: &Timeline, path: impl AsRef<Path>) -> io::Result<()> {
+	scene(master, timeline).save(path)
+}
+
+pub fn scene(master: &Master, timeline: &Timeline) -> Scene {
+	let mut scene = Scene::from_master(master);
+	let ids = packed_ids(master);
+	scene.timeline.triggers = timeline.triggers.iter()
+		.filter_map(|&trigger| Some(Trigger { sensor: ids.get(trigger.sensor).copied().flatten()?, fired: false, ..trigger }))
+		.collect();
+	scene.timeline.cannons = timeline.cannons.clone();
+	scene.timeline.automations = timeline.automations.clone();
+	scene.timeline.trails = timeline.trails.iter().map(|trail| Trail::new(trail.tag, trail.length)).collect();
+	scene
+}
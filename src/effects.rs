@@ -0,0 +1,131 @@
+use macroquad::prelude::*;
+use macroquad::rand::gen_range;
+
+use verlet_integration::Master;
+
+// how much shake a hit adds per unit of speed over the master's impact speed, and how fast it
+// fades. the shake itself grows with the square of this so small knocks barely move the screen
+pub const SHAKE_PER_SPEED: f32 = 0.04;
+pub const SHAKE_DECAY: f32 = 0.9;
+pub const MAX_SHAKE: f32 = 12.0;
+
+pub const SPARKS_PER_SPEED: f32 = 0.5;
+pub const MAX_SPARKS_PER_IMPACT: usize = 12;
+pub const SPARK_LENGTH: f32 = 2.0;
+// every bumper hit throws the same spray, however hard it was
+pub const SPARKS_PER_BUMPER: usize = 8;
+// and every bomb a bigger one, with a shake as big as a hit this much over the impact speed
+pub const SPARKS_PER_DETONATION: usize = 40;
+pub const DETONATION_SHAKE: f32 = 20.0;
+
+// a bit of light flying off a hit. it isn't part of the simulation, nothing touches it
+#[derive(Clone, Debug)]
+pub struct Spark {
+	pub position: Vec2,
+	pub velocity: Vec2,
+	// frames left
+	pub life: f32,
+	pub max_life: f32,
+}
+
+// screen shake and sparks for the big impacts, bumper hits and bombs the master reports each frame
+#[derive(Clone, Debug, Default)]
+pub struct Effects {
+	pub shake: bool,
+	pub sparks_enabled: bool,
+	// 0 to 1
+	pub trauma: f32,
+	pub sparks: Vec<Spark>,
+}
+
+impl Effects {
+	pub fn update(&mut self, master: &Master) {
+		for impact in master.impacts.iter() {
+			let over = impact.speed - master.impact_speed;
+
+			if self.shake {
+				self.trauma = (self.trauma + over * SHAKE_PER_SPEED).min(1.0);
+			}
+
+			if self.sparks_enabled {
+				let count = ((over * SPARKS_PER_SPEED) as usize + 1).min(MAX_SPARKS_PER_IMPACT);
+				for _ in 0..count {
+					// thrown out along the normal (away from the wall, for the container), spread out either side
+					let angle = impact.normal.y.atan2(impact.normal.x) + gen_range(-1.2, 1.2);
+					let life = gen_range(20.0, 40.0);
+					self.sparks.push(Spark {
+						position: impact.point,
+						velocity: vec2(angle.cos(), angle.sin()) * gen_range(0.3, 1.0) * impact.speed.min(30.0) * 0.3,
+						life,
+						max_life: life,
+					});
+				}
+			}
+		}
+
+		if self.sparks_enabled {
+			for event in master.bumper_events.iter() {
+				for _ in 0..SPARKS_PER_BUMPER {
+					let angle = event.normal.y.atan2(event.normal.x) + gen_range(-0.8, 0.8);
+					let life = gen_range(15.0, 25.0);
+					self.sparks.push(Spark {
+						position: event.point,
+						velocity: vec2(angle.cos(), angle.sin()) * gen_range(2.0, 4.0),
+						life,
+						max_life: life,
+					});
+				}
+			}
+		}
+
+		for detonation in master.detonations.iter() {
+			if self.shake {
+				self.trauma = (self.trauma + DETONATION_SHAKE * SHAKE_PER_SPEED).min(1.0);
+			}
+			if self.sparks_enabled {
+				for _ in 0..SPARKS_PER_DETONATION {
+					let angle = gen_range(0.0, std::f32::consts::TAU);
+					let life = gen_range(20.0, 45.0);
+					self.sparks.push(Spark {
+						position: detonation.position,
+						velocity: vec2(angle.cos(), angle.sin()) * gen_range(0.2, 1.0) * detonation.strength * 0.5,
+						life,
+						max_life: life,
+					});
+				}
+			}
+		}
+
+		self.trauma *= SHAKE_DECAY;
+		if self.trauma < 0.01 {
+			self.trauma = 0.0;
+		}
+
+		for spark in self.sparks.iter_mut() {
+			spark.position += spark.velocity;
+			spark.velocity *= 0.92;
+			spark.velocity += master.gravity * 0.2;
+			spark.life -= 1.0;
+		}
+		self.sparks.retain(|spark| spark.life > 0.0);
+	}
+
+	// how far to move the camera this frame
+	pub fn offset(&self) -> Vec2 {
+		if self.trauma == 0.0 {
+			return Vec2::ZERO;
+		}
+		let strength = self.trauma * self.trauma * MAX_SHAKE;
+		vec2(gen_range(-1.0, 1.0), gen_range(-1.0, 1.0)) * strength
+	}
+
+	// yellow going orange going out
+	pub fn draw(&self) {
+		for spark in self.sparks.iter() {
+			let left = spark.life / spark.max_life;
+			let tail = spark.position - spark.velocity * SPARK_LENGTH;
+			let color = Color::new(1.0, 0.5 + left * 0.5, left * 0.4, left);
+			draw_line(tail.x, tail.y, spark.position.x, spark.position.y, 2.0, color);
+		}
+	}
+}
@@ -0,0 +1,186 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use macroquad::prelude::*;
+
+use verlet_integration::{Constraint, Master, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::scene::WALL_COLOR;
+
+fn svg_color(color: Color) -> String {
+	format!(
+		"rgb({},{},{})",
+		(color.r * 255.0) as u8,
+		(color.g * 255.0) as u8,
+		(color.b * 255.0) as u8,
+	)
+}
+
+// the current frame as a standalone svg, drawn the same way as the flat renderer
+pub fn frame_to_svg(master: &Master) -> String {
+	let mut svg = String::new();
+
+	let _ = writeln!(
+		svg,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+		w = WINDOW_WIDTH,
+		h = WINDOW_HEIGHT,
+	);
+	let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="rgb(23,23,31)"/>"#);
+	let _ = match &master.constraint {
+		Constraint::Circle { center, radius } => writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="black"/>"#, center.x, center.y, radius),
+		Constraint::Aabb { min, max } => writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="black"/>"#, min.x, min.y, max.x - min.x, max.y - min.y),
+		Constraint::ConvexPolygon(points) => {
+			let points: Vec<String> = points.iter().map(|point| format!("{},{}", point.x, point.y)).collect();
+			writeln!(svg, r#"<polygon points="{}" fill="black"/>"#, points.join(" "))
+		}
+		Constraint::None => Ok(()),
+	};
+
+	for segment in master.segments.iter() {
+		let _ = writeln!(
+			svg,
+			r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}" stroke-linecap="round"/>"#,
+			segment.a.x,
+			segment.a.y,
+			segment.b.x,
+			segment.b.y,
+			svg_color(WALL_COLOR.into()),
+			segment.thickness,
+		);
+	}
+
+	let _ = writeln!(svg, r#"<g stroke="{}" stroke-width="2">"#, svg_color(GRAY));
+	for chain_link in master.chain_links.iter().filter(|chain_link| chain_link.is_enabled(&master.groups)) {
+		let a = master.objects[chain_link.a].position;
+		let b = master.objects[chain_link.b].position;
+		let _ = writeln!(svg, r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#, a.x, a.y, b.x, b.y);
+	}
+	let _ = writeln!(svg, "</g>");
+
+	for object in master.objects.iter() {
+		let _ = writeln!(
+			svg,
+			r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+			object.position.x,
+			object.position.y,
+			object.radius,
+			svg_color(object.color.into()),
+		);
+	}
+
+	svg.push_str("</svg>\n");
+	svg
+}
+
+pub fn export_svg(master: &Master, path: impl AsRef<Path>) -> io::Result<()> {
+	fs::write(path, frame_to_svg(master))
+}
+
+// the first "<prefix>_0001.<extension>" style path in the directory that isn't taken yet,
+// an empty extension leaves the dot off (for directories)
+pub fn next_free_path(directory: impl AsRef<Path>, prefix: &str, extension: &str) -> PathBuf {
+	let mut i = 1;
+	loop {
+		let mut name = format!("{}_{:04}", prefix, i);
+		if !extension.is_empty() {
+			name = format!("{}.{}", name, extension);
+		}
+
+		let path = directory.as_ref().join(name);
+		if !path.exists() {
+			return path;
+		}
+		i += 1;
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointCloudFormat {
+	Obj,
+	Ply,
+}
+
+impl PointCloudFormat {
+	pub fn extension(&self) -> &'static str {
+		match self {
+			PointCloudFormat::Obj => "obj",
+			PointCloudFormat::Ply => "ply",
+		}
+	}
+}
+
+// y is flipped so up is +y, which is what blender and friends expect
+pub fn frame_to_point_cloud(master: &Master, format: PointCloudFormat) -> String {
+	let mut result = String::new();
+
+	match format {
+		PointCloudFormat::Obj => {
+			let _ = writeln!(result, "# {} objects", master.objects.len());
+			for object in master.objects.iter() {
+				// the vertex color extension most importers understand
+				let _ = writeln!(
+					result,
+					"v {} {} 0 {} {} {}",
+					object.position.x,
+					-object.position.y,
+					object.color.r,
+					object.color.g,
+					object.color.b,
+				);
+			}
+		}
+		PointCloudFormat::Ply => {
+			let _ = writeln!(result, "ply");
+			let _ = writeln!(result, "format ascii 1.0");
+			let _ = writeln!(result, "element vertex {}", master.objects.len());
+			for property in ["float x", "float y", "float z", "float radius", "uchar red", "uchar green", "uchar blue"] {
+				let _ = writeln!(result, "property {}", property);
+			}
+			let _ = writeln!(result, "end_header");
+
+			for object in master.objects.iter() {
+				let _ = writeln!(
+					result,
+					"{} {} 0 {} {} {} {}",
+					object.position.x,
+					-object.position.y,
+					object.radius,
+					(object.color.r * 255.0) as u8,
+					(object.color.g * 255.0) as u8,
+					(object.color.b * 255.0) as u8,
+				);
+			}
+		}
+	}
+
+	result
+}
+
+// writes one numbered point cloud file per recorded frame, for importing as a sequence
+pub struct PointCloudRecorder {
+	pub directory: PathBuf,
+	pub format: PointCloudFormat,
+	pub frame: usize,
+}
+
+impl PointCloudRecorder {
+	pub fn new(directory: impl Into<PathBuf>, format: PointCloudFormat) -> io::Result<Self> {
+		let directory = directory.into();
+		fs::create_dir_all(&directory)?;
+
+		Ok(Self {
+			directory,
+			format,
+			frame: 0,
+		})
+	}
+
+	pub fn record(&mut self, master: &Master) -> io::Result<()> {
+		self.frame += 1;
+		let path = self.directory.join(format!("frame_{:05}.{}", self.frame, self.format.extension()));
+		fs::write(path, frame_to_point_cloud(master, self.format))
+	}
+}
@@ -0,0 +1,52 @@
+use std::f32::consts::TAU;
+
+use macroquad::prelude::*;
+use verlet_integration::{Magnet, Master, Pole};
+
+// how many lines leave each magnet, and how they're traced
+pub const LINES_PER_MAGNET: usize = 12;
+const STEP: f32 = 4.0;
+const MAX_STEPS: usize = 250;
+
+const NORTH_COLOR: Color = Color::new(0.95, 0.35, 0.3, 0.5);
+const SOUTH_COLOR: Color = Color::new(0.3, 0.55, 0.95, 0.5);
+
+fn pole_color(pole: Pole) -> Color {
+	match pole {
+		Pole::North => NORTH_COLOR,
+		Pole::South => SOUTH_COLOR,
+	}
+}
+
+// rings each magnet in its pole's color and follows the field out of it, forwards from north
+// poles and backwards from south ones, until the line runs into a magnet or out of reach of
+// all of them. zoom is the camera's, so the lines stay the same thickness on screen
+pub fn draw_field_lines(master: &Master, zoom: f32) {
+	let magnets: Vec<(Vec2, f32, Magnet)> = master.objects.iter()
+		.filter(|object| !object.sensor)
+		.filter_map(|object| Some((object.position, object.radius, object.magnet?)))
+		.collect();
+	let thickness = 1.5 / zoom;
+
+	for &(center, radius, magnet) in magnets.iter() {
+		let color = pole_color(magnet.pole);
+		draw_circle_lines(center.x, center.y, radius + 3.0 / zoom, thickness * 2.0, color);
+
+		for i in 0..LINES_PER_MAGNET {
+			let mut point = center + Vec2::from_angle(TAU * i as f32 / LINES_PER_MAGNET as f32) * (radius + 1.0);
+			for _ in 0..MAX_STEPS {
+				let field = master.magnetic_field(point) * magnet.pole.sign();
+				if field == Vec2::ZERO {
+					break;
+				}
+				let next = point + field.normalize() * STEP;
+				draw_line(point.x, point.y, next.x, next.y, thickness, color);
+				point = next;
+
+				if magnets.iter().any(|&(other, radius, _)| other.distance(point) < radius) {
+					break;
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,189 @@
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+use glam::{vec2, Vec2};
+
+// 32.32 fixed point, only integer math on the way so the same inputs give the same bits
+// everywhere, no matter the compiler, cpu or optimization level
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(pub i64);
+
+impl Fixed {
+	pub const FRACTION_BITS: u32 = 32;
+	pub const ZERO: Fixed = Fixed(0);
+	pub const ONE: Fixed = Fixed(1 << Self::FRACTION_BITS);
+	pub const HALF: Fixed = Fixed(1 << (Self::FRACTION_BITS - 1));
+
+	// converting is the only place floats come in, and it's exact for anything
+	// a float can hold that fits in 32.32
+	pub fn from_f32(value: f32) -> Self {
+		// rounds half away from zero, f64::round() needs std
+		let scaled = value as f64 * Self::ONE.0 as f64;
+		Fixed((if scaled < 0.0 { scaled - 0.5 } else { scaled + 0.5 }) as i64)
+	}
+
+	pub fn to_f32(self) -> f32 {
+		(self.0 as f64 / Self::ONE.0 as f64) as f32
+	}
+
+	pub fn from_int(value: i32) -> Self {
+		Fixed((value as i64) << Self::FRACTION_BITS)
+	}
+
+	pub fn abs(self) -> Self {
+		Fixed(self.0.abs())
+	}
+
+	pub fn max(self, other: Fixed) -> Self {
+		Fixed(self.0.max(other.0))
+	}
+
+	pub fn min(self, other: Fixed) -> Self {
+		Fixed(self.0.min(other.0))
+	}
+
+	pub fn sqrt(self) -> Self {
+		if self.0 <= 0 {
+			return Fixed::ZERO;
+		}
+
+		Fixed(((self.0 as u128) << Self::FRACTION_BITS).isqrt() as i64)
+	}
+}
+
+impl Add for Fixed {
+	type Output = Fixed;
+
+	fn add(self, other: Fixed) -> Fixed {
+		Fixed(self.0.wrapping_add(other.0))
+	}
+}
+
+impl Sub for Fixed {
+	type Output = Fixed;
+
+	fn sub(self, other: Fixed) -> Fixed {
+		Fixed(self.0.wrapping_sub(other.0))
+	}
+}
+
+impl Mul for Fixed {
+	type Output = Fixed;
+
+	fn mul(self, other: Fixed) -> Fixed {
+		Fixed(((self.0 as i128 * other.0 as i128) >> Self::FRACTION_BITS) as i64)
+	}
+}
+
+impl Div for Fixed {
+	type Output = Fixed;
+
+	// dividing by zero gives zero instead of panicking, so two objects sitting exactly
+	// on top of each other just don't get pushed apart
+	fn div(self, other: Fixed) -> Fixed {
+		if other.0 == 0 {
+			return Fixed::ZERO;
+		}
+
+		Fixed((((self.0 as i128) << Self::FRACTION_BITS) / other.0 as i128) as i64)
+	}
+}
+
+impl Neg for Fixed {
+	type Output = Fixed;
+
+	fn neg(self) -> Fixed {
+		Fixed(-self.0)
+	}
+}
+
+impl AddAssign for Fixed {
+	fn add_assign(&mut self, other: Fixed) {
+		*self = *self + other;
+	}
+}
+
+impl SubAssign for Fixed {
+	fn sub_assign(&mut self, other: Fixed) {
+		*self = *self - other;
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixedVec2 {
+	pub x: Fixed,
+	pub y: Fixed,
+}
+
+impl FixedVec2 {
+	pub const ZERO: FixedVec2 = FixedVec2 {
+		x: Fixed::ZERO,
+		y: Fixed::ZERO,
+	};
+
+	pub fn new(x: Fixed, y: Fixed) -> Self {
+		Self {
+			x,
+			y,
+		}
+	}
+
+	pub fn from_vec2(value: Vec2) -> Self {
+		Self::new(Fixed::from_f32(value.x), Fixed::from_f32(value.y))
+	}
+
+	pub fn to_vec2(self) -> Vec2 {
+		vec2(self.x.to_f32(), self.y.to_f32())
+	}
+
+	pub fn dot(self, other: FixedVec2) -> Fixed {
+		self.x * other.x + self.y * other.y
+	}
+
+	pub fn length(self) -> Fixed {
+		self.dot(self).sqrt()
+	}
+}
+
+impl Add for FixedVec2 {
+	type Output = FixedVec2;
+
+	fn add(self, other: FixedVec2) -> FixedVec2 {
+		FixedVec2::new(self.x + other.x, self.y + other.y)
+	}
+}
+
+impl Sub for FixedVec2 {
+	type Output = FixedVec2;
+
+	fn sub(self, other: FixedVec2) -> FixedVec2 {
+		FixedVec2::new(self.x - other.x, self.y - other.y)
+	}
+}
+
+impl Mul<Fixed> for FixedVec2 {
+	type Output = FixedVec2;
+
+	fn mul(self, other: Fixed) -> FixedVec2 {
+		FixedVec2::new(self.x * other, self.y * other)
+	}
+}
+
+impl Div<Fixed> for FixedVec2 {
+	type Output = FixedVec2;
+
+	fn div(self, other: Fixed) -> FixedVec2 {
+		FixedVec2::new(self.x / other, self.y / other)
+	}
+}
+
+impl AddAssign for FixedVec2 {
+	fn add_assign(&mut self, other: FixedVec2) {
+		*self = *self + other;
+	}
+}
+
+impl SubAssign for FixedVec2 {
+	fn sub_assign(&mut self, other: FixedVec2) {
+		*self = *self - other;
+	}
+}
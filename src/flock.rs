@@ -0,0 +1,35 @@
+use crate::{FLOCK_ALIGNMENT, FLOCK_COHESION, FLOCK_SEPARATION, FLOCK_SPEED, FLOCK_VISION};
+
+// every object with this tag steers like a bird in a flock: away from neighbours crowding
+// it, towards the way they're heading and towards the middle of them. they fly, so gravity
+// and wind leave them alone, but they still collide with everything else like any object
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flock {
+	pub tag: u32,
+	// only the other members within this far of it count as neighbours
+	pub vision: f32,
+	// pixels a frame squared away from a neighbour right on top of it, less the further off
+	// it is
+	pub separation: f32,
+	// how much of the difference from the neighbours' average velocity it makes up a frame
+	pub alignment: f32,
+	// and of the way to the middle of them
+	pub cohesion: f32,
+	// in pixels a frame, it speeds up or slows down towards this along wherever it's going,
+	// as quickly as it lines up with its neighbours
+	pub speed: f32,
+}
+
+impl Flock {
+	pub fn new(tag: u32) -> Self {
+		Self {
+			tag,
+			vision: FLOCK_VISION,
+			separation: FLOCK_SEPARATION,
+			alignment: FLOCK_ALIGNMENT,
+			cohesion: FLOCK_COHESION,
+			speed: FLOCK_SPEED,
+		}
+	}
+}
@@ -0,0 +1,97 @@
+use alloc::boxed::Box;
+use core::fmt::Debug;
+
+use glam::Vec2;
+
+use crate::object::VerletObject;
+use crate::pool::Pool;
+
+// anything that pushes on every object each substep, on top of Master::gravity and wind.
+// delta is the substep's share of the frame, for forces that change over time (a constant
+// one only has to accelerate() everything and update_positions() scales it). boxed in
+// Master::forces, so it has to be cloneable through clone_box() for Master to be
+pub trait Force: Debug + Send + Sync {
+	fn apply(&self, objects: &mut Pool<VerletObject>, delta: f32);
+
+	fn clone_box(&self) -> Box<dyn Force>;
+}
+
+impl Clone for Box<dyn Force> {
+	fn clone(&self) -> Self {
+		self.clone_box()
+	}
+}
+
+// the same acceleration on everything whatever it weighs, like Master::gravity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gravity(pub Vec2);
+
+impl Force for Gravity {
+	fn apply(&self, objects: &mut Pool<VerletObject>, _delta: f32) {
+		for object in objects.iter_mut() {
+			object.accelerate(self.0);
+		}
+	}
+
+	fn clone_box(&self) -> Box<dyn Force> {
+		Box::new(*self)
+	}
+}
+
+// pushes by how wide an object is to catch it over how heavy it is, so light things blow
+// away and heavy ones hardly move (Master::wind pushes everything the same)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wind(pub Vec2);
+
+impl Force for Wind {
+	fn apply(&self, objects: &mut Pool<VerletObject>, _delta: f32) {
+		for object in objects.iter_mut() {
+			if object.mass > 0.0 {
+				object.accelerate(self.0 * object.radius / object.mass);
+			}
+		}
+	}
+
+	fn clone_box(&self) -> Box<dyn Force> {
+		Box::new(*self)
+	}
+}
+
+// pulls everything within reach towards the position, by strength over the distance
+// squared, and a negative strength pushes it away instead. it stops getting any stronger
+// once an object's closer than its own radius, so nothing gets flung out of the middle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attractor {
+	pub position: Vec2,
+	pub strength: f32,
+	pub reach: f32,
+}
+
+impl Attractor {
+	pub fn new(position: Vec2, strength: f32, reach: f32) -> Self {
+		Self { position, strength, reach }
+	}
+
+	pub fn acceleration(&self, object: &VerletObject) -> Vec2 {
+		let offset = self.position - object.position;
+		let distance = offset.length();
+		if distance >= self.reach || distance == 0.0 {
+			return Vec2::ZERO;
+		}
+		let close = distance.max(object.radius);
+		offset / distance * self.strength / (close * close)
+	}
+}
+
+impl Force for Attractor {
+	fn apply(&self, objects: &mut Pool<VerletObject>, _delta: f32) {
+		for object in objects.iter_mut() {
+			let acceleration = self.acceleration(object);
+			object.accelerate(acceleration);
+		}
+	}
+
+	fn clone_box(&self) -> Box<dyn Force> {
+		Box::new(*self)
+	}
+}
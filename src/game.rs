@@ -0,0 +1,247 @@
+use std::fmt::Write as _;
+
+use macroquad::prelude::*;
+
+use verlet_integration::{Contact, Master, SensorEvent};
+
+// how fast flippers swing, in radians a frame, and how hard they can push to get there
+pub const FLIPPER_SPEED: f32 = 0.3;
+pub const FLIPPER_TORQUE: f32 = 5_000_000.0;
+
+// how hard a player's skin turns around it to roll it along, in pixels a frame squared, how
+// hard it gets pushed the way it's going on top of that (all it has to steer by in the air),
+// how fast it can get going on its own and how fast it jumps, in pixels a frame
+pub const PLAYER_ROLL: f32 = 0.4;
+pub const PLAYER_PUSH: f32 = 0.1;
+pub const PLAYER_SPEED: f32 = 5.0;
+pub const PLAYER_JUMP: f32 = 11.0;
+
+// a sensor that does something when anything goes into it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Goal {
+	pub sensor: usize,
+	pub points: i32,
+	// ends the game there and then, like a ball dropping off the bottom
+	pub lose: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+	Left,
+	Right,
+}
+
+impl Side {
+	// which way a flipper on this side turns to swing up (clockwise is positive)
+	pub fn up(&self) -> f32 {
+		match self {
+			Side::Left => -1.0,
+			Side::Right => 1.0,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Side::Left => "left",
+			Side::Right => "right",
+		}
+	}
+}
+
+// a hinge with a motor that swings up while its side's key is held and back down when it's
+// let go
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Flipper {
+	pub hinge: usize,
+	pub side: Side,
+}
+
+// a soft body steered with the move keys, see Game::steer()
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Player {
+	pub soft_body: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+	Won,
+	Lost,
+}
+
+// the rules that make a scene a game: goals that score, a score to reach and a clock. with
+// a time limit and no score to reach, lasting until the end is the win
+#[derive(Clone, Debug, Default)]
+pub struct Game {
+	pub goals: Vec<Goal>,
+	pub score: i32,
+	pub win_score: Option<i32>,
+	// in seconds
+	pub time_limit: Option<f32>,
+	// objects the mouse flings slingshot style instead of grabbing, see Launch
+	pub launchers: Vec<usize>,
+	pub flippers: Vec<Flipper>,
+	pub players: Vec<Player>,
+	pub outcome: Option<Outcome>,
+}
+
+impl Game {
+	// new_events is whether the sensor events haven't been counted yet
+	pub fn update(&mut self, master: &Master, new_events: bool) {
+		if self.outcome.is_some() {
+			return;
+		}
+
+		if new_events {
+			for event in master.sensor_events.iter() {
+				let SensorEvent::Enter(sensor, _) = *event else {
+					continue;
+				};
+				for goal in self.goals.iter().filter(|goal| goal.sensor == sensor) {
+					self.score += goal.points;
+					if goal.lose {
+						self.outcome = Some(Outcome::Lost);
+					}
+				}
+			}
+		}
+
+		if self.outcome.is_none() && self.win_score.is_some_and(|win_score| self.score >= win_score) {
+			self.outcome = Some(Outcome::Won);
+		}
+		if self.outcome.is_none() && self.time_left(master) == Some(0.0) {
+			self.outcome = Some(match self.win_score {
+				Some(_) => Outcome::Lost,
+				None => Outcome::Won,
+			});
+		}
+	}
+
+	// points every flipper's motor up or down, at whatever speed it already has
+	pub fn flip(&self, master: &mut Master, left: bool, right: bool) {
+		for flipper in self.flippers.iter() {
+			let Some(motor) = master.hinges.get_mut(flipper.hinge).and_then(|hinge| hinge.motor.as_mut()) else {
+				continue;
+			};
+			let held = match flipper.side {
+				Side::Left => left,
+				Side::Right => right,
+			};
+			let up = if held { flipper.side.up() } else { -flipper.side.up() };
+			motor.speed = motor.speed.abs() * up;
+		}
+	}
+
+	// turns every player's skin around it while a move key is held, so it rolls along whatever
+	// it's sitting on, and with jump kicks it up off whatever it's standing on. delta is how
+	// many physics frames this is for, the same as Master::update()'s
+	pub fn steer(&self, master: &mut Master, left: bool, right: bool, jump: bool, delta: f32) {
+		let direction = right as i32 as f32 - left as i32 as f32;
+		// velocities are in pixels a substep
+		let per_substep = 1.0 / master.substeps.max(1) as f32;
+
+		for player in self.players.iter() {
+			let Some(soft_body) = master.soft_bodies.get(player.soft_body) else {
+				continue;
+			};
+			let skin: Vec<usize> = soft_body.ids.iter().copied().filter(|&id| master.objects.contains_index(id)).collect();
+			if skin.is_empty() {
+				continue;
+			}
+
+			let count = skin.len() as f32;
+			let center = skin.iter().fold(Vec2::ZERO, |sum, &id| sum + master.objects[id].position) / count;
+			let velocity = skin.iter().fold(Vec2::ZERO, |sum, &id| {
+				let object = &master.objects[id];
+				sum + object.position - object.last_position
+			}) / (count * per_substep);
+			let standing = master.contacts().any(|contact| holds_up(contact, &skin));
+
+			let rolling = direction != 0.0 && velocity.x * direction < PLAYER_SPEED;
+			for &id in skin.iter() {
+				let object = &mut master.objects[id];
+				if object.kinematic {
+					continue;
+				}
+				if rolling {
+					// clockwise on screen, which rolls it right
+					let offset = object.position - center;
+					let spin = vec2(-offset.y, offset.x).normalize_or_zero() * PLAYER_ROLL + vec2(PLAYER_PUSH, 0.0);
+					object.last_position -= spin * direction * delta * per_substep;
+				}
+				if jump && standing {
+					object.last_position.y += PLAYER_JUMP * per_substep;
+				}
+			}
+		}
+	}
+
+	pub fn time_left(&self, master: &Master) -> Option<f32> {
+		// stats.time counts 60ths of a second
+		self.time_limit.map(|limit| (limit - master.stats.time / 60.0).max(0.0))
+	}
+
+	// the goal, launch, flip, steer, win and time_limit lines it came from
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+
+		for goal in self.goals.iter() {
+			let _ = write!(text, "goal {} {}", goal.sensor, goal.points);
+			if goal.lose {
+				text.push_str(" lose");
+			}
+			text.push('\n');
+		}
+		for id in self.launchers.iter() {
+			let _ = writeln!(text, "launch {}", id);
+		}
+		for flipper in self.flippers.iter() {
+			let _ = writeln!(text, "flip {} {}", flipper.hinge, flipper.side.name());
+		}
+		for player in self.players.iter() {
+			let _ = writeln!(text, "steer {}", player.soft_body);
+		}
+		if let Some(win_score) = self.win_score {
+			let _ = writeln!(text, "win {}", win_score);
+		}
+		if let Some(time_limit) = self.time_limit {
+			let _ = writeln!(text, "time_limit {}", time_limit);
+		}
+
+		text
+	}
+
+	// the score and clock across the top right, and how it ended across the middle
+	pub fn draw(&self, master: &Master) {
+		let mut status = match self.win_score {
+			Some(win_score) => format!("SCORE: {} / {}", self.score, win_score),
+			None => format!("SCORE: {}", self.score),
+		};
+		if let Some(time_left) = self.time_left(master) {
+			let _ = write!(status, "  TIME: {}", time_left.ceil());
+		}
+		let size = measure_text(&status, None, 32, 1.0);
+		draw_text(&status, screen_width() - size.width - 20.0, 30.0, 32.0, WHITE);
+
+		let Some(outcome) = self.outcome else {
+			return;
+		};
+		let (banner, color) = match outcome {
+			Outcome::Won => ("YOU WIN", GREEN),
+			Outcome::Lost => ("YOU LOSE", RED),
+		};
+		let size = measure_text(banner, None, 96, 1.0);
+		let (x, y) = ((screen_width() - size.width) * 0.5, screen_height() * 0.5);
+		draw_rectangle(0.0, y - size.height - 30.0, screen_width(), size.height + 60.0, Color::new(0.0, 0.0, 0.0, 0.6));
+		draw_text(banner, x, y, 96.0, color);
+	}
+}
+
+// whether something outside the skin is pushing some of it up, as the ground does
+fn holds_up(contact: &Contact, skin: &[usize]) -> bool {
+	let inside = |id: Option<usize>| id.is_some_and(|id| skin.contains(&id));
+	match (inside(Some(contact.a)), inside(contact.b)) {
+		(true, false) => contact.normal.y < -0.5,
+		(false, true) => contact.normal.y > 0.5,
+		_ => false,
+	}
+}
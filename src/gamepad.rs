@@ -0,0 +1,136 @@
+use gilrs::{Axis, Button, Event, GamepadId, Gilrs};
+use macroquad::prelude::*;
+
+use verlet_integration::{Master, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::camera::WorldCamera;
+use crate::Command;
+
+// sticks resting a little off center shouldn't do anything
+pub const STICK_DEADZONE: f32 = 0.15;
+// pixels per frame at full tilt
+pub const CURSOR_SPEED: f32 = 10.0;
+pub const GRAB_RADIUS: f32 = 40.0;
+pub const GRAB_FALLOFF_HOPS: usize = 2;
+// frames between spawns while A is held, like holding the mouse
+pub const SPAWN_INTERVAL: f32 = 10.0;
+// the left trigger slows time down to this, the right one speeds it up to this
+pub const SLOWEST_TIME_SCALE: f32 = 0.1;
+pub const FASTEST_TIME_SCALE: f32 = 3.0;
+
+// left stick tilts gravity, right stick moves a cursor, A spawns at the cursor, B grabs
+// whatever's under it, and the triggers slow time down or speed it up
+pub struct GamepadInput {
+	gilrs: Gilrs,
+	// the last gamepad anything came from
+	active: Option<GamepadId>,
+	pub cursor: Vec2,
+	// the gravity from before the stick was pushed, to put back once it's let go
+	rest_gravity: Option<Vec2>,
+	spawn_timer: f32,
+	grabbing: bool,
+}
+
+impl GamepadInput {
+	pub fn new() -> Option<Self> {
+		match Gilrs::new() {
+			Ok(gilrs) => Some(Self {
+				gilrs,
+				active: None,
+				cursor: vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5),
+				rest_gravity: None,
+				spawn_timer: 0.0,
+				grabbing: false,
+			}),
+			Err(error) => {
+				eprintln!("no gamepad support: {}", error);
+				None
+			}
+		}
+	}
+
+	pub fn is_connected(&self) -> bool {
+		self.active.and_then(|id| self.gilrs.connected_gamepad(id)).is_some()
+	}
+
+	// returns the time scale the triggers are asking for
+	pub fn update(&mut self, master: &mut Master, commands: &mut Vec<Command>, delta: f32) -> f32 {
+		while let Some(Event { id, .. }) = self.gilrs.next_event() {
+			self.active = Some(id);
+		}
+
+		let Some(gamepad) = self.active.and_then(|id| self.gilrs.connected_gamepad(id)) else {
+			return 1.0;
+		};
+
+		// sticks are y-up
+		let stick = |x: Axis, y: Axis| {
+			let value = vec2(gamepad.value(x), -gamepad.value(y));
+			if value.length() < STICK_DEADZONE { Vec2::ZERO } else { value.clamp_length_max(1.0) }
+		};
+		let left = stick(Axis::LeftStickX, Axis::LeftStickY);
+		let right = stick(Axis::RightStickX, Axis::RightStickY);
+		let trigger = |button: Button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+		let slow_down = trigger(Button::LeftTrigger2);
+		let speed_up = trigger(Button::RightTrigger2);
+		let spawning = gamepad.is_pressed(Button::South);
+		let grabbing = gamepad.is_pressed(Button::East);
+
+		match (left != Vec2::ZERO, self.rest_gravity) {
+			(true, None) => {
+				self.rest_gravity = Some(master.gravity);
+				master.gravity = left * master.gravity.length();
+			}
+			(true, Some(gravity)) => master.gravity = left * gravity.length(),
+			(false, Some(gravity)) => {
+				master.gravity = gravity;
+				self.rest_gravity = None;
+			}
+			(false, None) => {}
+		}
+
+		self.cursor = (self.cursor + right * CURSOR_SPEED * delta).clamp(Vec2::ZERO, vec2(WINDOW_WIDTH, WINDOW_HEIGHT));
+
+		if spawning {
+			self.spawn_timer -= delta;
+			if self.spawn_timer <= 0.0 {
+				self.spawn_timer = SPAWN_INTERVAL;
+				commands.push(Command::Spawn { position: self.cursor, radius: None, tag: 0, velocity: Vec2::ZERO });
+			}
+		} else {
+			self.spawn_timer = 0.0;
+		}
+
+		match (grabbing, self.grabbing) {
+			(true, false) => {
+				let closest = master.objects_in_radius(self.cursor, GRAB_RADIUS).into_iter()
+					.min_by(|&a, &b| {
+						let distance = |id: usize| master.objects[id].position.distance(self.cursor);
+						distance(a).total_cmp(&distance(b))
+					});
+				if let Some(id) = closest {
+					commands.push(Command::Grab { id, target: self.cursor, hops: GRAB_FALLOFF_HOPS });
+				}
+			}
+			(true, true) => {
+				if master.drag.as_ref().is_some_and(|drag| drag.target != self.cursor) {
+					commands.push(Command::MoveGrab { target: self.cursor });
+				}
+			}
+			(false, true) => commands.push(Command::Release),
+			(false, false) => {}
+		}
+		self.grabbing = grabbing;
+
+		1.0 - slow_down * (1.0 - SLOWEST_TIME_SCALE) + speed_up * (FASTEST_TIME_SCALE - 1.0)
+	}
+
+	// the cursor is in the world, but it's drawn the same size however far in it's zoomed
+	pub fn draw_cursor(&self, camera: &WorldCamera) {
+		let color = Color::new(1.0, 1.0, 1.0, 0.8);
+		let cursor = camera.world_to_screen(self.cursor);
+		draw_circle_lines(cursor.x, cursor.y, 12.0, 2.0, color);
+		draw_line(cursor.x - 18.0, cursor.y, cursor.x + 18.0, cursor.y, 2.0, color);
+		draw_line(cursor.x, cursor.y - 18.0, cursor.x, cursor.y + 18.0, 2.0, color);
+	}
+}
@@ -0,0 +1,233 @@
+use std::collections::HashSet;
+
+use macroquad::prelude::*;
+use macroquad::rand::{gen_range, srand};
+
+use verlet_integration::{VerletObject, CONSTRAINT_RADIUS, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::scene::{Scene, WALL_COLOR};
+
+// walls are rows of small static balls, overlapping enough that nothing slips between them
+pub const WALL_RADIUS: f32 = 5.0;
+pub const WALL_SPACING: f32 = 1.4;
+
+pub const MAZE_CELLS: usize = 5;
+pub const MAZE_CELL_SIZE: f32 = 80.0;
+
+pub const CAVE_CELL_SIZE: f32 = 15.0;
+pub const CAVE_FILL: f32 = 0.45;
+pub const CAVE_SMOOTHING: usize = 5;
+
+// how far below the middle the ground sits on average, and how far it goes up and down
+pub const TERRAIN_DEPTH: f32 = 120.0;
+pub const TERRAIN_HEIGHT: f32 = 90.0;
+pub const TERRAIN_OCTAVES: usize = 3;
+
+// where spawn_object() drops things, kept clear so there's always room to spawn
+fn spawn_point() -> Vec2 {
+	vec2(WINDOW_WIDTH * 0.5 + 180.0, WINDOW_HEIGHT * 0.5)
+}
+
+fn center() -> Vec2 {
+	vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Generator {
+	Maze,
+	Cave,
+	Terrain,
+}
+
+impl Generator {
+	pub fn from_name(name: &str) -> Option<Generator> {
+		match name {
+			"maze" => Some(Generator::Maze),
+			"cave" => Some(Generator::Cave),
+			"terrain" => Some(Generator::Terrain),
+			_ => None,
+		}
+	}
+
+	// the same seed always makes the same scene
+	pub fn generate(&self, seed: u64) -> Scene {
+		srand(seed);
+		let mut walls = Walls::default();
+		match self {
+			Generator::Maze => maze(&mut walls),
+			Generator::Cave => cave(&mut walls),
+			Generator::Terrain => terrain(&mut walls),
+		}
+
+		Scene {
+			objects: walls.objects,
+			..Default::default()
+		}
+	}
+}
+
+// static balls, never two in the same spot where lines meet
+#[derive(Default)]
+struct Walls {
+	objects: Vec<VerletObject>,
+	taken: HashSet<(i32, i32)>,
+}
+
+impl Walls {
+	fn ball(&mut self, position: Vec2, radius: f32) {
+		if self.taken.insert(((position.x * 10.0).round() as i32, (position.y * 10.0).round() as i32)) {
+			self.objects.push(VerletObject::new(position, radius).with_color(WALL_COLOR).kinematic());
+		}
+	}
+
+	fn line(&mut self, a: Vec2, b: Vec2) {
+		let steps = (a.distance(b) / (WALL_RADIUS * WALL_SPACING)).ceil().max(1.0) as usize;
+		for i in 0..=steps {
+			self.ball(a.lerp(b, i as f32 / steps as f32), WALL_RADIUS);
+		}
+	}
+}
+
+// a perfect maze (one way between any two cells) dug out with a depth first search, with a
+// way in at the top left and out at the bottom right
+fn maze(walls: &mut Walls) {
+	let cells = MAZE_CELLS;
+	let origin = center() - MAZE_CELLS as f32 * MAZE_CELL_SIZE * 0.5;
+	let corner = |x: usize, y: usize| origin + vec2(x as f32, y as f32) * MAZE_CELL_SIZE;
+
+	// the wall on the right of and below every cell
+	let mut right = vec![true; cells * cells];
+	let mut below = vec![true; cells * cells];
+	let mut visited = vec![false; cells * cells];
+	let mut stack = vec![(0, 0)];
+	visited[0] = true;
+
+	while let Some(&(x, y)) = stack.last() {
+		let next: Vec<(usize, usize)> = [
+			(x > 0).then(|| (x - 1, y)),
+			(x + 1 < cells).then_some((x + 1, y)),
+			(y > 0).then(|| (x, y - 1)),
+			(y + 1 < cells).then_some((x, y + 1)),
+		].into_iter().flatten().filter(|&(x, y)| !visited[y * cells + x]).collect();
+
+		if next.is_empty() {
+			stack.pop();
+			continue;
+		}
+		let (next_x, next_y) = next[gen_range(0, next.len())];
+		// knock down the wall between them, which belongs to whichever is left of or above the other
+		if next_x > x {
+			right[y * cells + x] = false;
+		} else if next_x < x {
+			right[next_y * cells + next_x] = false;
+		} else if next_y > y {
+			below[y * cells + x] = false;
+		} else {
+			below[next_y * cells + next_x] = false;
+		}
+		visited[next_y * cells + next_x] = true;
+		stack.push((next_x, next_y));
+	}
+
+	// the outside, minus the top of the first cell and the bottom of the last
+	walls.line(corner(1, 0), corner(cells, 0));
+	walls.line(corner(0, 0), corner(0, cells));
+	for y in 0..cells {
+		for x in 0..cells {
+			let exit = x + 1 == cells && y + 1 == cells;
+			if right[y * cells + x] {
+				walls.line(corner(x + 1, y), corner(x + 1, y + 1));
+			}
+			if below[y * cells + x] && !exit {
+				walls.line(corner(x, y + 1), corner(x + 1, y + 1));
+			}
+		}
+	}
+}
+
+// random rock smoothed out with a cellular automaton until it's caves, keeping only the
+// outline since nothing can get inside the rock anyway
+fn cave(walls: &mut Walls) {
+	let size = (CONSTRAINT_RADIUS * 2.0 / CAVE_CELL_SIZE).ceil() as usize;
+	let origin = center() - CONSTRAINT_RADIUS;
+	let cell_center = |x: usize, y: usize| origin + (vec2(x as f32, y as f32) + 0.5) * CAVE_CELL_SIZE;
+	// the container's edge is rock, and there's a pocket to spawn into and one in the middle
+	let forced = |x: usize, y: usize| {
+		let point = cell_center(x, y);
+		if point.distance(center()) > CONSTRAINT_RADIUS - CAVE_CELL_SIZE {
+			Some(true)
+		} else if point.distance(spawn_point()) < 60.0 || point.distance(center()) < 60.0 {
+			Some(false)
+		} else {
+			None
+		}
+	};
+
+	let mut rock: Vec<bool> = (0..size * size)
+		.map(|i| forced(i % size, i / size).unwrap_or_else(|| gen_range(0.0, 1.0) < CAVE_FILL))
+		.collect();
+
+	let is_rock = |rock: &[bool], x: i64, y: i64| {
+		x < 0 || y < 0 || x >= size as i64 || y >= size as i64 || rock[y as usize * size + x as usize]
+	};
+	for _ in 0..CAVE_SMOOTHING {
+		rock = (0..size * size).map(|i| {
+			let (x, y) = (i % size, i / size);
+			if let Some(forced) = forced(x, y) {
+				return forced;
+			}
+			let around = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+				.filter(|&(dx, dy)| (dx, dy) != (0, 0) && is_rock(&rock, x as i64 + dx, y as i64 + dy))
+				.count();
+			match around {
+				5.. => true,
+				0..=3 => false,
+				_ => rock[i],
+			}
+		}).collect();
+	}
+
+	for y in 0..size {
+		for x in 0..size {
+			let edge = rock[y * size + x]
+				&& [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|&(dx, dy)| !is_rock(&rock, x as i64 + dx, y as i64 + dy));
+			if edge && cell_center(x, y).distance(center()) < CONSTRAINT_RADIUS {
+				walls.ball(cell_center(x, y), CAVE_CELL_SIZE * 0.6);
+			}
+		}
+	}
+}
+
+// smooth random hills from a few octaves of value noise, across the whole container
+fn terrain(walls: &mut Walls) {
+	let octaves: Vec<Vec<f32>> = (0..TERRAIN_OCTAVES)
+		.map(|octave| (0..=(4 << octave)).map(|_| gen_range(-1.0, 1.0)).collect())
+		.collect();
+	// 0 to 1 across the container
+	let height = |t: f32| {
+		let mut total = 0.0;
+		let mut amplitude = 1.0;
+		for values in octaves.iter() {
+			let along = t * (values.len() - 1) as f32;
+			let i = (along as usize).min(values.len() - 2);
+			let blend = (1.0 - ((along - i as f32) * std::f32::consts::PI).cos()) * 0.5;
+			total += (values[i] + (values[i + 1] - values[i]) * blend) * amplitude;
+			amplitude *= 0.5;
+		}
+		total / (2.0 - 0.5f32.powi(TERRAIN_OCTAVES as i32 - 1))
+	};
+
+	let left = center().x - CONSTRAINT_RADIUS;
+	let steps = (CONSTRAINT_RADIUS * 2.0 / (WALL_RADIUS * 4.0)) as usize;
+	let points: Vec<Vec2> = (0..=steps).map(|i| {
+		let t = i as f32 / steps as f32;
+		vec2(left + t * CONSTRAINT_RADIUS * 2.0, center().y + TERRAIN_DEPTH + height(t) * TERRAIN_HEIGHT)
+	}).collect();
+
+	for pair in points.windows(2) {
+		// only the part inside the container, the rest could never be reached
+		if pair.iter().all(|point| point.distance(center()) < CONSTRAINT_RADIUS - WALL_RADIUS) {
+			walls.line(pair[0], pair[1]);
+		}
+	}
+}
@@ -0,0 +1,83 @@
+use macroquad::prelude::*;
+
+use crate::camera::WorldCamera;
+
+// what the grid spacing keys step through, in world units
+pub const GRID_SPACINGS: [f32; 9] = [5.0, 10.0, 20.0, 25.0, 50.0, 100.0, 200.0, 500.0, 1000.0];
+pub const GRID_SPACING: f32 = 50.0;
+// every this many lines is a brighter one with its coordinate written next to it
+pub const MAJOR_EVERY: i64 = 5;
+// lines closer together than this on screen get thinned out to every major line instead
+pub const MIN_LINE_GAP: f32 = 6.0;
+
+const MINOR_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.05);
+const MAJOR_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.12);
+const X_AXIS_COLOR: Color = Color::new(0.9, 0.35, 0.35, 0.6);
+const Y_AXIS_COLOR: Color = Color::new(0.35, 0.9, 0.45, 0.6);
+const LABEL_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.5);
+
+// one step up or down GRID_SPACINGS from wherever the spacing is now
+pub fn step_spacing(spacing: f32, wider: bool) -> f32 {
+	let index = GRID_SPACINGS.iter().position(|&other| other >= spacing).unwrap_or(GRID_SPACINGS.len() - 1);
+	let index = match wider {
+		true => (index + 1).min(GRID_SPACINGS.len() - 1),
+		false => index.saturating_sub(1),
+	};
+	GRID_SPACINGS[index]
+}
+
+// the spacing actually drawn, zoomed out far enough the lines would smear together
+fn visible_spacing(camera: &WorldCamera, spacing: f32) -> f32 {
+	let mut spacing = spacing.max(f32::EPSILON);
+	while spacing * camera.zoom < MIN_LINE_GAP {
+		spacing *= MAJOR_EVERY as f32;
+	}
+	spacing
+}
+
+// every line across the visible range, by how many spacings it is from the origin
+fn lines(from: f32, to: f32, spacing: f32) -> impl Iterator<Item = (i64, f32)> {
+	((from / spacing).floor() as i64..=(to / spacing).ceil() as i64).map(move |i| (i, i as f32 * spacing))
+}
+
+// drawn in world space, under everything else
+pub fn draw_grid(camera: &WorldCamera, spacing: f32) {
+	let visible = camera.visible();
+	let spacing = visible_spacing(camera, spacing);
+	// a pixel wide, however far in it's zoomed
+	let thickness = 1.0 / camera.zoom;
+	let color = |i: i64| if i % MAJOR_EVERY == 0 { MAJOR_COLOR } else { MINOR_COLOR };
+
+	for (i, x) in lines(visible.left(), visible.right(), spacing) {
+		draw_line(x, visible.top(), x, visible.bottom(), thickness, color(i));
+	}
+	for (i, y) in lines(visible.top(), visible.bottom(), spacing) {
+		draw_line(visible.left(), y, visible.right(), y, thickness, color(i));
+	}
+
+	draw_line(visible.left(), 0.0, visible.right(), 0.0, thickness * 2.0, X_AXIS_COLOR);
+	draw_line(0.0, visible.top(), 0.0, visible.bottom(), thickness * 2.0, Y_AXIS_COLOR);
+}
+
+// the coordinates of the major lines, along the axes or along the edge of the screen when an
+// axis is off it. drawn in screen space so the text stays the same size
+pub fn draw_grid_labels(camera: &WorldCamera, spacing: f32) {
+	let visible = camera.visible();
+	let spacing = visible_spacing(camera, spacing) * MAJOR_EVERY as f32;
+	let origin = camera.world_to_screen(Vec2::ZERO);
+	let label_y = origin.y.clamp(20.0, screen_height() - 6.0);
+	let label_x = origin.x.clamp(6.0, screen_width() - 60.0);
+
+	for (_, x) in lines(visible.left(), visible.right(), spacing) {
+		let screen_x = camera.world_to_screen(vec2(x, 0.0)).x;
+		draw_text(&format!("{}", x), screen_x + 4.0, label_y - 4.0, 20.0, LABEL_COLOR);
+	}
+	for (i, y) in lines(visible.top(), visible.bottom(), spacing) {
+		// the origin already has its label from the x axis
+		if i == 0 {
+			continue;
+		}
+		let screen_y = camera.world_to_screen(vec2(0.0, y)).y;
+		draw_text(&format!("{}", y), label_x + 4.0, screen_y - 4.0, 20.0, LABEL_COLOR);
+	}
+}
@@ -0,0 +1,73 @@
+use macroquad::prelude::*;
+
+use verlet_integration::Master;
+
+use crate::input::{Action, InputMap};
+use crate::Command;
+
+// every link group in the scene, to knock scaffolding out or put it back while it runs. up
+// and down pick one, enter switches it and escape closes the list. the switching goes out as
+// commands, so it's recorded like anything else done from outside
+#[derive(Clone, Debug, Default)]
+pub struct GroupPanel {
+	pub open: bool,
+	selected: usize,
+}
+
+impl GroupPanel {
+	pub fn update(&mut self, input: &mut InputMap, master: &Master, commands: &mut Vec<Command>) {
+		if !self.open {
+			if input.pressed(Action::LinkGroups) {
+				self.open = true;
+			}
+			input.suspended |= self.open;
+			return;
+		}
+
+		let count = master.groups.len();
+		self.selected = self.selected.min(count.saturating_sub(1));
+		if is_key_pressed(KeyCode::Escape) || input.binding(Action::LinkGroups).is_pressed() {
+			self.open = false;
+		} else if count > 0 {
+			if is_key_pressed(KeyCode::Up) {
+				self.selected = (self.selected + count - 1) % count;
+			} else if is_key_pressed(KeyCode::Down) {
+				self.selected = (self.selected + 1) % count;
+			} else if is_key_pressed(KeyCode::Enter) {
+				let enabled = !master.groups[self.selected].enabled;
+				commands.push(Command::SetGroup { group: self.selected, enabled });
+			}
+		}
+
+		input.suspended |= self.open;
+	}
+
+	// only across the top, so whatever the groups hold up stays in sight
+	pub fn draw(&self, master: &Master) {
+		if !self.open {
+			return;
+		}
+
+		let rows = master.groups.len().max(1);
+		draw_rectangle(0.0, 0.0, screen_width(), 50.0 + rows as f32 * 34.0, Color::new(0.0, 0.0, 0.0, 0.8));
+		draw_text("LINK GROUPS (ENTER TO SWITCH ONE OFF OR ON)", 20.0, 30.0, 24.0, WHITE);
+
+		if master.groups.is_empty() {
+			draw_text("NONE IN THIS SCENE", 20.0, 70.0, 28.0, GRAY);
+		}
+		for (i, group) in master.groups.iter().enumerate() {
+			let color = match (i == self.selected, group.enabled) {
+				(true, _) => YELLOW,
+				(false, true) => WHITE,
+				(false, false) => GRAY,
+			};
+			draw_text(
+				&format!("{}  {}", group.name, if group.enabled { "ON" } else { "OFF" }),
+				20.0,
+				70.0 + i as f32 * 34.0,
+				28.0,
+				color,
+			);
+		}
+	}
+}
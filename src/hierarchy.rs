@@ -0,0 +1,228 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use glam::Vec2;
+
+use crate::link::{ChainLink, LinkGroup};
+use crate::object::VerletObject;
+use crate::pool::Pool;
+
+// a coarse copy of a big cloth or chain, solved before its own links so stretching that'd
+// take the links dozens of substeps to pull back out comes out in one. the objects are split
+// into clusters around one every stride links or so, each cluster is a single proxy at its
+// middle (by mass) and neighbouring proxies are kept from getting further apart than they
+// were when it was built. whatever a proxy moves by, everything in its cluster moves by too,
+// and the fine links smooth out the steps between clusters after. like the long range
+// attachments it only ever pulls in, so the cloth still folds and bunches up
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hierarchy {
+	// everything it's over, see Master::add_hierarchy()
+	pub ids: Vec<usize>,
+	// about how many links apart the proxies are
+	pub stride: usize,
+	// how many times the coarse links are gone over each substep
+	pub iterations: usize,
+	// the objects in each proxy's cluster, and the links between proxies, worked out by build()
+	pub clusters: Vec<Vec<usize>>,
+	pub links: Vec<CoarseLink>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoarseLink {
+	// clusters
+	pub a: usize,
+	pub b: usize,
+	pub target_distance: f32,
+	// every fine link from one cluster to the other. it only holds while one of them is still
+	// there and switched on, so tearing the cloth tears this too
+	pub crossing: Vec<Crossing>,
+}
+
+// a link's id with the objects it was between, so it doesn't count if something else takes
+// its slot
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Crossing {
+	pub link: usize,
+	pub a: usize,
+	pub b: usize,
+}
+
+// what solve() works in, kept between calls so solving doesn't allocate
+#[derive(Clone, Debug, Default)]
+pub struct HierarchyScratch {
+	pub proxies: Vec<(Vec2, f32)>,
+	pub positions: Vec<Vec2>,
+	// the coarse links still holding, by index into Hierarchy::links
+	pub holding: Vec<usize>,
+}
+
+impl HierarchyScratch {
+	// enough room to solve this one
+	pub fn reserve(&mut self, hierarchy: &Hierarchy) {
+		let clusters = hierarchy.clusters.len();
+		self.proxies.reserve(clusters.saturating_sub(self.proxies.len()));
+		self.positions.reserve(clusters.saturating_sub(self.positions.len()));
+		self.holding.reserve(hierarchy.links.len().saturating_sub(self.holding.len()));
+	}
+}
+
+impl Hierarchy {
+	// nothing worked out yet, build() does that
+	pub fn new(ids: Vec<usize>, stride: usize, iterations: usize) -> Self {
+		Self { ids, stride, iterations, clusters: vec![], links: vec![] }
+	}
+
+	// splits it into clusters along the links that are switched on, with the proxies resting
+	// as far apart as they are now
+	pub fn build(&mut self, objects: &Pool<VerletObject>, chain_links: &Pool<ChainLink>, groups: &[LinkGroup]) {
+		let slots = objects.slot_count();
+		let mut inside = vec![false; slots];
+		for &id in self.ids.iter().filter(|&&id| id < slots) {
+			inside[id] = true;
+		}
+		let fine: Vec<(usize, &ChainLink)> = chain_links.indexed()
+			.filter(|(_, chain_link)| chain_link.is_enabled(groups) && inside[chain_link.a] && inside[chain_link.b])
+			.collect();
+		let mut neighbours = vec![vec![]; slots];
+		for (_, chain_link) in fine.iter() {
+			neighbours[chain_link.a].push(chain_link.b);
+			neighbours[chain_link.b].push(chain_link.a);
+		}
+
+		// anything more than stride - 1 links from every proxy so far gets one of its own
+		let mut seeds = vec![];
+		let mut covered = vec![false; slots];
+		let mut reached = vec![usize::MAX; slots];
+		for &id in self.ids.iter().filter(|&&id| id < slots) {
+			if covered[id] {
+				continue;
+			}
+			let seed = seeds.len();
+			seeds.push(id);
+			covered[id] = true;
+			reached[id] = seed;
+			let mut frontier = vec![id];
+			for _ in 1..self.stride {
+				let mut next = vec![];
+				for &from in frontier.iter() {
+					for &to in neighbours[from].iter() {
+						if reached[to] != seed {
+							reached[to] = seed;
+							covered[to] = true;
+							next.push(to);
+						}
+					}
+				}
+				frontier = next;
+			}
+		}
+
+		// then everything goes to whichever proxy is the fewest links away
+		let mut cluster = vec![usize::MAX; slots];
+		for (i, &seed) in seeds.iter().enumerate() {
+			cluster[seed] = i;
+		}
+		let mut frontier = seeds.clone();
+		while !frontier.is_empty() {
+			let mut next = vec![];
+			for &from in frontier.iter() {
+				for &to in neighbours[from].iter() {
+					if cluster[to] == usize::MAX {
+						cluster[to] = cluster[from];
+						next.push(to);
+					}
+				}
+			}
+			frontier = next;
+		}
+		self.clusters = vec![vec![]; seeds.len()];
+		for &id in self.ids.iter().filter(|&&id| id < slots && cluster[id] != usize::MAX) {
+			self.clusters[cluster[id]].push(id);
+		}
+
+		let mut crossings: BTreeMap<(usize, usize), Vec<Crossing>> = BTreeMap::new();
+		for &(id, chain_link) in fine.iter() {
+			let (a, b) = (cluster[chain_link.a], cluster[chain_link.b]);
+			if a != b {
+				crossings.entry((a.min(b), a.max(b))).or_default().push(Crossing { link: id, a: chain_link.a, b: chain_link.b });
+			}
+		}
+		let mut proxies = vec![];
+		self.proxies(objects, &mut proxies);
+		self.links = crossings.into_iter()
+			.map(|((a, b), crossing)| CoarseLink { a, b, target_distance: proxies[a].0.distance(proxies[b].0), crossing })
+			.collect();
+	}
+
+	// where each proxy is and how easily it moves, which is not at all with anything pinned
+	// in its cluster
+	pub fn proxies(&self, objects: &Pool<VerletObject>, proxies: &mut Vec<(Vec2, f32)>) {
+		proxies.clear();
+		proxies.extend(self.clusters.iter()
+			.map(|cluster| {
+				let (mut sum, mut mass, mut pinned) = (Vec2::ZERO, 0.0, false);
+				for object in cluster.iter().filter_map(|&id| objects.get(id)) {
+					sum += object.position * object.mass;
+					mass += object.mass;
+					pinned |= object.kinematic;
+				}
+				if mass <= f32::EPSILON {
+					return (Vec2::ZERO, 0.0);
+				}
+				(sum / mass, if pinned { 0.0 } else { 1.0 / mass })
+			}));
+	}
+
+	// pulls the proxies back in as far as stiffness says (like Master::link_stiffness) and
+	// moves every object with its proxy
+	pub fn solve(&self, objects: &mut Pool<VerletObject>, chain_links: &Pool<ChainLink>, groups: &[LinkGroup], stiffness: f32, scratch: &mut HierarchyScratch) {
+		let HierarchyScratch { proxies, positions, holding } = scratch;
+		self.proxies(objects, proxies);
+		positions.clear();
+		positions.extend(proxies.iter().map(|proxy| proxy.0));
+		holding.clear();
+		holding.extend(self.links.iter().enumerate()
+			.filter(|(_, link)| link.crossing.iter().any(|crossing| {
+				chain_links.get(crossing.link).is_some_and(|chain_link| chain_link.a == crossing.a && chain_link.b == crossing.b && chain_link.is_enabled(groups))
+			}))
+			.map(|(i, _)| i));
+
+		for _ in 0..self.iterations {
+			for link in holding.iter().map(|&i| &self.links[i]) {
+				let (weight_a, weight_b) = (proxies[link.a].1, proxies[link.b].1);
+				let axis = positions[link.a] - positions[link.b];
+				let distance = axis.length();
+				if distance <= link.target_distance || weight_a + weight_b <= 0.0 {
+					continue;
+				}
+				let correction = axis / distance * (distance - link.target_distance) * stiffness / (weight_a + weight_b);
+				positions[link.a] -= correction * weight_a;
+				positions[link.b] += correction * weight_b;
+			}
+		}
+
+		for (i, cluster) in self.clusters.iter().enumerate() {
+			let shift = positions[i] - proxies[i].0;
+			if shift == Vec2::ZERO {
+				continue;
+			}
+			for &id in cluster.iter() {
+				if let Some(object) = objects.get_mut(id).filter(|object| !object.kinematic) {
+					object.position += shift;
+				}
+			}
+		}
+	}
+
+	// so nothing that moves into its slot later gets dragged around with it
+	pub fn forget(&mut self, id: usize) {
+		self.ids.retain(|&other| other != id);
+		for cluster in self.clusters.iter_mut() {
+			cluster.retain(|&other| other != id);
+		}
+	}
+}
@@ -0,0 +1,52 @@
+use macroquad::prelude::*;
+
+use verlet_integration::Master;
+
+pub const HISTOGRAM_BINS: usize = 20;
+pub const HISTOGRAM_SIZE: Vec2 = Vec2::new(260.0, 90.0);
+
+// how many of the values land in each of count equal bins from 0 to the biggest value, and
+// that biggest value
+pub fn bins(values: impl Iterator<Item = f32>, count: usize) -> (Vec<usize>, f32) {
+	let values: Vec<f32> = values.collect();
+	let most = values.iter().copied().fold(0.0, f32::max);
+	let mut bins = vec![0; count.max(1)];
+
+	for value in values {
+		let bin = (value / most.max(f32::EPSILON) * bins.len() as f32) as usize;
+		bins[bin.min(count.max(1) - 1)] += 1;
+	}
+
+	(bins, most)
+}
+
+// one bar chart in the top right corner of the screen, slot counts down
+fn draw_histogram(slot: usize, title: &str, (bins, most): (Vec<usize>, f32), color: Color) {
+	let corner = vec2(screen_width() - HISTOGRAM_SIZE.x - 20.0, 20.0 + slot as f32 * (HISTOGRAM_SIZE.y + 40.0));
+	draw_rectangle(corner.x, corner.y, HISTOGRAM_SIZE.x, HISTOGRAM_SIZE.y, Color::new(0.0, 0.0, 0.0, 0.6));
+	draw_rectangle_lines(corner.x, corner.y, HISTOGRAM_SIZE.x, HISTOGRAM_SIZE.y, 1.0, GRAY);
+
+	let tallest = bins.iter().copied().max().unwrap_or(0).max(1) as f32;
+	let width = HISTOGRAM_SIZE.x / bins.len() as f32;
+	for (i, &count) in bins.iter().enumerate() {
+		let height = count as f32 / tallest * HISTOGRAM_SIZE.y;
+		draw_rectangle(corner.x + i as f32 * width + 1.0, corner.y + HISTOGRAM_SIZE.y - height, width - 2.0, height, color);
+	}
+
+	draw_text(
+		&format!("{} (0 TO {:.1}, TALLEST {})", title, most, tallest),
+		corner.x,
+		corner.y + HISTOGRAM_SIZE.y + 20.0,
+		20.0,
+		WHITE,
+	);
+}
+
+// how fast everything is going (per frame) and how big everything is, right now
+pub fn draw_histograms(master: &Master) {
+	let speeds = master.objects.iter().map(|object| (object.position - object.last_position).length());
+	draw_histogram(0, "SPEED", bins(speeds, HISTOGRAM_BINS), Color::new(1.0, 0.85, 0.3, 0.8));
+
+	let radii = master.objects.iter().map(|object| object.radius);
+	draw_histogram(1, "RADIUS", bins(radii, HISTOGRAM_BINS), Color::new(0.3, 0.85, 1.0, 0.8));
+}
@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+// everything the keyboard and mouse can do in the demo
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+	Spawn,
+	// blows everything around the mouse away, or with alternate held takes out the object
+	// under it
+	Explode,
+	// held with spawn for crates, or with the point cloud recorder for obj
+	Alternate,
+	MoreSpawns,
+	FewerSpawns,
+	Glow,
+	Pixel,
+	SaveSvg,
+	RecordPointCloud,
+	ColorMode,
+	Ropes,
+	SoftBodies,
+	Cloth,
+	ImpactEffects,
+	ReducedMotion,
+	HighContrast,
+	Palette,
+	Glue,
+	DebugOverlay,
+	BiggerCells,
+	SmallerCells,
+	LongRange,
+	FrameBudget,
+	Tilt,
+	NBody,
+	Automations,
+	Tuning,
+	Pause,
+	// one physics frame on, while it's paused
+	Step,
+	Faster,
+	Slower,
+	TimeReversal,
+	FpsCap,
+	Vsync,
+	Measure,
+	// with alternate held it shows the force too
+	Plot,
+	Histograms,
+	Grid,
+	WiderGrid,
+	NarrowerGrid,
+	// magnets' field lines
+	FieldLines,
+	// held to drag the camera around
+	Pan,
+	ResetCamera,
+	Checkpoints,
+	// the list of link groups, for switching them off and on
+	LinkGroups,
+	Chaos,
+	// cycles through the contraption editor's tools
+	Editor,
+	SaveScene,
+	// the scene from its file again
+	ReloadScene,
+	// starts recording everything done to the simulation from outside, or stops and saves it
+	Record,
+	// from the start of the last recording
+	Replay,
+	Bindings,
+	// a game's flippers
+	FlipLeft,
+	FlipRight,
+	// and its players
+	MoveLeft,
+	MoveRight,
+	Jump,
+	// the picked cannon, see CannonControls
+	RaiseAim,
+	LowerAim,
+	MorePower,
+	LessPower,
+	// with alternate held it picks the next cannon instead
+	Fire,
+}
+
+impl Action {
+	pub const ALL: [Action; 62] = [
+		Action::Spawn,
+		Action::Explode,
+		Action::Alternate,
+		Action::MoreSpawns,
+		Action::FewerSpawns,
+		Action::Glow,
+		Action::Pixel,
+		Action::SaveSvg,
+		Action::RecordPointCloud,
+		Action::ColorMode,
+		Action::Ropes,
+		Action::SoftBodies,
+		Action::Cloth,
+		Action::ImpactEffects,
+		Action::ReducedMotion,
+		Action::HighContrast,
+		Action::Palette,
+		Action::Glue,
+		Action::DebugOverlay,
+		Action::BiggerCells,
+		Action::SmallerCells,
+		Action::LongRange,
+		Action::FrameBudget,
+		Action::Tilt,
+		Action::NBody,
+		Action::Automations,
+		Action::Tuning,
+		Action::Pause,
+		Action::Step,
+		Action::Faster,
+		Action::Slower,
+		Action::TimeReversal,
+		Action::FpsCap,
+		Action::Vsync,
+		Action::Measure,
+		Action::Plot,
+		Action::Histograms,
+		Action::Grid,
+		Action::WiderGrid,
+		Action::NarrowerGrid,
+		Action::FieldLines,
+		Action::Pan,
+		Action::ResetCamera,
+		Action::Checkpoints,
+		Action::LinkGroups,
+		Action::Chaos,
+		Action::Editor,
+		Action::SaveScene,
+		Action::ReloadScene,
+		Action::Record,
+		Action::Replay,
+		Action::Bindings,
+		Action::FlipLeft,
+		Action::FlipRight,
+		Action::MoveLeft,
+		Action::MoveRight,
+		Action::Jump,
+		Action::RaiseAim,
+		Action::LowerAim,
+		Action::MorePower,
+		Action::LessPower,
+		Action::Fire,
+	];
+
+	// what it's called in the settings file
+	pub fn name(&self) -> &'static str {
+		match self {
+			Action::Spawn => "spawn",
+			Action::Explode => "explode",
+			Action::Alternate => "alternate",
+			Action::MoreSpawns => "more_spawns",
+			Action::FewerSpawns => "fewer_spawns",
+			Action::Glow => "glow",
+			Action::Pixel => "pixel",
+			Action::SaveSvg => "save_svg",
+			Action::RecordPointCloud => "record_point_cloud",
+			Action::ColorMode => "color_mode",
+			Action::Ropes => "ropes",
+			Action::SoftBodies => "soft_bodies",
+			Action::Cloth => "cloth",
+			Action::ImpactEffects => "impact_effects",
+			Action::ReducedMotion => "reduced_motion",
+			Action::HighContrast => "high_contrast",
+			Action::Palette => "palette",
+			Action::Glue => "glue",
+			Action::DebugOverlay => "debug_overlay",
+			Action::BiggerCells => "bigger_cells",
+			Action::SmallerCells => "smaller_cells",
+			Action::LongRange => "long_range",
+			Action::FrameBudget => "frame_budget",
+			Action::Tilt => "tilt",
+			Action::NBody => "n_body",
+			Action::Automations => "automations",
+			Action::Tuning => "tuning",
+			Action::Pause => "pause",
+			Action::Step => "step",
+			Action::Faster => "faster",
+			Action::Slower => "slower",
+			Action::TimeReversal => "time_reversal",
+			Action::FpsCap => "fps_cap",
+			Action::Vsync => "vsync",
+			Action::Measure => "measure",
+			Action::Plot => "plot",
+			Action::Histograms => "histograms",
+			Action::Grid => "grid",
+			Action::WiderGrid => "wider_grid",
+			Action::NarrowerGrid => "narrower_grid",
+			Action::FieldLines => "field_lines",
+			Action::Pan => "pan",
+			Action::ResetCamera => "reset_camera",
+			Action::Checkpoints => "checkpoints",
+			Action::LinkGroups => "link_groups",
+			Action::Chaos => "chaos",
+			Action::Editor => "editor",
+			Action::SaveScene => "save_scene",
+			Action::ReloadScene => "reload_scene",
+			Action::Record => "record",
+			Action::Replay => "replay",
+			Action::Bindings => "bindings",
+			Action::FlipLeft => "flip_left",
+			Action::FlipRight => "flip_right",
+			Action::MoveLeft => "move_left",
+			Action::MoveRight => "move_right",
+			Action::Jump => "jump",
+			Action::RaiseAim => "raise_aim",
+			Action::LowerAim => "lower_aim",
+			Action::MorePower => "more_power",
+			Action::LessPower => "less_power",
+			Action::Fire => "fire",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Action> {
+		Action::ALL.into_iter().find(|action| action.name() == name)
+	}
+
+	pub fn default_binding(&self) -> Binding {
+		match self {
+			Action::Spawn => Binding::Mouse(MouseButton::Left),
+			Action::Explode => Binding::Mouse(MouseButton::Right),
+			Action::Alternate => Binding::Key(KeyCode::LeftShift),
+			Action::MoreSpawns => Binding::Key(KeyCode::RightBracket),
+			Action::FewerSpawns => Binding::Key(KeyCode::LeftBracket),
+			Action::Glow => Binding::Key(KeyCode::B),
+			Action::Pixel => Binding::Key(KeyCode::P),
+			Action::SaveSvg => Binding::Key(KeyCode::F2),
+			Action::RecordPointCloud => Binding::Key(KeyCode::F3),
+			Action::ColorMode => Binding::Key(KeyCode::V),
+			Action::Ropes => Binding::Key(KeyCode::K),
+			Action::SoftBodies => Binding::Key(KeyCode::J),
+			Action::Cloth => Binding::Key(KeyCode::M),
+			Action::ImpactEffects => Binding::Key(KeyCode::X),
+			Action::ReducedMotion => Binding::Key(KeyCode::F5),
+			Action::HighContrast => Binding::Key(KeyCode::F6),
+			Action::Palette => Binding::Key(KeyCode::C),
+			Action::Glue => Binding::Key(KeyCode::G),
+			Action::DebugOverlay => Binding::Key(KeyCode::F1),
+			Action::BiggerCells => Binding::Key(KeyCode::Equal),
+			Action::SmallerCells => Binding::Key(KeyCode::Minus),
+			Action::LongRange => Binding::Key(KeyCode::L),
+			Action::FrameBudget => Binding::Key(KeyCode::F4),
+			Action::Tilt => Binding::Key(KeyCode::Y),
+			Action::NBody => Binding::Key(KeyCode::Semicolon),
+			Action::Automations => Binding::Key(KeyCode::Apostrophe),
+			Action::Tuning => Binding::Key(KeyCode::GraveAccent),
+			Action::Pause => Binding::Key(KeyCode::Space),
+			Action::Step => Binding::Key(KeyCode::Period),
+			Action::Faster => Binding::Key(KeyCode::PageUp),
+			Action::Slower => Binding::Key(KeyCode::PageDown),
+			Action::TimeReversal => Binding::Key(KeyCode::T),
+			Action::FpsCap => Binding::Key(KeyCode::F8),
+			Action::Vsync => Binding::Key(KeyCode::F9),
+			Action::Measure => Binding::Key(KeyCode::U),
+			Action::Plot => Binding::Key(KeyCode::O),
+			Action::Histograms => Binding::Key(KeyCode::I),
+			Action::Grid => Binding::Key(KeyCode::H),
+			Action::WiderGrid => Binding::Key(KeyCode::Key0),
+			Action::NarrowerGrid => Binding::Key(KeyCode::Key9),
+			Action::FieldLines => Binding::Key(KeyCode::Q),
+			Action::Pan => Binding::Mouse(MouseButton::Middle),
+			Action::ResetCamera => Binding::Key(KeyCode::Home),
+			Action::Checkpoints => Binding::Key(KeyCode::F10),
+			Action::LinkGroups => Binding::Key(KeyCode::F),
+			Action::Chaos => Binding::Key(KeyCode::F11),
+			Action::Editor => Binding::Key(KeyCode::E),
+			Action::SaveScene => Binding::Key(KeyCode::F12),
+			Action::ReloadScene => Binding::Key(KeyCode::R),
+			Action::Record => Binding::Key(KeyCode::Backslash),
+			Action::Replay => Binding::Key(KeyCode::N),
+			Action::Bindings => Binding::Key(KeyCode::F7),
+			Action::FlipLeft => Binding::Key(KeyCode::Z),
+			Action::FlipRight => Binding::Key(KeyCode::Slash),
+			Action::MoveLeft => Binding::Key(KeyCode::Left),
+			Action::MoveRight => Binding::Key(KeyCode::Right),
+			Action::Jump => Binding::Key(KeyCode::Up),
+			Action::RaiseAim => Binding::Key(KeyCode::W),
+			Action::LowerAim => Binding::Key(KeyCode::S),
+			Action::MorePower => Binding::Key(KeyCode::D),
+			Action::LessPower => Binding::Key(KeyCode::A),
+			Action::Fire => Binding::Key(KeyCode::Enter),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+	Key(KeyCode),
+	Mouse(MouseButton),
+}
+
+// every key that can be bound, so they can be found by name
+const KEYS: [KeyCode; 105] = [
+	KeyCode::Space, KeyCode::Apostrophe, KeyCode::Comma, KeyCode::Minus, KeyCode::Period,
+	KeyCode::Slash, KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+	KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9, KeyCode::Semicolon,
+	KeyCode::Equal, KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F,
+	KeyCode::G, KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+	KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V,
+	KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z, KeyCode::LeftBracket, KeyCode::Backslash,
+	KeyCode::RightBracket, KeyCode::GraveAccent, KeyCode::Escape, KeyCode::Enter, KeyCode::Tab,
+	KeyCode::Backspace, KeyCode::Insert, KeyCode::Delete, KeyCode::Right, KeyCode::Left,
+	KeyCode::Down, KeyCode::Up, KeyCode::PageUp, KeyCode::PageDown, KeyCode::Home, KeyCode::End,
+	KeyCode::CapsLock, KeyCode::ScrollLock, KeyCode::NumLock, KeyCode::PrintScreen, KeyCode::Pause,
+	KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7,
+	KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12, KeyCode::Kp0, KeyCode::Kp1,
+	KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4, KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8,
+	KeyCode::Kp9, KeyCode::KpDecimal, KeyCode::KpDivide, KeyCode::KpMultiply, KeyCode::KpSubtract,
+	KeyCode::KpAdd, KeyCode::KpEnter, KeyCode::KpEqual, KeyCode::LeftShift, KeyCode::LeftControl,
+	KeyCode::LeftAlt, KeyCode::LeftSuper, KeyCode::RightShift, KeyCode::RightControl,
+	KeyCode::RightAlt, KeyCode::RightSuper, KeyCode::Menu,
+];
+
+const MOUSE_BUTTONS: [(MouseButton, &str); 3] = [
+	(MouseButton::Left, "MouseLeft"),
+	(MouseButton::Right, "MouseRight"),
+	(MouseButton::Middle, "MouseMiddle"),
+];
+
+impl Binding {
+	// the key's name as macroquad spells it (LeftBracket, F1, Key0), or MouseLeft and so on
+	pub fn name(&self) -> String {
+		match self {
+			Binding::Key(key) => format!("{:?}", key),
+			Binding::Mouse(button) => MOUSE_BUTTONS.iter()
+				.find(|(other, _)| other == button)
+				.map_or("MouseUnknown", |(_, name)| name)
+				.to_string(),
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Binding> {
+		if let Some(&(button, _)) = MOUSE_BUTTONS.iter().find(|(_, other)| *other == name) {
+			return Some(Binding::Mouse(button));
+		}
+		KEYS.into_iter().find(|key| format!("{:?}", key) == name).map(Binding::Key)
+	}
+
+	pub fn is_pressed(&self) -> bool {
+		match *self {
+			Binding::Key(key) => is_key_pressed(key),
+			Binding::Mouse(button) => is_mouse_button_pressed(button),
+		}
+	}
+
+	pub fn is_down(&self) -> bool {
+		match *self {
+			Binding::Key(key) => is_key_down(key),
+			Binding::Mouse(button) => is_mouse_button_down(button),
+		}
+	}
+}
+
+// which key or button does what. actions that aren't in the map use their default
+#[derive(Clone, Debug, Default)]
+pub struct InputMap {
+	pub bindings: HashMap<Action, Binding>,
+	// while the bindings screen is open nothing counts as pressed
+	pub suspended: bool,
+}
+
+// suspended is only for this run, so it doesn't count
+impl PartialEq for InputMap {
+	fn eq(&self, other: &Self) -> bool {
+		Action::ALL.iter().all(|action| self.binding(*action) == other.binding(*action))
+	}
+}
+
+impl InputMap {
+	pub fn binding(&self, action: Action) -> Binding {
+		self.bindings.get(&action).copied().unwrap_or(action.default_binding())
+	}
+
+	pub fn pressed(&self, action: Action) -> bool {
+		!self.suspended && self.binding(action).is_pressed()
+	}
+
+	pub fn down(&self, action: Action) -> bool {
+		!self.suspended && self.binding(action).is_down()
+	}
+
+	// some other action that's already on this key or button
+	pub fn taken_by(&self, action: Action, binding: Binding) -> Option<Action> {
+		Action::ALL.into_iter().find(|&other| other != action && self.binding(other) == binding)
+	}
+
+	// puts the default back for anything rebound onto a key or button that something else is
+	// already on, until no two actions share one. returns the ones it put back, with what they
+	// were on
+	pub fn drop_duplicates(&mut self) -> Vec<(Action, Binding)> {
+		let mut dropped = vec![];
+		while let Some(action) = Action::ALL.into_iter().find(|&action| self.bindings.contains_key(&action) && self.taken_by(action, self.binding(action)).is_some()) {
+			dropped.push((action, self.binding(action)));
+			self.bindings.remove(&action);
+		}
+		dropped
+	}
+}
+
+// lists every action with what it's bound to. up and down pick one, enter waits for the
+// next key or mouse button to bind it to, backspace puts the default back and escape closes.
+// a key that's already on something else isn't taken, it has to be freed up first
+#[derive(Clone, Debug, Default)]
+pub struct BindingsScreen {
+	pub open: bool,
+	selected: usize,
+	waiting: bool,
+	// what the last key tried was already on, and the key
+	taken: Option<(Action, Binding)>,
+}
+
+impl BindingsScreen {
+	pub fn update(&mut self, input: &mut InputMap) {
+		if !self.open {
+			if input.pressed(Action::Bindings) {
+				self.open = true;
+			}
+			input.suspended = self.open;
+			return;
+		}
+
+		let action = Action::ALL[self.selected];
+		if self.waiting {
+			let pressed = get_last_key_pressed().map(Binding::Key).or_else(|| {
+				MOUSE_BUTTONS.iter()
+					.map(|&(button, _)| Binding::Mouse(button))
+					.find(Binding::is_pressed)
+			});
+
+			match pressed {
+				Some(Binding::Key(KeyCode::Escape)) => {
+					self.waiting = false;
+					self.taken = None;
+				}
+				Some(binding) => match input.taken_by(action, binding) {
+					Some(other) => self.taken = Some((other, binding)),
+					None => {
+						input.bindings.insert(action, binding);
+						self.waiting = false;
+						self.taken = None;
+					}
+				},
+				None => {}
+			}
+		} else if is_key_pressed(KeyCode::Escape) || input.binding(Action::Bindings).is_pressed() {
+			self.open = false;
+		} else if is_key_pressed(KeyCode::Up) {
+			self.selected = (self.selected + Action::ALL.len() - 1) % Action::ALL.len();
+		} else if is_key_pressed(KeyCode::Down) {
+			self.selected = (self.selected + 1) % Action::ALL.len();
+		} else if is_key_pressed(KeyCode::Enter) {
+			self.waiting = true;
+		} else if is_key_pressed(KeyCode::Backspace) && input.taken_by(action, action.default_binding()).is_none() {
+			input.bindings.remove(&action);
+		}
+
+		input.suspended = self.open;
+	}
+
+	pub fn draw(&self, input: &InputMap) {
+		if !self.open {
+			return;
+		}
+
+		draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+		draw_text("BINDINGS (ENTER TO CHANGE, BACKSPACE FOR DEFAULT, ESCAPE TO CLOSE)", 20.0, 30.0, 24.0, WHITE);
+		if let Some((other, binding)) = self.taken.filter(|_| self.waiting) {
+			draw_text(&format!("{} IS ALREADY {}", binding.name(), other.name()).to_uppercase(), 20.0, screen_height() - 20.0, 24.0, RED);
+		}
+
+		for (i, action) in Action::ALL.iter().enumerate() {
+			let binding = if i == self.selected && self.waiting { "...".to_string() } else { input.binding(*action).name() };
+			let color = if i == self.selected { YELLOW } else { WHITE };
+			// two columns so it all fits
+			let rows = Action::ALL.len().div_ceil(2);
+			let (column, row) = (i / rows, i % rows);
+			draw_text(
+				&format!("{}: {}", action.name(), binding),
+				20.0 + column as f32 * 460.0,
+				70.0 + row as f32 * 28.0,
+				24.0,
+				color,
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn every_action_has_its_own_default() {
+		let input = InputMap::default();
+		for action in Action::ALL {
+			assert_eq!(input.taken_by(action, action.default_binding()), None, "{} shares its default", action.name());
+		}
+	}
+
+	#[test]
+	fn rebinding_onto_a_taken_key_is_dropped() {
+		let mut input = InputMap::default();
+		input.bindings.insert(Action::Glow, Action::Pixel.default_binding());
+		// swapped around is fine, nothing ends up sharing
+		input.bindings.insert(Action::Ropes, Action::Cloth.default_binding());
+		input.bindings.insert(Action::Cloth, Action::Ropes.default_binding());
+		assert_eq!(input.drop_duplicates(), vec![(Action::Glow, Action::Pixel.default_binding())]);
+		assert_eq!(input.binding(Action::Glow), Action::Glow.default_binding());
+		assert_eq!(input.binding(Action::Ropes), Action::Cloth.default_binding());
+	}
+}
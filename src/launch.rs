@@ -0,0 +1,76 @@
+use macroquad::prelude::*;
+
+use verlet_integration::{Master, ObjectId};
+
+// how fast a launch goes for each pixel the mouse is pulled back, and the fastest it goes
+pub const LAUNCH_STRENGTH: f32 = 0.05;
+pub const LAUNCH_MAX_SPEED: f32 = 9.0;
+// how far ahead the preview looks, in frames, and how many frames apart its dots are
+pub const LAUNCH_PREVIEW_FRAMES: usize = 90;
+pub const LAUNCH_PREVIEW_EVERY: usize = 3;
+
+// slingshot style: pull back from the object and let go to fling it the other way. it's held
+// where it was grabbed until then
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Launch {
+	pub id: ObjectId,
+	pub anchor: Vec2,
+}
+
+impl Launch {
+	// None when there's nothing there
+	pub fn new(master: &Master, index: usize) -> Option<Self> {
+		let id = master.object_id(index)?;
+		Some(Self { id, anchor: master.object(id)?.position })
+	}
+
+	// as position - last_position, so per substep
+	pub fn velocity(&self, mouse: Vec2) -> Vec2 {
+		((self.anchor - mouse) * LAUNCH_STRENGTH).clamp_length_max(LAUNCH_MAX_SPEED)
+	}
+
+	pub fn hold(&self, master: &mut Master) {
+		let Some(object) = master.object_mut(self.id) else {
+			return;
+		};
+		object.position = self.anchor;
+		object.last_position = self.anchor;
+	}
+
+	pub fn release(&self, master: &mut Master, mouse: Vec2) {
+		let Some(object) = master.object_mut(self.id) else {
+			return;
+		};
+		object.last_position = object.position - self.velocity(mouse);
+	}
+
+	// where it'd go if it were let go now, worked out on a copy of the whole scene so it
+	// bounces off whatever's in the way
+	pub fn trajectory(&self, master: &Master, mouse: Vec2) -> Vec<Vec2> {
+		let mut preview = master.clone();
+		self.release(&mut preview, mouse);
+
+		let mut points = vec![];
+		for frame in 1..=LAUNCH_PREVIEW_FRAMES {
+			preview.update(1.0);
+			let Some(object) = preview.object(self.id) else {
+				break;
+			};
+			if frame % LAUNCH_PREVIEW_EVERY == 0 {
+				points.push(object.position);
+			}
+		}
+		points
+	}
+
+	// the band back to the mouse and the dots of the preview, fading out
+	pub fn draw(&self, master: &Master, mouse: Vec2) {
+		draw_line(self.anchor.x, self.anchor.y, mouse.x, mouse.y, 2.0, Color::new(1.0, 1.0, 1.0, 0.6));
+
+		let points = self.trajectory(master, mouse);
+		for (i, point) in points.iter().enumerate() {
+			let alpha = 1.0 - i as f32 / points.len() as f32;
+			draw_circle(point.x, point.y, 3.0, Color::new(1.0, 1.0, 1.0, alpha * 0.8));
+		}
+	}
+}
@@ -0,0 +1,287 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+pub use glam::{vec2, Vec2};
+
+pub mod bomb;
+pub mod broadphase;
+pub mod color;
+pub mod constraint;
+pub mod damping_curve;
+pub mod fixed;
+pub mod flock;
+pub mod force;
+pub mod hierarchy;
+pub mod link;
+pub mod magnet;
+pub mod n_body;
+pub mod object;
+pub mod pool;
+pub mod rand;
+#[cfg(feature = "serde")]
+pub mod scene_file;
+pub mod segment;
+pub mod soft_body;
+pub mod solver;
+pub mod vector_field;
+
+pub use bomb::{Bomb, Detonation};
+pub use constraint::{Constraint, KillZone};
+pub use damping_curve::DampingCurve;
+pub use flock::Flock;
+pub use force::{Attractor, Force, Gravity, Wind};
+pub use hierarchy::{CoarseLink, Crossing, Hierarchy, HierarchyScratch};
+pub use link::{ChainLink, Hinge, LinkGroup, LongRangeAttachment, Motor, Spring};
+pub use magnet::{Magnet, Pole};
+pub use n_body::NBody;
+pub use object::{ObjectId, VerletObject};
+pub use segment::StaticSegment;
+pub use soft_body::SoftBody;
+pub use solver::*;
+pub use vector_field::VectorField;
+
+use color::Color;
+use rand::{gen_range, Rng};
+
+pub const WINDOW_WIDTH: f32 = 960.0;
+pub const WINDOW_HEIGHT: f32 = 600.0;
+
+pub const CONSTRAINT_RADIUS: f32 = 300.0;
+
+pub const GRAVITY: f32 = 1.0;
+
+pub const EXPLOSION_STRENGTH: f32 = 20.0;
+pub const EXPLOSION_RADIUS: f32 = 200.0;
+// what a bomb's debris is as big as, as a share of the bomb, how fast it flies out (on top of
+// the blast) and how many frames it lasts before it's despawned
+pub const BOMB_DEBRIS_SIZE: f32 = 0.3;
+pub const BOMB_DEBRIS_SPEED: f32 = 4.0;
+pub const BOMB_DEBRIS_LIFETIME: f32 = 180.0;
+
+// how far a flock member sees its neighbours, and how hard it steers by them, when it isn't
+// set
+pub const FLOCK_VISION: f32 = 60.0;
+pub const FLOCK_SEPARATION: f32 = 0.6;
+pub const FLOCK_ALIGNMENT: f32 = 0.1;
+pub const FLOCK_COHESION: f32 = 0.01;
+pub const FLOCK_SPEED: f32 = 4.0;
+
+// how hard everything pulls on everything else with Master::n_body, and the defaults for
+// how close it lets them get and how rough it works that out, see NBody
+pub const N_BODY_STRENGTH: f32 = 0.2;
+pub const N_BODY_SOFTENING: f32 = 10.0;
+pub const N_BODY_THETA: f32 = 0.7;
+
+pub const PHYSICS_SUBSTEPS: usize = 4;
+pub const DAMPING: f32 = 0.0;
+pub const FRICTION: f32 = 0.0;
+pub const LINK_STIFFNESS: f32 = 1.0;
+// what a hierarchy gets when a scene doesn't say, about how many links apart its proxies are
+// and how many times it goes over them each substep
+pub const HIERARCHY_STRIDE: usize = 4;
+pub const HIERARCHY_ITERATIONS: usize = 2;
+
+pub const WATCHDOG_PENETRATION: f32 = 8.0;
+pub const WATCHDOG_MAX_SUBSTEPS: usize = 32;
+pub const WATCHDOG_HOLD_FRAMES: usize = 60;
+
+pub const DRAG_STIFFNESS: f32 = 0.5;
+pub const DRAG_FORCE_HISTORY: usize = 600;
+
+// closing speed (per frame) a collision needs to count as an impact
+pub const IMPACT_SPEED: f32 = 6.0;
+
+// where the broadphase starts, it resizes its cells to the objects as soon as it sees them
+pub const BROADPHASE_CELL_SIZE: f32 = 40.0;
+pub const BROADPHASE_MIN_CELL_SIZE: f32 = 4.0;
+pub const BROADPHASE_BUCKETS: usize = 4096;
+// with the parallel feature, collisions are spread over threads once there are this many
+// objects. below it, splitting them up costs more than it saves
+pub const PARALLEL_OBJECTS: usize = 2000;
+
+// Okabe-Ito, minus black since it'd disappear into the background
+pub const COLORBLIND_SAFE_COLORS: [Color; 7] = [
+	Color { r: 0.90, g: 0.62, b: 0.00, a: 1.0 },
+	Color { r: 0.34, g: 0.71, b: 0.91, a: 1.0 },
+	Color { r: 0.00, g: 0.62, b: 0.45, a: 1.0 },
+	Color { r: 0.94, g: 0.89, b: 0.26, a: 1.0 },
+	Color { r: 0.00, g: 0.45, b: 0.70, a: 1.0 },
+	Color { r: 0.84, g: 0.37, b: 0.00, a: 1.0 },
+	Color { r: 0.80, g: 0.47, b: 0.65, a: 1.0 },
+];
+
+pub const NEON_COLORS: [Color; 6] = [
+	Color { r: 1.00, g: 0.08, b: 0.58, a: 1.0 },
+	Color { r: 0.22, g: 1.00, b: 0.08, a: 1.0 },
+	Color { r: 0.00, g: 0.94, b: 1.00, a: 1.0 },
+	Color { r: 1.00, g: 0.94, b: 0.00, a: 1.0 },
+	Color { r: 0.74, g: 0.07, b: 1.00, a: 1.0 },
+	Color { r: 1.00, g: 0.37, b: 0.00, a: 1.0 },
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Palette {
+	#[default]
+	Random,
+	Pastel,
+	Neon,
+	Grayscale,
+	ColorblindSafe,
+}
+
+impl Palette {
+	pub const ALL: [Palette; 5] = [
+		Palette::Random,
+		Palette::Pastel,
+		Palette::Neon,
+		Palette::Grayscale,
+		Palette::ColorblindSafe,
+	];
+
+	pub fn color(&self) -> Color {
+		self.pick(gen_range)
+	}
+
+	// the same, out of a generator of its own
+	pub fn color_with(&self, rng: &mut Rng) -> Color {
+		self.pick(|low, high| rng.range(low, high))
+	}
+
+	fn pick(&self, mut range: impl FnMut(f32, f32) -> f32) -> Color {
+		// one of a fixed set, evenly
+		let mut choose = |colors: &[Color]| colors[(range(0.0, colors.len() as f32) as usize).min(colors.len() - 1)];
+		match self {
+			Palette::Random => Color {
+				r: range(0.2, 1.0),
+				g: range(0.2, 1.0),
+				b: range(0.2, 1.0),
+				a: 1.0,
+			},
+			Palette::Pastel => Color {
+				r: range(0.7, 1.0),
+				g: range(0.7, 1.0),
+				b: range(0.7, 1.0),
+				a: 1.0,
+			},
+			Palette::Neon => choose(&NEON_COLORS),
+			Palette::Grayscale => {
+				let value = range(0.3, 1.0);
+				Color::new(value, value, value, 1.0)
+			}
+			Palette::ColorblindSafe => choose(&COLORBLIND_SAFE_COLORS),
+		}
+	}
+
+	pub fn next(&self) -> Palette {
+		let index = Palette::ALL.iter().position(|palette| palette == self).unwrap();
+		Palette::ALL[(index + 1) % Palette::ALL.len()]
+	}
+}
+
+
+// the rope bridge, pinned at both ends
+pub fn generate_objects() -> Vec<VerletObject> {
+	let mut result = vec![];
+
+	for i in 0..=14 {
+		let object = VerletObject::new(
+			vec2(WINDOW_WIDTH * 0.5 - 210.0 + i as f32 * 30.0, WINDOW_HEIGHT * 0.5 + 100.0),
+			10.0,
+		);
+		result.push(if i == 0 || i == 14 { object.kinematic() } else { object });
+	}
+
+	result
+}
+
+pub fn generate_chain_links() -> Vec<ChainLink> {
+	let mut result = vec![];
+
+	for i in 1..=14 {
+		result.push(
+			ChainLink {
+				a: i - 1,
+				b: i,
+				target_distance: 30.0,
+				priority: 0,
+				spring: None,
+				max_strain: None,
+				group: None,
+			}
+		);
+	}
+
+	result
+}
+
+// a sheet of objects hanging from its top row, which is pinned. object ids go along each row
+// and then down, links hold each one to the next one across and down (structural) and to
+// both diagonals below (shear), without those it folds up like a bead curtain
+pub fn generate_cloth(origin: Vec2, cols: usize, rows: usize, spacing: f32) -> (Vec<VerletObject>, Vec<ChainLink>) {
+	let mut objects = vec![];
+	let mut chain_links = vec![];
+	let id = |col: usize, row: usize| row * cols + col;
+	let diagonal = spacing * core::f32::consts::SQRT_2;
+
+	for row in 0..rows {
+		for col in 0..cols {
+			let object = VerletObject::new(origin + vec2(col as f32, row as f32) * spacing, spacing * 0.5);
+			objects.push(if row == 0 { object.kinematic() } else { object });
+		}
+	}
+
+	let mut link = |a: usize, b: usize, target_distance: f32| chain_links.push(ChainLink {
+		a,
+		b,
+		target_distance,
+		priority: 0,
+		spring: None,
+		max_strain: None,
+		group: None,
+	});
+	for row in 0..rows {
+		for col in 0..cols {
+			if col + 1 < cols {
+				link(id(col, row), id(col + 1, row), spacing);
+			}
+			if row + 1 < rows {
+				link(id(col, row), id(col, row + 1), spacing);
+				if col + 1 < cols {
+					link(id(col, row), id(col + 1, row + 1), diagonal);
+					link(id(col + 1, row), id(col, row + 1), diagonal);
+				}
+			}
+		}
+	}
+
+	(objects, chain_links)
+}
+
+// a ring of count objects this big around center, linked to the ones either side, for a
+// SoftBody to fill. ids go around clockwise from the right
+pub fn generate_blob(center: Vec2, radius: f32, count: usize, object_radius: f32) -> (Vec<VerletObject>, Vec<ChainLink>) {
+	let count = count.max(3);
+	let objects: Vec<VerletObject> = (0..count)
+		.map(|i| {
+			let angle = i as f32 / count as f32 * core::f32::consts::TAU;
+			VerletObject::new(center + Vec2::from_angle(angle) * radius, object_radius)
+		})
+		.collect();
+	let chain_links = (0..count)
+		.map(|i| ChainLink {
+			a: i,
+			b: (i + 1) % count,
+			target_distance: objects[i].position.distance(objects[(i + 1) % count].position),
+			priority: 0,
+			spring: None,
+			max_strain: None,
+			group: None,
+		})
+		.collect();
+
+	(objects, chain_links)
+}
@@ -0,0 +1,96 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use glam::Vec2;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChainLink {
+	pub a: usize,
+	pub b: usize,
+	pub target_distance: f32,
+	// links are solved from lowest to highest priority, ties keep insertion order
+	pub priority: i32,
+	// None holds the distance as hard as Master::link_stiffness says
+	pub spring: Option<Spring>,
+	// comes apart once it's stretched or squashed by more than this much of its target
+	// distance at the end of an update (0.1 is 10%), and None never does. see
+	// Master::broken_links
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub max_strain: Option<f32>,
+	// an index into Master::groups, it's only solved while that's switched on
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub group: Option<usize>,
+}
+
+impl ChainLink {
+	pub fn is_enabled(&self, groups: &[LinkGroup]) -> bool {
+		self.group.and_then(|group| groups.get(group)).is_none_or(|group| group.enabled)
+	}
+}
+
+// links that get switched off and back on all at once, like scaffolding that comes out from
+// under something to let it fall. a link that's off is still there, it just doesn't hold
+// anything together, break or get drawn
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinkGroup {
+	pub name: String,
+	pub enabled: bool,
+}
+
+// a link that gives. it's only pulled part of the way back to its length each substep, so it
+// stretches and bounces, stiffness is how much of the way (0 to 1) and damping how much of
+// the speed it's stretching or squashing at gets taken away (0 to 1 as well)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spring {
+	pub stiffness: f32,
+	pub damping: f32,
+}
+
+// keeps an object from getting further from an anchor than the rope between them is long.
+// a long chain only stretches a little per link, but that adds up over a lot of links and
+// takes many iterations to pull back, this fixes it in one go
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LongRangeAttachment {
+	pub object: usize,
+	pub anchor: usize,
+	pub max_distance: f32,
+}
+
+// two bodies turning around a shared pivot object, each held to it by links of its own (a
+// lever's, say). the angle is from the first object of a to the first object of b around the
+// pivot, clockwise in radians, counted from rest_angle. the pivot can be linked to anything,
+// pinned for a flipper or part of another arm for an elbow. an empty a is the world, which
+// never turns and points along x
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hinge {
+	pub pivot: usize,
+	pub a: Vec<usize>,
+	pub b: Vec<usize>,
+	pub rest_angle: f32,
+	// how far it turns either way from rest
+	pub limits: Option<(f32, f32)>,
+	pub motor: Option<Motor>,
+}
+
+impl Hinge {
+	// how far it's turned from rest with the pivot and the first object of each body here
+	// (None for the world), from -pi to pi
+	pub fn angle(&self, pivot: Vec2, a: Option<Vec2>, b: Vec2) -> f32 {
+		let a = a.map_or(Vec2::X, |a| a - pivot);
+		Vec2::from_angle(self.rest_angle).rotate(a).angle_between(b - pivot)
+	}
+}
+
+// turns b against a (and a against b, just as hard) until they're going speed, in radians
+// per frame clockwise, pushing with no more than torque
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Motor {
+	pub speed: f32,
+	pub torque: f32,
+}
@@ -0,0 +1,50 @@
+use glam::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Pole {
+	North,
+	South,
+}
+
+impl Pole {
+	// 1 for north, -1 for south, so two poles pull together when these multiply out negative
+	pub fn sign(self) -> f32 {
+		match self {
+			Pole::North => 1.0,
+			Pole::South => -1.0,
+		}
+	}
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Pole::North => "north",
+			Pole::South => "south",
+		}
+	}
+}
+
+// makes an object one end of a magnet. two of the same pole push each other apart and a north
+// and a south pull together, as hard as both strengths multiplied over the distance between
+// them squared, in pixels a frame squared on something of mass 1. only other magnets feel it,
+// and only while they're closer than the further reaching of the two
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Magnet {
+	pub pole: Pole,
+	pub strength: f32,
+	pub reach: f32,
+}
+
+impl Magnet {
+	// the push it gives a north pole of strength 1 that's offset from it (a south one gets
+	// pulled the other way). it's never closer than closest, so nothing blows up right on top
+	pub fn field(&self, offset: Vec2, closest: f32) -> Vec2 {
+		let distance = offset.length();
+		if distance >= self.reach || distance == 0.0 {
+			return Vec2::ZERO;
+		}
+		let distance = distance.max(closest);
+		offset.normalize() * self.pole.sign() * self.strength / (distance * distance)
+	}
+}
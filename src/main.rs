@@ -1,243 +1,973 @@
-use macroquad::rand::gen_range;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
 use macroquad::prelude::*;
+use macroquad::rand::srand;
+use macroquad::Window;
+use verlet_integration::rand::Rng;
+use verlet_integration::*;
+
+use input::Action;
+
+mod animation;
+mod automation;
+mod bench;
+mod camera;
+mod cannon;
+mod chaos;
+mod checkpoints;
+mod editor;
+mod effects;
+mod export;
+mod field_lines;
+mod game;
+mod generate;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod grid;
+mod groups;
+mod histogram;
+mod input;
+mod launch;
+mod measure;
+#[cfg(feature = "osc")]
+mod osc;
+mod parts;
+mod plot;
+#[cfg(feature = "remote")]
+mod remote;
+mod replay;
+mod scene;
+mod scene_diff;
+mod settings;
+mod skin;
+mod soak;
+mod sweep;
+#[cfg(feature = "terminal")]
+mod terminal;
+mod touch;
+mod trails;
+#[cfg(feature = "egui")]
+mod tuning;
+
+// objects are drawn from the lowest layer up, links sit just under the default object layer
+// and whatever is being dragged gets pulled up on top of everything
+pub const LINK_LAYER: i32 = -1;
+pub const DRAG_LAYER: i32 = 100;
+
+// how many screen pixels wide each pixel is in the pixel art render mode
+pub const PIXEL_SCALE: f32 = 4.0;
+
+// frames the time reversal experiment runs each way
+pub const REVERSAL_WINDOW: usize = 120;
+
+pub const FRAME_BUDGET: Duration = Duration::from_millis(4);
+
+pub const CONTACTS_FOR_RED: f32 = 6.0;
+
+// how many links out from a grabbed object the pull fades over
+pub const MOUSE_FALLOFF_HOPS: usize = 2;
+
+// how close to the middle of where a broken link was a click has to be to put it back
+pub const REPAIR_RADIUS: f32 = 30.0;
+
+pub const GLUE_SELECTION_RADIUS: f32 = 80.0;
+pub const GLUE_RADIUS: f32 = 5.0;
+
+// zigzags per spring
+pub const SPRING_COILS: usize = 6;
+
+// how far from the center the mouse has to be for the full strength of gravity in tilt mode
+pub const TILT_RADIUS: f32 = CONSTRAINT_RADIUS;
+
+// how fast time runs in reduced motion mode
+pub const REDUCED_MOTION_TIME_SCALE: f32 = 0.5;
+
+// what the fps cap key cycles through
+pub const FPS_CAPS: [Option<u32>; 4] = [None, Some(30), Some(60), Some(144)];
+
+// a scene where nothing has moved faster than this (pixels per frame) for this many frames,
+// with nobody touching anything, stops updating and only draws a few times a second
+pub const SLEEP_SPEED: f32 = 0.75;
+pub const IDLE_AFTER: f32 = 120.0;
+pub const IDLE_FPS: u32 = 10;
 
-pub const WINDOW_WIDTH: f32 = 960.0;
-pub const WINDOW_HEIGHT: f32 = 600.0;
+// the most physics frames one drawn frame catches up on, any slower than that and the
+// simulation slows down with it instead of taking longer and longer to catch up
+pub const MAX_CATCH_UP: f32 = 4.0;
+// what faster and slower go up and down through, the 1 is where it starts
+pub const TIME_SCALES: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0];
 
-pub const CONSTRAINT_RADIUS: f32 = 300.0;
+// where spawn_object() and the spawner drop things
+pub const SPAWN_POINT: Vec2 = Vec2::new(WINDOW_WIDTH * 0.5 + 180.0, WINDOW_HEIGHT * 0.5);
+// the smallest and biggest radius of anything spawned without one, until it's tuned. it's
+// picked when it's spawned, so a recording only has to say there wasn't one
+pub const SPAWN_SIZES: (f32, f32) = (10.0, 40.0);
 
-pub const GRAVITY: f32 = 1.0;
+// objects spawned with shift held get this tag and get drawn as crates
+pub const CRATE_TAG: u32 = 1;
 
-pub const PHYSICS_SUBSTEPS: usize = 4;
+// how fast a flicked object goes for each pixel the mouse is dragged, and the fastest it goes
+pub const FLICK_STRENGTH: f32 = 0.05;
+pub const FLICK_MAX_SPEED: f32 = 9.0;
 
-fn window_conf() -> Conf {
+fn window_conf(settings: &settings::Settings) -> Conf {
 	Conf {
 		window_title: "Verlet Integration Physics ~ v1.0.0".to_string(),
-		window_width: WINDOW_WIDTH as i32,
-		window_height: WINDOW_HEIGHT as i32,
+		window_width: settings.window_width,
+		window_height: settings.window_height,
+		platform: macroquad::miniquad::conf::Platform {
+			swap_interval: Some(settings.vsync as i32),
+			..Default::default()
+		},
 		..Default::default()
 	}
 }
 
-#[derive(Debug)]
-pub struct VerletObject {
-	pub position: Vec2,
-	pub last_position: Vec2,
-	pub acceleration: Vec2,
-	pub radius: f32,
-	pub color: Color,
-}
+fn main() {
+	// seeded before anything gets a color, so the bridge and scenes come out the same too
+	let args: Vec<String> = std::env::args().collect();
+	let deterministic = deterministic_seed(&args);
+	if let Some(seed) = deterministic {
+		println!("deterministic with seed {}", seed);
+		srand(seed);
+	}
+	let mut master = Master::new(generate_objects(), generate_chain_links());
 
-impl VerletObject {
-	pub fn new(position: Vec2, radius: f32) -> Self {
-		Self {
-			position,
-			last_position: position,
-			acceleration: Vec2::ZERO,
-			radius,
-			color: Color {
-				r: gen_range(0.2, 1.0),
-				g: gen_range(0.2, 1.0),
-				b: gen_range(0.2, 1.0),
-				a: 1.0,
-			}
+	// --sweep [path] runs the parameter sweep headless and writes a csv summary
+	if let Some(index) = args.iter().position(|arg| arg == "--sweep") {
+		let path = args.get(index + 1).map(String::as_str).unwrap_or("sweep.csv");
+		sweep::run(path).unwrap();
+		return;
+	}
+
+	// --diff-scenes <old> <new> [patch] writes what changed between two scene files, and
+	// --patch-scene <scene> <patch> <out> makes the same changes to a scene
+	if let Some(index) = args.iter().position(|arg| arg == "--diff-scenes") {
+		let (Some(old), Some(new)) = (args.get(index + 1), args.get(index + 2)) else {
+			eprintln!("--diff-scenes needs an old and a new scene");
+			return;
+		};
+		if let Err(error) = scene_diff::diff_files(old, new, args.get(index + 3).map(String::as_str)) {
+			eprintln!("couldn't diff {} and {}: {}", old, new, error);
+		}
+		return;
+	}
+	if let Some(index) = args.iter().position(|arg| arg == "--patch-scene") {
+		let (Some(scene), Some(patch), Some(out)) = (args.get(index + 1), args.get(index + 2), args.get(index + 3)) else {
+			eprintln!("--patch-scene needs a scene, a patch and where to write the result");
+			return;
+		};
+		if let Err(error) = scene_diff::patch_file(scene, patch, out) {
+			eprintln!("couldn't patch {} with {}: {}", scene, patch, error);
 		}
+		return;
 	}
 
-	pub fn update_position(&mut self, delta: f32) {
-		let velocity = self.position - self.last_position;
+	// --bench [steps] [objects] times the solver on its own, see bench.rs
+	if args.iter().any(|arg| arg == "--bench") {
+		if let Err(error) = bench::run(&args) {
+			eprintln!("{}", error);
+		}
+		return;
+	}
 
-		self.last_position = self.position;
-		self.position += velocity + self.acceleration * delta * delta;
+	let mut settings = settings::Settings::load();
 
-		self.acceleration = Vec2::ZERO;
+	// --scene <path> starts from a scene file instead of the bridge, and without it the last
+	// scene opened comes back. --bridge goes back to the bridge for good
+	let mut timeline = scene::Timeline::default();
+	if let Some(path) = args.iter().position(|arg| arg == "--scene").and_then(|index| args.get(index + 1)) {
+		match scene::open(path) {
+			Ok(loaded) => {
+				(master, timeline) = loaded;
+				settings.last_scene = Some(std::fs::canonicalize(path).unwrap_or_else(|_| path.into()));
+			}
+			Err(error) => {
+				eprintln!("couldn't load the scene {}: {}", path, error);
+				return;
+			}
+		}
+	} else if let Some(index) = args.iter().position(|arg| arg == "--generate") {
+		// --generate maze|cave|terrain [seed], a new seed every run without one
+		let generator = args.get(index + 1).and_then(|name| generate::Generator::from_name(name));
+		let seed = args.get(index + 2).and_then(|seed| seed.parse().ok()).unwrap_or_else(|| macroquad::miniquad::date::now() as u64);
+		let Some(generator) = generator else {
+			eprintln!("--generate needs maze, cave or terrain");
+			return;
+		};
+		println!("generating with seed {}", seed);
+		(master, timeline) = generator.generate(seed).into_master();
+	} else if args.iter().any(|arg| arg == "--bridge") {
+		settings.last_scene = None;
+	} else if let Some(path) = &settings.last_scene {
+		match scene::open(path) {
+			Ok(loaded) => (master, timeline) = loaded,
+			Err(error) => eprintln!("couldn't load the last scene {}: {}", path.display(), error),
+		}
+	}
+	if let Err(error) = settings.save() {
+		eprintln!("couldn't save the settings: {}", error);
+	}
+	if let Some(seed) = deterministic {
+		master.rng = Some(Rng::new(seed));
+	}
+	if fixed_point(&args) {
+		println!("stepping in fixed point");
+		master.fixed_point = true;
 	}
 
-	pub fn accelerate(&mut self, acc: Vec2) {
-		self.acceleration += acc;
+	// --soak [seed] runs whatever scene it would've opened headless with chaos mode on, until
+	// something breaks or the hours run out, see soak.rs
+	if let Some(index) = args.iter().position(|arg| arg == "--soak") {
+		let seed = args.get(index + 1).and_then(|seed| seed.parse().ok()).unwrap_or_else(|| macroquad::miniquad::date::now() as u64);
+		match soak::run(master, timeline, seed, &args) {
+			Ok(None) => {}
+			Ok(Some(_)) => std::process::exit(1),
+			Err(error) => {
+				eprintln!("couldn't save what went wrong: {}", error);
+				std::process::exit(1);
+			}
+		}
+		return;
 	}
-}
 
-pub struct ChainLink {
-	pub a: usize,
-	pub b: usize,
-	pub target_distance: f32,
+	#[cfg(feature = "terminal")]
+	if std::env::args().any(|arg| arg == "--terminal") {
+		terminal::run(master, timeline).unwrap();
+		return;
+	}
+
+	Window::from_config(window_conf(&settings), run(master, timeline, settings));
 }
 
-pub struct Master {
-	pub objects: Vec<VerletObject>,
-	pub chain_links: Vec<ChainLink>,
+// --deterministic [seed], 1 without one. it seeds everything the simulation picks at random
+// and steps exactly one physics frame every drawn frame, with nothing going by the clock (no
+// frame budget, no idling), so the same seed and the same input always give the same run
+fn deterministic_seed(args: &[String]) -> Option<u64> {
+	let index = args.iter().position(|arg| arg == "--deterministic")?;
+	Some(args.get(index + 1).and_then(|seed| seed.parse().ok()).unwrap_or(1))
 }
 
-impl Master {
-	pub fn update(&mut self, delta: f32) {
-		let sub_delta = delta / PHYSICS_SUBSTEPS as f32;
-		for _ in 0..PHYSICS_SUBSTEPS {
-			self.apply_gravity();
-			self.apply_constraint();
-			self.solve_collisions();
-			self.apply_chain_links();
+// --fixed-point steps every position in fixed point (Master::update_fixed()), so a run comes
+// out the same to the bit on any machine and not just this one. it leaves out everything
+// update_fixed() doesn't do
+fn fixed_point(args: &[String]) -> bool {
+	args.iter().any(|arg| arg == "--fixed-point")
+}
 
-			// this is to keep the ends of the rope bridge thing static
-			let old_color = self.objects[0].color;
-			self.objects[0] = VerletObject::new(
-				vec2(WINDOW_WIDTH * 0.5 - 210.0, WINDOW_HEIGHT * 0.5 + 100.0),
-				10.0,
-			);
-			self.objects[0].color = old_color;
+async fn run(mut master: Master, mut timeline: scene::Timeline, mut settings: settings::Settings) {
+	let mut flat = FlatRenderer;
+	let mut view = View::default();
+	view.sprites.tags.insert(CRATE_TAG, skin::Sprite::Draw(Rc::new(draw_crate)));
 
-			let old_color = self.objects[14].color;
-			self.objects[14] = VerletObject::new(
-				vec2(WINDOW_WIDTH * 0.5 + 210.0, WINDOW_HEIGHT * 0.5 + 100.0),
-				10.0,
-			);
-			self.objects[14].color = old_color;
+	// --rope-texture <path> tiles an image along the ropes
+	let args: Vec<String> = std::env::args().collect();
+	let deterministic = deterministic_seed(&args);
+	if let Some(path) = args.iter().position(|arg| arg == "--rope-texture").and_then(|index| args.get(index + 1)) {
+		match load_texture(path).await {
+			Ok(texture) => view.rope_texture = Some(texture),
+			Err(error) => eprintln!("couldn't load the rope texture {}: {}", path, error),
+		}
+	}
 
-			self.update_positions(sub_delta);
+	// --sprite <path> draws every untagged object as the image
+	if let Some(path) = args.iter().position(|arg| arg == "--sprite").and_then(|index| args.get(index + 1)) {
+		match load_texture(path).await {
+			Ok(texture) => {
+				view.sprites.tags.insert(0, skin::Sprite::Texture(texture));
+			}
+			Err(error) => eprintln!("couldn't load the sprite {}: {}", path, error),
 		}
 	}
 
-	pub fn apply_gravity(&mut self) {
-		for object in self.objects.iter_mut() {
-			object.accelerate(vec2(0.0, GRAVITY));
+	view.ropes = settings.ropes;
+	view.soft_bodies = settings.soft_bodies;
+	view.cloth = settings.cloth;
+	view.effects.shake = settings.impact_effects;
+	view.effects.sparks_enabled = settings.impact_effects;
+	view.reduced_motion = settings.reduced_motion || args.iter().any(|arg| arg == "--reduced-motion");
+	view.high_contrast = settings.high_contrast || args.iter().any(|arg| arg == "--high-contrast");
+	let mut grid = settings.grid;
+	let mut histograms = settings.histograms;
+	let mut grid_spacing = settings.grid_spacing;
+	let mut field_lines = settings.field_lines;
+	let mut paused = false;
+	// which of TIME_SCALES it's running at
+	let mut speed = TIME_SCALES.iter().position(|&scale| scale == 1.0).unwrap_or(0);
+
+	// --cloth-texture <path> stretches an image over the cloth mesh
+	if let Some(path) = args.iter().position(|arg| arg == "--cloth-texture").and_then(|index| args.get(index + 1)) {
+		match load_texture(path).await {
+			Ok(texture) => view.cloth_texture = Some(texture),
+			Err(error) => eprintln!("couldn't load the cloth texture {}: {}", path, error),
 		}
 	}
 
-	pub fn apply_constraint(&mut self) {
-		let position = vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5);
-		let radius = CONSTRAINT_RADIUS;
-		for object in self.objects.iter_mut() {
-			let to_object = object.position - position;
-			let distance = to_object.length();
-			if distance > radius - object.radius {
-				let n = to_object / distance;
-				object.position = position + n * (radius - object.radius);
+	// where the button came down on empty space, to spawn something when it comes back up
+	let mut flick: Option<Vec2> = None;
+	let mut mouse_dragging = false;
+	let mut launch: Option<launch::Launch> = None;
+	let mut spawner = Spawner::default();
+	// whatever the inputs, chaos and the spawner want done this frame
+	let mut commands: Vec<Command> = vec![];
+	// --record starts recording right away
+	let mut recording = args.iter().any(|arg| arg == "--record")
+		.then(|| replay::Recording::start(&mut master, &timeline, deterministic.unwrap_or(1)));
+	let mut recorded: Option<replay::Recording> = None;
+	let mut point_cloud_recorder: Option<export::PointCloudRecorder> = None;
+	let mut time_reversal: Option<TimeReversal> = None;
+	// the gravity from before tilting, to put back afterwards
+	let mut tilt: Option<Vec2> = None;
+	// and from before n-body switched it off
+	let mut n_body_gravity: Option<Vec2> = None;
+
+	let mut input = settings.input.clone();
+	let mut bindings_screen = input::BindingsScreen::default();
+	let mut checkpoint_panel = checkpoints::CheckpointPanel::default();
+	let mut group_panel = groups::GroupPanel::default();
+	let mut automation_panel = automation::AutomationPanel::default();
+	#[cfg(feature = "egui")]
+	let mut tuning_panel = tuning::TuningPanel::default();
+	let mut cannon_controls = cannon::CannonControls::default();
+
+	let mut fps_cap = settings.fps_cap;
+	let mut vsync = settings.vsync;
+	let vsync_at_start = vsync;
+	let mut frame_limiter = FrameLimiter::default();
+	let mut idle = IdleThrottle::default();
+	let mut timestep = FixedTimestep::default();
+	let mut measure = measure::Measure::default();
+	let mut editor = editor::Editor::default();
+	let mut plot = plot::Plot::default();
+	let mut chaos = chaos::Chaos::from_args(&args);
+	if let Some(chaos) = &chaos {
+		println!("chaos mode with seed {}", chaos.seed);
+	}
+
+	let mut debug_overlay = settings.debug_overlay;
+	if view.cloth {
+		view.cloth_uvs = skin::face_uvs(&master, &skin::find_faces(&master));
+	}
+
+	#[cfg(feature = "render")]
+	let mut glow = GlowRenderer::load();
+	#[cfg(feature = "render")]
+	let mut pixel = PixelRenderer::load();
+	#[cfg(feature = "render")]
+	let mut render_mode = RenderMode::Flat;
+
+	#[cfg(feature = "osc")]
+	let mut osc_listener = osc::listener_from_args();
+
+	#[cfg(feature = "remote")]
+	let remote_server = remote::server_from_args();
+
+	#[cfg(feature = "gamepad")]
+	let mut gamepad = gamepad::GamepadInput::new();
+
+	let mut touch = touch::TouchInput::default();
+
+	loop {
+		// in physics frames, and exactly one whenever the master has a generator of its own
+		// (deterministic, recording, a replay or a scene with a seed)
+		let exact = master.rng.is_some();
+		let delta = if exact { 1.0 } else { delta_time() };
+
+		#[cfg(feature = "osc")]
+		if let Some(listener) = &mut osc_listener {
+			for command in listener.poll() {
+				send_command(&mut master, &mut spawner, &mut recording, command);
 			}
 		}
-	}
 
-	pub fn solve_collisions(&mut self) {
-		let object_count = self.objects.len();
-		for i in 0..object_count {
-			for j in 0..object_count {
-				if i == j {
-					continue;
+		#[cfg(feature = "remote")]
+		if let Some(server) = &remote_server {
+			server.poll(|command| send_command(&mut master, &mut spawner, &mut recording, command));
+		}
+
+		bindings_screen.update(&mut input);
+		checkpoint_panel.update(&mut input, &mut master, &mut timeline);
+		group_panel.update(&mut input, &master, &mut commands);
+		automation_panel.update(&mut input, &mut timeline.automations);
+		#[cfg(feature = "egui")]
+		tuning_panel.update(&mut input, &mut master, &mut spawner);
+		cannon_controls.update(&input, &mut timeline.cannons, &mut commands);
+
+		if input.pressed(Action::MoreSpawns) {
+			let rate = spawner.rate + 1.0;
+			run_command(&mut master, &mut spawner, Command::SetSpawnRate { rate });
+		}
+		if input.pressed(Action::FewerSpawns) {
+			let rate = spawner.rate - 1.0;
+			run_command(&mut master, &mut spawner, Command::SetSpawnRate { rate });
+		}
+
+		timeline.update(&mut master, &mut spawner);
+		if let Some(game) = &timeline.game {
+			game.flip(&mut master, input.down(Action::FlipLeft), input.down(Action::FlipRight));
+			game.steer(&mut master, input.down(Action::MoveLeft), input.down(Action::MoveRight), input.pressed(Action::Jump), delta);
+		}
+
+		if input.pressed(Action::Chaos) {
+			match &mut chaos {
+				Some(chaos) => chaos.enabled = !chaos.enabled,
+				None => {
+					let seed = macroquad::miniquad::date::now() as u64;
+					println!("chaos mode with seed {}", seed);
+					chaos = Some(chaos::Chaos::new(seed, chaos::ChaosEvent::ALL.to_vec(), chaos::CHAOS_INTERVAL));
 				}
+			}
+		}
+		if let Some(chaos) = &mut chaos {
+			chaos.update(&master, &mut commands);
+		}
+		if !paused {
+			spawner.update(delta / 60.0, &mut commands);
+		}
+		for command in commands.drain(..) {
+			send_command(&mut master, &mut spawner, &mut recording, command);
+		}
+
+		let (mouse_x, mouse_y) = mouse_position();
+		let wheel = if input.suspended { 0.0 } else { mouse_wheel().1 };
+		view.camera.update(vec2(mouse_x, mouse_y), input.down(Action::Pan), wheel);
+		if input.pressed(Action::ResetCamera) {
+			view.camera = camera::WorldCamera::default();
+		}
+		// where the mouse is in the world, everything that goes by the mouse uses this
+		let mouse = view.camera.screen_to_world(vec2(mouse_x, mouse_y));
+
+		// one set of tools at a time, picking one puts the other away
+		if input.pressed(Action::Measure) {
+			measure.set_tool(measure::Tool::next(measure.tool));
+			editor.set_tool(None);
+		}
+		measure.update(&master, mouse, input.pressed(Action::Spawn), input.down(Action::Spawn));
+
+		if input.pressed(Action::Editor) {
+			editor.set_tool(editor::Tool::next(editor.tool));
+			measure.set_tool(None);
+		}
+		editor.update(&mut master, &mut timeline, mouse, input.pressed(Action::Spawn), input.down(Action::Spawn), input.down(Action::Alternate));
+		input.suspended |= editor.typing();
 
-				let collision_axis = self.objects[i].position - self.objects[j].position;
-				let distance = collision_axis.length();
-				let object_distance = self.objects[i].radius + self.objects[j].radius;
-				if distance < object_distance {
-					let n = collision_axis / distance;
-					let delta = object_distance - distance;
-					self.objects[i].position += 0.5 * delta * n;
-					self.objects[j].position -= 0.5 * delta * n;
+		// with alternate held it's json instead, everything in the master but none of the timeline
+		if input.pressed(Action::SaveScene) {
+			let json = cfg!(feature = "serde") && input.down(Action::Alternate);
+			let path = export::next_free_path(".", "contraption", if json { "json" } else { "scene" });
+			let saved = match json {
+				#[cfg(feature = "serde")]
+				true => master.save_scene(&path),
+				_ => editor::save(&master, &timeline, &path),
+			};
+			match saved {
+				Ok(()) => println!("saved {}", path.display()),
+				Err(error) => eprintln!("couldn't save {}: {}", path.display(), error),
+			}
+		}
+
+		// stopping saves it as a scene that plays it all back when it's opened
+		if input.pressed(Action::Record) {
+			match recording.take() {
+				Some(stopped) => {
+					let path = export::next_free_path(".", "recording", "scene");
+					match stopped.save(&path) {
+						Ok(()) => println!("saved {} commands to {}", stopped.commands, path.display()),
+						Err(error) => eprintln!("couldn't save {}: {}", path.display(), error),
+					}
+					recorded = Some(stopped);
+				}
+				None => {
+					let seed = deterministic.unwrap_or_else(|| macroquad::miniquad::date::now() as u64);
+					recording = Some(replay::Recording::start(&mut master, &timeline, seed));
 				}
 			}
 		}
-	}
 
-	pub fn apply_chain_links(&mut self) {
-		for chain_link in self.chain_links.iter_mut() {
-			let axis = self.objects[chain_link.a].position - self.objects[chain_link.b].position;
-			let distance = axis.length();
-			let n = axis / distance;
-			let delta = chain_link.target_distance - distance;
-			self.objects[chain_link.a].position += 0.5 * delta * n;
-			self.objects[chain_link.b].position -= 0.5 * delta * n;
+		// loads the scene it started with again from its file, so it can be edited while this is
+		// open. the bridge just starts over
+		let mut loaded = None;
+		if input.pressed(Action::ReloadScene) {
+			if let Some(seed) = deterministic {
+				srand(seed);
+			}
+			loaded = match &settings.last_scene {
+				Some(path) => scene::open(path).map_err(|error| eprintln!("couldn't reload {}: {}", path.display(), error)).ok(),
+				None => Some((Master::new(generate_objects(), generate_chain_links()), scene::Timeline::default())),
+			};
+			if let (Some((master, _)), Some(seed)) = (&mut loaded, deterministic) {
+				master.rng = Some(Rng::new(seed));
+			}
+			if let Some((master, _)) = &mut loaded {
+				master.fixed_point |= fixed_point(&args);
+			}
+		}
+		// back to the start of the last recording, without chaos or the spawner adding anything
+		// the recording already has
+		if input.pressed(Action::Replay) {
+			if let Some(replaying) = recording.as_ref().or(recorded.as_ref()) {
+				loaded = Some(replaying.replay());
+				spawner = Spawner::default();
+				if let Some(chaos) = &mut chaos {
+					chaos.enabled = false;
+				}
+			}
+		}
+		if let Some(loaded) = loaded {
+			// a recording doesn't carry on into something else, it stops there without saving
+			if let Some(stopped) = recording.take() {
+				recorded = Some(stopped);
+			}
+			(master, timeline) = loaded;
+			timestep.reset();
+			launch = None;
+			mouse_dragging = false;
+			if view.cloth {
+				view.cloth_uvs = skin::face_uvs(&master, &skin::find_faces(&master));
+			}
+			// the timeline already ran this frame, and the first of the recording came in after
+			if input.pressed(Action::Replay) {
+				timeline.update(&mut master, &mut spawner);
+			}
 		}
-	}
 
-	pub fn update_positions(&mut self, delta: f32) {
-		for object in self.objects.iter_mut() {
-			object.update_position(delta);
+		// picks whatever's under the mouse, or with nothing there stops plotting
+		if input.pressed(Action::Plot) {
+			if input.down(Action::Alternate) {
+				plot.show_force = !plot.show_force;
+			} else {
+				plot.select(master.object_at(mouse));
+			}
 		}
-	}
-}
 
-pub fn generate_objects() -> Vec<VerletObject> {
-	let mut result = vec![];
+		// coming down on an object grabs it, and it follows the mouse until the button comes
+		// back up. the ones a game launches get pulled back and let go instead, coming down
+		// where a link broke puts it back, and anywhere else gets flicked (see below)
+		if input.pressed(Action::Spawn) && measure.tool.is_none() && editor.tool.is_none() {
+			if let Some(id) = master.object_at(mouse) {
+				if timeline.game.as_ref().is_some_and(|game| game.launchers.contains(&id)) {
+					launch = launch::Launch::new(&master, id);
+				} else {
+					send_command(&mut master, &mut spawner, &mut recording, Command::Grab { id, target: mouse, hops: MOUSE_FALLOFF_HOPS });
+					mouse_dragging = true;
+				}
+			} else if let Some(index) = broken_link_at(&master, mouse) {
+				send_command(&mut master, &mut spawner, &mut recording, Command::RepairLink { index });
+			} else {
+				flick = Some(mouse);
+			}
+		}
+		if input.pressed(Action::Explode) && measure.tool.is_none() && editor.tool.is_none() {
+			if !input.down(Action::Alternate) {
+				send_command(&mut master, &mut spawner, &mut recording, Command::Explode { position: mouse, strength: EXPLOSION_STRENGTH });
+			} else if let Some(id) = master.object_at(mouse) {
+				send_command(&mut master, &mut spawner, &mut recording, Command::Despawn { id });
+			}
+		}
+		if let Some(aim) = launch {
+			if input.down(Action::Spawn) {
+				aim.hold(&mut master);
+			} else {
+				aim.release(&mut master, mouse);
+				launch = None;
+			}
+		}
+		if mouse_dragging {
+			match (input.down(Action::Spawn), master.drag.as_ref().map(|drag| drag.target)) {
+				(true, Some(target)) => {
+					if target != mouse {
+						send_command(&mut master, &mut spawner, &mut recording, Command::MoveGrab { target: mouse });
+					}
+				}
+				(true, None) => mouse_dragging = false,
+				(false, _) => {
+					send_command(&mut master, &mut spawner, &mut recording, Command::Release);
+					mouse_dragging = false;
+				}
+			}
+		}
 
-	for i in 0..=14 {
-		result.push(
-			VerletObject::new(
-				vec2(WINDOW_WIDTH * 0.5 - 210.0 + i as f32 * 30.0, WINDOW_HEIGHT * 0.5 + 100.0),
-				10.0,
-			)
-		);
-	}
+		// letting go spawns one where the button came down, going the way the mouse was dragged
+		// and faster the further it went. a click without a drag drops it in still
+		if let Some(start) = flick.filter(|_| !input.down(Action::Spawn)) {
+			let velocity = flick_velocity(start, mouse);
+			let tag = if input.down(Action::Alternate) { CRATE_TAG } else { 0 };
+			send_command(&mut master, &mut spawner, &mut recording, Command::Spawn { position: start, radius: None, tag, velocity });
+			flick = None;
+		}
 
-	result
-}
+		#[cfg(feature = "render")]
+		if input.pressed(Action::Glow) {
+			render_mode = render_mode.toggle(RenderMode::Glow);
+		}
 
-pub fn generate_chain_links() -> Vec<ChainLink> {
-	let mut result = vec![];
+		#[cfg(feature = "render")]
+		if input.pressed(Action::Pixel) {
+			render_mode = render_mode.toggle(RenderMode::Pixel);
+		}
 
-	for i in 1..=14 {
-		result.push(
-			ChainLink {
-				a: i - 1,
-				b: i,
-				target_distance: 30.0,
+		if input.pressed(Action::SaveSvg) {
+			let path = export::next_free_path(".", "frame", "svg");
+			if let Err(error) = export::export_svg(&master, &path) {
+				eprintln!("couldn't export {}: {}", path.display(), error);
 			}
-		);
-	}
+		}
 
-	result
-}
+		if input.pressed(Action::RecordPointCloud) {
+			if point_cloud_recorder.is_some() {
+				point_cloud_recorder = None;
+			} else {
+				let format = if input.down(Action::Alternate) {
+					export::PointCloudFormat::Obj
+				} else {
+					export::PointCloudFormat::Ply
+				};
 
-#[macroquad::main(window_conf)]
-async fn main() {
-	let mut master = Master {
-		objects: generate_objects(),
-		chain_links: generate_chain_links(),
-	};
+				let directory = export::next_free_path(".", "point_clouds", "");
+				match export::PointCloudRecorder::new(&directory, format) {
+					Ok(recorder) => point_cloud_recorder = Some(recorder),
+					Err(error) => eprintln!("couldn't create {}: {}", directory.display(), error),
+				}
+			}
+		}
+
+		if input.pressed(Action::ColorMode) {
+			view.color_mode = view.color_mode.next();
+		}
 
-	let mut mouse_timer = 0.0;
+		if input.pressed(Action::Ropes) {
+			view.ropes = !view.ropes;
+		}
 
-	loop {
-		if is_mouse_button_down(MouseButton::Left) {
-			mouse_timer -= delta_time();
-			if mouse_timer <= 0.0 {
-				mouse_timer = 10.0;
-				master.objects.push(
-					VerletObject::new(
-						vec2(WINDOW_WIDTH * 0.5 + 180.0, WINDOW_HEIGHT * 0.5),
-						gen_range(10.0, 40.0),
-					)
-				);
+		if input.pressed(Action::SoftBodies) {
+			view.soft_bodies = !view.soft_bodies;
+		}
+
+		if input.pressed(Action::Cloth) {
+			view.cloth = !view.cloth;
+			// pins the texture to the cloth as it is right now
+			view.cloth_uvs = skin::face_uvs(&master, &skin::find_faces(&master));
+		}
+
+		if input.pressed(Action::ImpactEffects) {
+			view.effects.shake = !view.effects.shake && !view.reduced_motion;
+			view.effects.sparks_enabled = view.effects.shake;
+		}
+
+		if input.pressed(Action::ReducedMotion) {
+			view.reduced_motion = !view.reduced_motion;
+			if view.reduced_motion {
+				view.effects.shake = false;
+				view.effects.sparks_enabled = false;
 			}
-		} else {
-			mouse_timer = 0.0;
 		}
 
-		master.update(delta_time());
+		if input.pressed(Action::HighContrast) {
+			view.high_contrast = !view.high_contrast;
+		}
 
-		clear_background(Color {
-			r: 0.09,
-			g: 0.09,
-			b: 0.12,
-			a: 1.0,
-		});
+		if input.pressed(Action::Palette) {
+			master.set_palette(master.palette.next());
+		}
 
-		draw_circle(
-			WINDOW_WIDTH * 0.5,
-			WINDOW_HEIGHT * 0.5,
-			CONSTRAINT_RADIUS,
-			BLACK,
-		);
+		if input.pressed(Action::Glue) {
+			let selection = master.objects_in_radius(mouse, GLUE_SELECTION_RADIUS);
+			master.glue_objects(&selection, GLUE_RADIUS);
+		}
+
+		if input.pressed(Action::Histograms) {
+			histograms = !histograms;
+		}
+
+		if input.pressed(Action::Grid) {
+			grid = !grid;
+		}
+		if input.pressed(Action::Pause) {
+			paused = !paused;
+		}
+		let step = paused && input.pressed(Action::Step);
+		if input.pressed(Action::Faster) {
+			speed = (speed + 1).min(TIME_SCALES.len() - 1);
+		}
+		if input.pressed(Action::Slower) {
+			speed = speed.saturating_sub(1);
+		}
+		if grid && input.pressed(Action::WiderGrid) {
+			grid_spacing = grid::step_spacing(grid_spacing, true);
+		}
+		if grid && input.pressed(Action::NarrowerGrid) {
+			grid_spacing = grid::step_spacing(grid_spacing, false);
+		}
+		if input.pressed(Action::FieldLines) {
+			field_lines = !field_lines;
+		}
+
+		if input.pressed(Action::DebugOverlay) {
+			debug_overlay = !debug_overlay;
+		}
+
+		// picks the broadphase's cell size instead of leaving it to fit the biggest object, and
+		// with shift goes back to that
+		if debug_overlay && (input.pressed(Action::BiggerCells) || input.pressed(Action::SmallerCells)) {
+			let cell_size = master.broadphase.cell_size;
+			master.cell_size = match (input.down(Action::Alternate), input.pressed(Action::BiggerCells)) {
+				(true, _) => None,
+				(false, true) => Some(cell_size + 5.0),
+				(false, false) => Some((cell_size - 5.0).max(BROADPHASE_MIN_CELL_SIZE)),
+			};
+		}
+
+		// ties everything linked straight to whatever it hangs from
+		if input.pressed(Action::LongRange) {
+			if master.attachments.is_empty() {
+				let anchors: Vec<usize> = master.chain_links.iter()
+					.flat_map(|chain_link| [chain_link.a, chain_link.b])
+					.filter(|&id| master.objects[id].kinematic)
+					.collect();
+				master.attach_long_range(&anchors);
+			} else {
+				master.attachments.clear();
+			}
+		}
 
-		for object in master.objects.iter() {
-			draw_circle(
-				object.position.x,
-				object.position.y,
-				object.radius,
-				object.color,
+		if input.pressed(Action::FrameBudget) && !exact {
+			master.frame_budget = match master.frame_budget {
+				Some(_) => None,
+				None => Some(FRAME_BUDGET),
+			};
+		}
+
+		if input.pressed(Action::FpsCap) {
+			let index = FPS_CAPS.iter().position(|&cap| cap == fps_cap).map_or(0, |index| index + 1);
+			fps_cap = FPS_CAPS[index % FPS_CAPS.len()];
+		}
+
+		if input.pressed(Action::Vsync) {
+			vsync = !vsync;
+		}
+
+		if input.pressed(Action::Tilt) {
+			tilt = match tilt {
+				Some(gravity) => {
+					send_command(&mut master, &mut spawner, &mut recording, Command::SetGravity { gravity });
+					None
+				}
+				None => Some(master.gravity),
+			};
+		}
+
+		if let Some(gravity) = tilt {
+			let gravity = tilt_gravity(mouse, gravity.length());
+			if gravity != master.gravity {
+				send_command(&mut master, &mut spawner, &mut recording, Command::SetGravity { gravity });
+			}
+		}
+
+		// everything pulling on everything else instead of falling, a scene that starts with
+		// it on keeps its gravity
+		if input.pressed(Action::NBody) {
+			if master.n_body.take().is_none() {
+				master.n_body = Some(NBody::new(N_BODY_STRENGTH));
+				n_body_gravity = Some(master.gravity);
+				send_command(&mut master, &mut spawner, &mut recording, Command::SetGravity { gravity: Vec2::ZERO });
+			} else if let Some(gravity) = n_body_gravity.take() {
+				send_command(&mut master, &mut spawner, &mut recording, Command::SetGravity { gravity });
+			}
+		}
+
+		if input.pressed(Action::TimeReversal) {
+			time_reversal = Some(TimeReversal::start(&master, REVERSAL_WINDOW));
+		}
+
+		touch.update(&mut master, &mut commands, &view.camera, delta);
+
+		#[cfg(feature = "gamepad")]
+		let time_scale = match &mut gamepad {
+			Some(gamepad) => gamepad.update(&mut master, &mut commands, delta),
+			None => 1.0,
+		};
+		for command in commands.drain(..) {
+			send_command(&mut master, &mut spawner, &mut recording, command);
+		}
+		#[cfg(not(feature = "gamepad"))]
+		let time_scale = 1.0;
+		let time_scale = time_scale * timeline.time_scale.unwrap_or(1.0) * TIME_SCALES[speed];
+		let time_scale = if view.reduced_motion { time_scale * REDUCED_MOTION_TIME_SCALE } else { time_scale };
+
+		#[cfg(feature = "egui")]
+		let tuning = tuning_panel.open;
+		#[cfg(not(feature = "egui"))]
+		let tuning = false;
+		let reversing = time_reversal.as_ref().is_some_and(|reversal| reversal.phase != ReversalPhase::Done);
+		let busy = reversing
+			|| timeline.is_running()
+			|| chaos.as_ref().is_some_and(|chaos| chaos.enabled)
+			|| spawner.rate > 0.0
+			|| bindings_screen.open
+			|| checkpoint_panel.open
+			|| group_panel.open
+			|| automation_panel.open
+			|| tuning
+			|| mouse_delta_position() != Vec2::ZERO
+			|| mouse_wheel() != (0.0, 0.0)
+			|| get_last_key_pressed().is_some()
+			|| Action::ALL.iter().any(|&action| input.down(action))
+			|| !touches().is_empty();
+		let asleep = !exact && idle.update(&master, busy, delta);
+
+		match &mut time_reversal {
+			Some(reversal) if reversal.phase != ReversalPhase::Done => {
+				reversal.update(&mut master);
+				timestep.reset();
+			}
+			// a step is drawn where it ends up, with nothing in between
+			_ if paused => {
+				if step {
+					timestep.reset();
+					timestep.update(&mut master, 1.0);
+					timestep.reset();
+				}
+			}
+			_ if asleep => {}
+			// the frame it wakes up on took as long as an idle frame, which would be a lot to
+			// catch up on
+			_ if idle.woke => {
+				timestep.reset();
+				timestep.update(&mut master, time_scale);
+			}
+			_ => timestep.update(&mut master, delta * time_scale),
+		}
+
+		if master.stats.escalated {
+			eprintln!(
+				"penetration of {:.1} at {:.1}, raised substeps to {}",
+				master.stats.max_penetration,
+				master.stats.time,
+				master.watchdog.substeps(master.substeps),
+			);
+		}
+
+		plot.update(&master);
+		for trail in timeline.trails.iter_mut() {
+			trail.update(&master);
+		}
+
+		if let Some(recorder) = &mut point_cloud_recorder {
+			if let Err(error) = recorder.record(&master) {
+				eprintln!("stopped recording point clouds: {}", error);
+				point_cloud_recorder = None;
+			}
+		}
+
+		let current = settings::Settings {
+			debug_overlay,
+			histograms,
+			grid,
+			grid_spacing,
+			field_lines,
+			ropes: view.ropes,
+			soft_bodies: view.soft_bodies,
+			cloth: view.cloth,
+			impact_effects: view.effects.shake,
+			reduced_motion: view.reduced_motion,
+			high_contrast: view.high_contrast,
+			input: input.clone(),
+			fps_cap,
+			vsync,
+			..settings.clone()
+		};
+		if current != settings {
+			settings = current;
+			if let Err(error) = settings.save() {
+				eprintln!("couldn't save the settings: {}", error);
+			}
+		}
+
+		view.heat_map = debug_overlay;
+		view.grid_spacing = grid.then_some(grid_spacing);
+		view.effects.update(&master);
+		view.shake = view.effects.offset();
+
+		// the world's drawn partway between the last two physics frames
+		timestep.interpolate(&master);
+		let positions = &timestep.positions;
+
+		#[cfg(feature = "render")]
+		match render_mode {
+			RenderMode::Flat => flat.draw(&master, positions, &view),
+			RenderMode::Glow => glow.draw(&master, positions, &view),
+			RenderMode::Pixel => pixel.draw(&master, positions, &view),
+		}
+
+		#[cfg(not(feature = "render"))]
+		flat.draw(&master, positions, &view);
+
+		// the tools drawn over the world don't shake with it
+		set_camera(&view.camera.camera(Vec2::ZERO));
+		if field_lines {
+			field_lines::draw_field_lines(&master, view.camera.zoom);
+		}
+		for trail in timeline.trails.iter() {
+			trail.draw(&master, view.camera.zoom);
+		}
+		measure.draw(&master, mouse, view.camera.zoom);
+		editor.draw(&master, mouse, view.camera.zoom);
+		cannon_controls.draw(&master, &timeline.cannons);
+		plot.draw_selection(&master, view.camera.zoom);
+		if let Some(aim) = &launch {
+			aim.draw(&master, mouse);
+		}
+		if let Some(start) = flick {
+			draw_flick(start, mouse, view.camera.zoom);
+		}
+
+		// which way is down right now
+		if tilt.is_some() {
+			let center = vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5);
+			let end = center + master.gravity * 60.0;
+			draw_line(center.x, center.y, end.x, end.y, 3.0, Color::new(1.0, 1.0, 1.0, 0.5));
+			draw_circle(end.x, end.y, 5.0, Color::new(1.0, 1.0, 1.0, 0.5));
+		}
+
+		// the shake only moves the world, not the text over it
+		set_default_camera();
+
+		if let Some(spacing) = view.grid_spacing {
+			grid::draw_grid_labels(&view.camera, spacing);
+		}
+
+		if debug_overlay {
+			draw_text(
+				&format!(
+					"SUBSTEPS: {}{}  PENETRATION: {:.2}",
+					master.stats.substeps,
+					if master.stats.degraded { " (OVER BUDGET)" } else { "" },
+					master.stats.max_penetration,
+				),
+				20.0,
+				screen_height() - 60.0,
+				32.0,
+				WHITE,
+			);
+
+			draw_text(
+				&format!("CELL SIZE: {} {} (-/=, SHIFT FOR AUTO)", master.broadphase.cell_size, if master.cell_size.is_some() { "SET" } else { "AUTO" }),
+				20.0,
+				screen_height() - 20.0,
+				32.0,
+				WHITE,
 			);
 		}
 
 		draw_text(
-			&format!("FPS: {}", get_fps()),
+			&format!(
+				"FPS: {}{}{}",
+				get_fps(),
+				fps_cap.map(|fps| format!(" (CAP {})", fps)).unwrap_or_default(),
+				if vsync != vsync_at_start { " (VSYNC CHANGES ON RESTART)" } else { "" },
+			),
 			20.0,
 			30.0,
 			32.0,
@@ -252,10 +982,1085 @@ async fn main() {
 			WHITE,
 		);
 
+		#[cfg(feature = "gamepad")]
+		if let Some(gamepad) = gamepad.as_ref().filter(|gamepad| gamepad.is_connected()) {
+			gamepad.draw_cursor(&view.camera);
+		}
+
+		if let Some(reversal) = &time_reversal {
+			let text = match reversal.phase {
+				ReversalPhase::Forward => "TIME REVERSAL: FORWARD".to_string(),
+				ReversalPhase::Backward => "TIME REVERSAL: BACKWARD".to_string(),
+				ReversalPhase::Done => format!("TIME REVERSAL ERROR: {:.4}", reversal.error),
+			};
+
+			draw_text(
+				&text,
+				20.0,
+				130.0,
+				32.0,
+				WHITE,
+			);
+		}
+
+		if let Some(chaos) = chaos.as_ref().filter(|chaos| chaos.enabled) {
+			draw_text(
+				&format!(
+					"CHAOS: SEED {}, {} EVENTS{}",
+					chaos.seed,
+					chaos.events,
+					chaos.last.map(|event| format!(", LAST {}", event.name().to_uppercase())).unwrap_or_default(),
+				),
+				20.0,
+				230.0,
+				32.0,
+				WHITE,
+			);
+		}
+
+		if let Some(recording) = &recording {
+			draw_text(
+				&format!("RECORDING: {} COMMANDS", recording.commands),
+				20.0,
+				280.0,
+				32.0,
+				RED,
+			);
+		}
+
+		if paused || TIME_SCALES[speed] != 1.0 {
+			draw_text(
+				&format!("{}{}x", if paused { format!("PAUSED ({} STEPS), ", input.binding(Action::Step).name().to_uppercase()) } else { String::new() }, TIME_SCALES[speed]),
+				20.0,
+				330.0,
+				32.0,
+				WHITE,
+			);
+		}
+
+		if let Some(tool) = measure.tool {
+			draw_text(
+				&format!("MEASURING: {}", tool.name()),
+				20.0,
+				180.0,
+				32.0,
+				WHITE,
+			);
+		}
+
+		if let Some(tool) = editor.tool {
+			draw_text(
+				&format!("BUILDING: {}", tool.name()),
+				20.0,
+				180.0,
+				32.0,
+				WHITE,
+			);
+		}
+
+		plot.draw();
+		if histograms {
+			histogram::draw_histograms(&master);
+		}
+		if let Some(game) = &timeline.game {
+			game.draw(&master);
+		}
+		checkpoint_panel.draw();
+		group_panel.draw(&master);
+		automation_panel.draw(&master, &timeline.automations);
+		bindings_screen.draw(&input);
+		#[cfg(feature = "egui")]
+		tuning_panel.draw();
+
+		frame_limiter.wait(match asleep {
+			true => Some(fps_cap.map_or(IDLE_FPS, |fps| fps.min(IDLE_FPS))),
+			false => fps_cap,
+		});
 		next_frame().await
 	}
 }
 
+// sleeps off whatever's left of the frame, so it doesn't run any faster than the cap
+pub struct FrameLimiter {
+	last_frame: Instant,
+}
+
+impl Default for FrameLimiter {
+	fn default() -> Self {
+		Self {
+			last_frame: Instant::now(),
+		}
+	}
+}
+
+impl FrameLimiter {
+	pub fn wait(&mut self, fps: Option<u32>) {
+		if let Some(fps) = fps.filter(|&fps| fps > 0) {
+			let frame_time = Duration::from_secs_f64(1.0 / fps as f64);
+			if let Some(left) = frame_time.checked_sub(self.last_frame.elapsed()) {
+				std::thread::sleep(left);
+			}
+		}
+		self.last_frame = Instant::now();
+	}
+}
+
+// notices when the scene has come to rest, see SLEEP_SPEED
+#[derive(Default)]
+pub struct IdleThrottle {
+	pub still_frames: f32,
+	pub asleep: bool,
+	// set for the one update where it wakes back up
+	pub woke: bool,
+	// object count, gravity and wind last frame, anything changing them from outside wakes it up
+	last: (usize, Vec2, Vec2),
+}
+
+impl IdleThrottle {
+	// busy is for anything going on outside the simulation, like input. returns whether it's asleep
+	pub fn update(&mut self, master: &Master, busy: bool, delta: f32) -> bool {
+		let state = (master.objects.len(), master.gravity, master.wind);
+		let awake = busy
+			|| state != self.last
+			|| master.drag.is_some()
+			|| !master.touches.is_empty()
+			|| master.max_speed() > SLEEP_SPEED;
+		self.last = state;
+
+		self.woke = self.asleep && awake;
+		if awake {
+			self.still_frames = 0.0;
+			self.asleep = false;
+		} else {
+			self.still_frames += delta;
+			self.asleep = self.still_frames >= IDLE_AFTER;
+		}
+		self.asleep
+	}
+}
+
+// steps the simulation a whole physics frame (a 60th of a second) at a time, as many as the
+// time that's gone by adds up to, so it does the same thing at any frame rate. whatever's
+// left over is how far to draw between the last two
+#[derive(Default)]
+pub struct FixedTimestep {
+	// physics frames of time that haven't been stepped yet, under 1 after an update
+	pub accumulator: f32,
+	// where each object was before the last step
+	previous: Vec<(ObjectId, Vec2)>,
+	// where to draw everything this frame, by id, see interpolate()
+	pub positions: Vec<Vec2>,
+}
+
+impl FixedTimestep {
+	// frames is how much time has gone by, in 60ths of a second
+	pub fn update(&mut self, master: &mut Master, frames: f32) {
+		self.accumulator = (self.accumulator + frames).min(MAX_CATCH_UP);
+		while self.accumulator >= 1.0 {
+			self.previous.clear();
+			self.previous.extend(master.objects.ids().filter_map(|id| Some((master.object_id(id)?, master.objects[id].position))));
+			master.update(1.0);
+			self.accumulator -= 1.0;
+		}
+	}
+
+	// for when the simulation's been changed some other way, so there's nothing to draw
+	// between
+	pub fn reset(&mut self) {
+		self.accumulator = 0.0;
+		self.previous.clear();
+	}
+
+	// works out positions, partway between where everything was and where it is. the objects
+	// themselves are left alone, so anything reading their speed still gets the real one.
+	// objects that weren't there for the last step are drawn where they are
+	pub fn interpolate(&mut self, master: &Master) {
+		object_positions(master, &mut self.positions);
+		for &(id, previous) in self.previous.iter() {
+			if let Some(object) = master.object(id) {
+				self.positions[id.index] = previous.lerp(object.position, self.accumulator);
+			}
+		}
+	}
+}
+
+// spawns objects on its own at a steady rate, without holding the mouse. it also keeps how big
+// anything spawned without a radius gets and how many objects there can be before spawns stop
+pub struct Spawner {
+	// objects per second
+	pub rate: f32,
+	pub timer: f32,
+	// the smallest and biggest radius
+	pub sizes: (f32, f32),
+	pub max_objects: Option<usize>,
+}
+
+impl Default for Spawner {
+	fn default() -> Self {
+		Self { rate: 0.0, timer: 0.0, sizes: SPAWN_SIZES, max_objects: None }
+	}
+}
+
+impl Spawner {
+	// how many more there's room for
+	pub fn room(&self, master: &Master) -> usize {
+		self.max_objects.map_or(usize::MAX, |max| max.saturating_sub(master.objects.len()))
+	}
+
+	pub fn update(&mut self, seconds: f32, commands: &mut Vec<Command>) {
+		if self.rate <= 0.0 {
+			return;
+		}
+
+		self.timer += seconds * self.rate;
+		while self.timer >= 1.0 {
+			self.timer -= 1.0;
+			commands.push(Command::Spawn { position: SPAWN_POINT, radius: None, tag: 0, velocity: Vec2::ZERO });
+		}
+	}
+}
+
+// everything that can be done to a running simulation from outside of it (osc, the remote api, keys)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "remote", derive(serde::Deserialize))]
+#[cfg_attr(feature = "remote", serde(tag = "command", rename_all = "snake_case"))]
+pub enum Command {
+	// a radius picked at random from the spawner's sizes without one
+	Spawn {
+		position: Vec2,
+		radius: Option<f32>,
+		#[cfg_attr(feature = "remote", serde(default))]
+		tag: u32,
+		// per substep like position - last_position, still when it's left off
+		#[cfg_attr(feature = "remote", serde(default))]
+		velocity: Vec2,
+	},
+	// this many objects scattered around the position
+	SpawnBurst {
+		position: Vec2,
+		count: usize,
+		radius: f32,
+	},
+	Explode {
+		position: Vec2,
+		strength: f32,
+	},
+	// changes the velocity of one object, or every object when there's no id
+	Impulse {
+		id: Option<usize>,
+		impulse: Vec2,
+	},
+	BreakLink {
+		id: usize,
+	},
+	// puts back one of master.broken_links, counting from 0 for the oldest
+	RepairLink {
+		index: usize,
+	},
+	// takes an object out along with its links
+	Despawn {
+		id: usize,
+	},
+	// a shot like a cannon's, see cannon::fire()
+	Fire {
+		position: Vec2,
+		velocity: Vec2,
+		radius: f32,
+		#[cfg_attr(feature = "remote", serde(default))]
+		spin: f32,
+	},
+	// switches every link in one of master.groups off or back on
+	SetGroup {
+		group: usize,
+		enabled: bool,
+	},
+	SetGravity {
+		gravity: Vec2,
+	},
+	SetWind {
+		wind: Vec2,
+	},
+	SetSpawnRate {
+		rate: f32,
+	},
+	// pulls an object towards the target, like the mouse does, until it's released
+	Grab {
+		id: usize,
+		target: Vec2,
+		hops: usize,
+	},
+	MoveGrab {
+		target: Vec2,
+	},
+	Release,
+	QueryStats,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "remote", derive(serde::Serialize))]
+pub struct StatsReport {
+	pub time: f32,
+	pub fps: i32,
+	pub objects: usize,
+	pub chain_links: usize,
+	pub gravity: Vec2,
+	pub wind: Vec2,
+	pub spawn_rate: f32,
+	pub drag_force: Option<Vec2>,
+}
+
+// run_command() for anything coming from outside the simulation, which goes into the
+// recording first when there is one. the timeline's own commands don't, they'd only
+// happen twice when it's played back
+fn send_command(master: &mut Master, spawner: &mut Spawner, recording: &mut Option<replay::Recording>, command: Command) -> Option<StatsReport> {
+	if let Some(recording) = recording {
+		recording.record(master, command);
+	}
+	run_command(master, spawner, command)
+}
+
+// only QueryStats has anything to say back
+pub fn run_command(master: &mut Master, spawner: &mut Spawner, command: Command) -> Option<StatsReport> {
+	match command {
+		Command::Spawn { position, radius, tag, velocity } => {
+			if spawner.room(master) == 0 {
+				return None;
+			}
+			let radius = radius.unwrap_or_else(|| master.random_range(spawner.sizes.0, spawner.sizes.1));
+			let index = spawn(master, position, radius, tag);
+			master.objects[index].last_position = position - velocity;
+		}
+		Command::SpawnBurst { position, count, radius } => {
+			// spread over about as much room as they'd take up packed together
+			let spread = radius * (count as f32).sqrt();
+			for _ in 0..count.min(spawner.room(master)) {
+				let offset = vec2(master.random_range(-spread, spread), master.random_range(-spread, spread));
+				let color = master.random_color();
+				master.objects.push(VerletObject::new(position + offset, radius).with_color(color));
+			}
+		}
+		Command::Explode { position, strength } => master.explode(position, strength, EXPLOSION_RADIUS),
+		Command::Impulse { id, impulse } => {
+			for (i, object) in master.objects.indexed_mut() {
+				if (id.is_none() || id == Some(i)) && !object.kinematic {
+					object.last_position -= impulse;
+				}
+			}
+		}
+		Command::BreakLink { id } => {
+			master.break_link(id);
+		}
+		Command::RepairLink { index } => {
+			master.repair_link(index);
+		}
+		Command::Despawn { id } => {
+			master.remove_object(id);
+		}
+		Command::Fire { position, velocity, radius, spin } => {
+			cannon::fire(master, position, velocity, radius, spin);
+		}
+		Command::SetGroup { group, enabled } => {
+			master.set_group_enabled(group, enabled);
+		}
+		Command::SetGravity { gravity } => master.gravity = gravity,
+		Command::SetWind { wind } => master.wind = wind,
+		Command::SetSpawnRate { rate } => spawner.rate = rate.max(0.0),
+		Command::Grab { id, target, hops } => {
+			if master.objects.contains_index(id) {
+				master.start_drag(id, target, hops);
+			}
+		}
+		Command::MoveGrab { target } => {
+			if let Some(drag) = &mut master.drag {
+				drag.target = target;
+			}
+		}
+		Command::Release => master.stop_drag(),
+		Command::QueryStats => {
+			return Some(StatsReport {
+				time: master.stats.time,
+				fps: get_fps(),
+				objects: master.objects.len(),
+				chain_links: master.chain_links.len(),
+				gravity: master.gravity,
+				wind: master.wind,
+				spawn_rate: spawner.rate,
+				drag_force: master.drag.as_ref()
+					.and_then(|_| master.stats.drag_force.back())
+					.map(|&(_, force)| force),
+			});
+		}
+	}
+
+	None
+}
+
+// gravity pulling from the center of the screen towards the point, like tilting the
+// container that way. it gets stronger the further out the point is, up to full strength
+pub fn tilt_gravity(point: Vec2, strength: f32) -> Vec2 {
+	let offset = point - vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5);
+	offset.normalize_or_zero() * strength * (offset.length() / TILT_RADIUS).min(1.0)
+}
+
+pub fn flick_velocity(start: Vec2, end: Vec2) -> Vec2 {
+	((end - start) * FLICK_STRENGTH).clamp_length_max(FLICK_MAX_SPEED)
+}
+
+// an arrow from where it'll spawn the way it'll go, as long as it is fast (past the fastest
+// it stops growing)
+pub fn draw_flick(start: Vec2, mouse: Vec2, zoom: f32) {
+	let color = Color::new(1.0, 1.0, 1.0, 0.6);
+	let thickness = 2.0 / zoom;
+	draw_circle_lines(start.x, start.y, 6.0 / zoom, thickness, color);
+	let end = start + flick_velocity(start, mouse) / FLICK_STRENGTH;
+	let Some(direction) = (end - start).try_normalize() else {
+		return;
+	};
+	draw_line(start.x, start.y, end.x, end.y, thickness, color);
+	for side in [-1.0, 1.0] {
+		let barb = end - (direction + direction.perp() * side * 0.5) * 12.0 / zoom;
+		draw_line(end.x, end.y, barb.x, barb.y, thickness, color);
+	}
+}
+
+pub fn spawn_object(master: &mut Master) -> usize {
+	let radius = master.random_range(SPAWN_SIZES.0, SPAWN_SIZES.1);
+	spawn(master, SPAWN_POINT, radius, 0)
+}
+
+pub fn spawn(master: &mut Master, position: Vec2, radius: f32, tag: u32) -> usize {
+	let color = master.random_color();
+	master.spawn(VerletObject::new(position, radius).with_color(color).with_tag(tag)).index
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorMode {
+	#[default]
+	Normal,
+	// blue when free, red with CONTACTS_FOR_RED or more objects touching it
+	Contacts,
+	// relative to whatever is under the most pressure right now
+	Pressure,
+}
+
+impl ColorMode {
+	pub fn next(&self) -> ColorMode {
+		match self {
+			ColorMode::Normal => ColorMode::Contacts,
+			ColorMode::Contacts => ColorMode::Pressure,
+			ColorMode::Pressure => ColorMode::Normal,
+		}
+	}
+
+	// one color per slot in master.objects, so it can be indexed by object id
+	pub fn colors(&self, master: &Master) -> Vec<Color> {
+		let heat = |amount: f32| {
+			let amount = amount.clamp(0.0, 1.0);
+			Color::new(0.2 + 0.8 * amount, 0.3 * (1.0 - amount), 1.0 - amount, 1.0)
+		};
+		let most_pressure = master.objects.iter().map(|object| object.pressure).fold(f32::EPSILON, f32::max);
+
+		(0..master.objects.slot_count()).map(|id| match master.objects.get(id) {
+			None => BLANK,
+			Some(object) => match self {
+				ColorMode::Normal => object.color.into(),
+				ColorMode::Contacts => heat(object.contacts as f32 / CONTACTS_FOR_RED),
+				ColorMode::Pressure => heat(object.pressure / most_pressure),
+			},
+		}).collect()
+	}
+}
+
+// how the renderers should show things, the same for all of them
+#[derive(Clone, Debug, Default)]
+pub struct View {
+	pub color_mode: ColorMode,
+	// draws the broadphase's cells behind the objects, by how crowded they are
+	pub heat_map: bool,
+	// the world grid and axes behind everything, with this spacing
+	pub grid_spacing: Option<f32>,
+	pub camera: camera::WorldCamera,
+	// linked objects drawn as smooth ropes instead of balls, textured if there's a texture
+	pub ropes: bool,
+	pub rope_texture: Option<Texture2D>,
+	// triangles and quads in the link graph filled in as a mesh, see skin::find_faces()
+	pub cloth: bool,
+	pub cloth_texture: Option<Texture2D>,
+	pub cloth_uvs: HashMap<usize, Vec2>,
+	// rings of linked objects drawn as one filled, outlined blob
+	pub soft_bodies: bool,
+	// textures or draw functions to use instead of circles, by object or tag
+	pub sprites: skin::Sprites,
+	// screen shake and sparks from big impacts, and how far the shake moves the camera this frame
+	pub effects: effects::Effects,
+	pub shake: Vec2,
+	// slower, and no shaking or sparks
+	pub reduced_motion: bool,
+	// outlines and a different pattern for every linked body, see skin::draw_high_contrast()
+	pub high_contrast: bool,
+}
+
+// anything that can show the current state of the simulation, with every object drawn at
+// positions[id] instead of where it is (see FixedTimestep::interpolate())
+pub trait Renderer {
+	fn draw(&mut self, master: &Master, positions: &[Vec2], view: &View);
+}
+
+// where every object is right now, by id, for drawing them there
+pub fn object_positions(master: &Master, positions: &mut Vec<Vec2>) {
+	positions.clear();
+	positions.extend((0..master.objects.slot_count()).map(|id| master.objects.get(id).map_or(Vec2::ZERO, |object| object.position)));
+}
+
+// plain circles straight to the screen
+pub struct FlatRenderer;
+
+impl Renderer for FlatRenderer {
+	fn draw(&mut self, master: &Master, positions: &[Vec2], view: &View) {
+		set_camera(&view.camera.camera(view.shake));
+		draw_world(master, positions, view);
+	}
+}
+
+pub fn draw_background(constraint: &Constraint) {
+	clear_background(Color {
+		r: 0.09,
+		g: 0.09,
+		b: 0.12,
+		a: 1.0,
+	});
+
+	match constraint {
+		Constraint::Circle { center, radius } => draw_circle(center.x, center.y, *radius, BLACK),
+		Constraint::Aabb { min, max } => draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, BLACK),
+		// a fan from the first corner, which covers any convex shape
+		Constraint::ConvexPolygon(points) => {
+			for pair in points.windows(2).skip(1) {
+				draw_triangle(points[0], pair[0], pair[1], BLACK);
+			}
+		}
+		Constraint::None => {}
+	}
+}
+
+pub fn draw_world(master: &Master, positions: &[Vec2], view: &View) {
+	draw_background(&master.constraint);
+	draw_kill_zones(master);
+	draw_vector_field(master);
+	draw_segments(master);
+
+	if let Some(spacing) = view.grid_spacing {
+		grid::draw_grid(&view.camera, spacing);
+	}
+
+	if view.heat_map {
+		draw_heat_map(master);
+	}
+
+	draw_master(master, positions, view);
+}
+
+// faintly, so they don't hide anything going into them. a zone that goes on forever only gets
+// drawn as far as anyone would scroll
+pub fn draw_kill_zones(master: &Master) {
+	for zone in master.kill_zones.iter() {
+		let min = zone.min.max(Vec2::splat(-10000.0));
+		let max = zone.max.min(Vec2::splat(10000.0));
+		draw_rectangle(min.x, min.y, max.x - min.x, max.y - min.y, Color::new(0.8, 0.1, 0.1, 0.15));
+	}
+}
+
+// a faint arrow across the middle of every square that pushes, as long as the square at the
+// strongest a field can be painted
+pub fn draw_vector_field(master: &Master) {
+	let Some(field) = &master.vector_field else {
+		return;
+	};
+	let color = Color::new(0.5, 0.8, 1.0, 0.35);
+	for (cell, &push) in field.cells.iter().enumerate() {
+		let Some(direction) = push.try_normalize() else {
+			continue;
+		};
+		let arrow = push / editor::FIELD_MAX_STRENGTH * field.cell_size;
+		let (start, end) = (field.center(cell) - arrow * 0.5, field.center(cell) + arrow * 0.5);
+		draw_line(start.x, start.y, end.x, end.y, 1.5, color);
+		for side in [-1.0, 1.0] {
+			let barb = end - (direction + direction.perp() * side * 0.5) * field.cell_size * 0.15;
+			draw_line(end.x, end.y, barb.x, barb.y, 1.5, color);
+		}
+	}
+}
+
+// the same color as walls, with round ends like they collide
+pub fn draw_segments(master: &Master) {
+	let color = scene::WALL_COLOR.into();
+	for segment in master.segments.iter() {
+		let (a, b, radius) = (segment.a, segment.b, segment.thickness * 0.5);
+		draw_line(a.x, a.y, b.x, b.y, segment.thickness, color);
+		draw_circle(a.x, a.y, radius, color);
+		draw_circle(b.x, b.y, radius, color);
+	}
+}
+
+pub fn draw_master(master: &Master, positions: &[Vec2], view: &View) {
+	let colors = view.color_mode.colors(master);
+
+	let layer = |id: usize| match &master.drag {
+		Some(drag) if drag.id == id => DRAG_LAYER,
+		_ if master.touches.iter().any(|touch| touch.id == id) => DRAG_LAYER,
+		_ => master.objects[id].layer,
+	};
+
+	// within a layer bigger objects go first so they can't cover up the small ones
+	let mut draw_order: Vec<usize> = master.objects.ids().collect();
+	draw_order.sort_by(|&a, &b|
+		layer(a).cmp(&layer(b))
+			.then(master.objects[b].radius.total_cmp(&master.objects[a].radius))
+	);
+
+	// skins replace the objects they're made of, and the links between them. the mesh goes
+	// down first so anything still drawn as a ball sits on top
+	let mut skinned = HashSet::new();
+
+	let faces = if view.cloth { skin::find_faces(master) } else { vec![] };
+	if !faces.is_empty() {
+		skin::draw_faces(positions, &faces, &colors, view.cloth_texture, &view.cloth_uvs);
+		skinned.extend(faces.iter().flatten().copied());
+	}
+
+	let ropes = if view.ropes || view.soft_bodies { skin::find_ropes(master) } else { vec![] };
+	let (soft_bodies, ropes): (Vec<skin::Rope>, Vec<skin::Rope>) = ropes.into_iter()
+		.partition(|rope| view.soft_bodies && rope.closed);
+	let ropes = if view.ropes { ropes } else { vec![] };
+	for rope in soft_bodies.iter().chain(ropes.iter()) {
+		skinned.extend(rope.ids.iter().copied());
+	}
+
+	draw_order.retain(|id| !skinned.contains(id));
+	let draw_links = || {
+		for soft_body in soft_bodies.iter() {
+			skin::draw_soft_body(positions, soft_body, &colors);
+		}
+		for rope in ropes.iter() {
+			skin::draw_rope(master, positions, rope, &colors, view.rope_texture);
+		}
+		draw_chain_links_outside(master, positions, &skinned);
+		draw_broken_links(master, positions);
+	};
+
+	let links_at = draw_order.iter().position(|&id| layer(id) > LINK_LAYER).unwrap_or(draw_order.len());
+	let groups = if view.high_contrast { skin::link_groups(master) } else { vec![] };
+
+	for (i, &id) in draw_order.iter().enumerate() {
+		if i == links_at {
+			draw_links();
+		}
+
+		let object = &VerletObject { position: positions[id], ..master.objects[id].clone() };
+		skin::draw_object(object, colors[id], view.sprites.get(id, object));
+		if let Some(bomb) = &object.bomb {
+			skin::draw_bomb(object, bomb);
+		}
+		if view.high_contrast {
+			skin::draw_high_contrast(object, groups[id]);
+		}
+	}
+
+	if links_at == draw_order.len() {
+		draw_links();
+	}
+
+	view.effects.draw();
+}
+
+// a wooden box with a cross brace, as big as the object
+pub fn draw_crate(object: &VerletObject, _color: Color) {
+	let size = object.radius * 2.0;
+	let (x, y) = (object.position.x - object.radius, object.position.y - object.radius);
+	let wood = Color::new(0.62, 0.42, 0.22, 1.0);
+	let dark = Color::new(0.36, 0.23, 0.11, 1.0);
+	let edge = (object.radius * 0.2).max(1.0);
+
+	draw_rectangle(x, y, size, size, wood);
+	draw_line(x, y, x + size, y + size, edge, dark);
+	draw_line(x + size, y, x, y + size, edge, dark);
+	draw_rectangle_lines(x, y, size, size, edge * 2.0, dark);
+}
+
+// the links that aren't part of a skin, see draw_master()
+pub fn draw_chain_links_outside(master: &Master, positions: &[Vec2], skinned: &HashSet<usize>) {
+	for chain_link in master.chain_links.iter() {
+		if skinned.contains(&chain_link.a) && skinned.contains(&chain_link.b) {
+			continue;
+		}
+
+		draw_chain_link(master, positions, chain_link);
+	}
+}
+
+// springs are drawn as zigzags, so they can be told apart from the links that don't give.
+// ones in a group that's switched off aren't drawn at all
+pub fn draw_chain_link(master: &Master, positions: &[Vec2], chain_link: &ChainLink) {
+	if !chain_link.is_enabled(&master.groups) {
+		return;
+	}
+	let (a, b) = (positions[chain_link.a], positions[chain_link.b]);
+	let color = link_color(master, chain_link);
+	if chain_link.spring.is_none() {
+		draw_line(a.x, a.y, b.x, b.y, 2.0, color);
+		return;
+	}
+
+	let across = (b - a).normalize_or_zero().perp() * 4.0;
+	let mut last = a;
+	for i in 1..=SPRING_COILS * 2 {
+		let point = match i == SPRING_COILS * 2 {
+			true => b,
+			false => a.lerp(b, i as f32 / (SPRING_COILS * 2) as f32) + if i % 2 == 0 { -across } else { across },
+		};
+		draw_line(last.x, last.y, point.x, point.y, 2.0, color);
+		last = point;
+	}
+}
+
+// gray, or for a link that breaks, redder the closer it is to breaking
+pub fn link_color(master: &Master, chain_link: &ChainLink) -> Color {
+	let Some(max_strain) = chain_link.max_strain else {
+		return GRAY;
+	};
+	let amount = (master.link_strain(chain_link) / max_strain).min(1.0);
+	Color::new(
+		GRAY.r + (RED.r - GRAY.r) * amount,
+		GRAY.g + (RED.g - GRAY.g) * amount,
+		GRAY.b + (RED.b - GRAY.b) * amount,
+		1.0,
+	)
+}
+
+// faint where the broken links were, to show where to click to put them back
+pub fn draw_broken_links(master: &Master, positions: &[Vec2]) {
+	for chain_link in master.broken_links.iter() {
+		if !master.objects.contains_index(chain_link.a) || !master.objects.contains_index(chain_link.b) {
+			continue;
+		}
+		let (a, b) = (positions[chain_link.a], positions[chain_link.b]);
+		draw_line(a.x, a.y, b.x, b.y, 2.0, Color::new(1.0, 0.3, 0.3, 0.25));
+	}
+}
+
+// the broken link whose middle is closest to the point, if any are within REPAIR_RADIUS
+pub fn broken_link_at(master: &Master, point: Vec2) -> Option<usize> {
+	master.broken_links.iter().enumerate()
+		.filter_map(|(index, chain_link)| {
+			let a = master.objects.get(chain_link.a)?.position;
+			let b = master.objects.get(chain_link.b)?.position;
+			Some((index, a.lerp(b, 0.5).distance(point)))
+		})
+		.filter(|&(_, distance)| distance <= REPAIR_RADIUS)
+		.min_by(|a, b| a.1.total_cmp(&b.1))
+		.map(|(index, _)| index)
+}
+
+pub fn draw_chain_links(master: &Master, positions: &[Vec2]) {
+	for chain_link in master.chain_links.iter() {
+		draw_chain_link(master, positions, chain_link);
+	}
+}
+
+// how many objects the broadphase has in each of its cells as of the last substep, each one
+// only in the cell its center is in, the way the spatial hash bins them
+pub fn cell_occupancy(master: &Master) -> HashMap<(i32, i32), usize> {
+	let mut result = HashMap::new();
+
+	for (_, cell) in master.broadphase.entries() {
+		*result.entry(cell).or_insert(0) += 1;
+	}
+
+	result
+}
+
+// the broadphase's cells behind the objects, translucent and redder the more crowded they are
+pub fn draw_heat_map(master: &Master) {
+	let cell_size = master.broadphase.cell_size;
+	let occupancy = cell_occupancy(master);
+	let most = occupancy.values().copied().max().unwrap_or(1) as f32;
+
+	for (&(x, y), &count) in occupancy.iter() {
+		let heat = count as f32 / most;
+		draw_rectangle(
+			x as f32 * cell_size,
+			y as f32 * cell_size,
+			cell_size,
+			cell_size,
+			Color::new(heat, 0.2 * (1.0 - heat), 1.0 - heat, 0.15 + 0.35 * heat),
+		);
+	}
+}
+
+#[cfg(feature = "render")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+	Flat,
+	Glow,
+	Pixel,
+}
+
+#[cfg(feature = "render")]
+impl RenderMode {
+	pub fn toggle(self, mode: RenderMode) -> RenderMode {
+		if self == mode {
+			RenderMode::Flat
+		} else {
+			mode
+		}
+	}
+}
+
+// camera for drawing the world into an offscreen texture, showing the same part of it as
+// the view's camera. render targets come out flipped, so this is y-up to cancel it out
+#[cfg(feature = "render")]
+pub fn offscreen_camera(target: RenderTarget, view: &View) -> Camera2D {
+	Camera2D {
+		target: view.camera.center + view.shake,
+		zoom: vec2(2.0 / screen_width(), 2.0 / screen_height()) * view.camera.zoom,
+		render_target: Some(target),
+		..Default::default()
+	}
+}
+
+#[cfg(feature = "render")]
+pub fn draw_to_screen(texture: Texture2D, material: Material) {
+	set_default_camera();
+
+	gl_use_material(material);
+	draw_texture_ex(
+		texture,
+		0.0,
+		0.0,
+		WHITE,
+		DrawTextureParams {
+			dest_size: Some(vec2(screen_width(), screen_height())),
+			..Default::default()
+		},
+	);
+	gl_use_default_material();
+}
+
+// draws the world additively into an offscreen texture, then blits it to the screen
+// through a bloom shader so bright overlapping objects glow
+#[cfg(feature = "render")]
+pub struct GlowRenderer {
+	pub target: RenderTarget,
+	pub additive: Material,
+	pub bloom: Material,
+}
+
+#[cfg(feature = "render")]
+impl GlowRenderer {
+	pub fn load() -> Self {
+		use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation};
+
+		let target = render_target(WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32);
+		target.texture.set_filter(FilterMode::Linear);
+
+		let additive = load_material(
+			POST_VERTEX_SHADER,
+			ADDITIVE_FRAGMENT_SHADER,
+			MaterialParams {
+				pipeline_params: PipelineParams {
+					color_blend: Some(BlendState::new(
+						Equation::Add,
+						BlendFactor::Value(BlendValue::SourceAlpha),
+						BlendFactor::One,
+					)),
+					..Default::default()
+				},
+				..Default::default()
+			},
+		).unwrap();
+
+		let bloom = load_material(
+			POST_VERTEX_SHADER,
+			BLOOM_FRAGMENT_SHADER,
+			MaterialParams {
+				uniforms: vec![("TexelSize".to_string(), UniformType::Float2)],
+				..Default::default()
+			},
+		).unwrap();
+		bloom.set_uniform("TexelSize", vec2(1.0 / WINDOW_WIDTH, 1.0 / WINDOW_HEIGHT));
+
+		Self {
+			target,
+			additive,
+			bloom,
+		}
+	}
+}
+
+#[cfg(feature = "render")]
+impl Renderer for GlowRenderer {
+	fn draw(&mut self, master: &Master, positions: &[Vec2], view: &View) {
+		set_camera(&offscreen_camera(self.target, view));
+
+		clear_background(BLACK);
+		if let Some(spacing) = view.grid_spacing {
+			grid::draw_grid(&view.camera, spacing);
+		}
+
+		gl_use_material(self.additive);
+		draw_master(master, positions, view);
+		gl_use_default_material();
+
+		draw_to_screen(self.target.texture, self.bloom);
+	}
+}
+
+// draws the world into a low resolution texture and scales it back up with nearest filtering,
+// snapping every pixel to a small fixed palette on the way out
+#[cfg(feature = "render")]
+pub struct PixelRenderer {
+	pub target: RenderTarget,
+	pub quantize: Material,
+}
+
+#[cfg(feature = "render")]
+impl PixelRenderer {
+	pub fn load() -> Self {
+		let target = render_target(
+			(WINDOW_WIDTH / PIXEL_SCALE) as u32,
+			(WINDOW_HEIGHT / PIXEL_SCALE) as u32,
+		);
+		target.texture.set_filter(FilterMode::Nearest);
+
+		let quantize = load_material(
+			POST_VERTEX_SHADER,
+			QUANTIZE_FRAGMENT_SHADER,
+			Default::default(),
+		).unwrap();
+
+		Self {
+			target,
+			quantize,
+		}
+	}
+}
+
+#[cfg(feature = "render")]
+impl Renderer for PixelRenderer {
+	fn draw(&mut self, master: &Master, positions: &[Vec2], view: &View) {
+		set_camera(&offscreen_camera(self.target, view));
+		draw_world(master, positions, view);
+		draw_to_screen(self.target.texture, self.quantize);
+	}
+}
+
+#[cfg(feature = "render")]
+const POST_VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+	gl_Position = Projection * Model * vec4(position, 1);
+	color = color0 / 255.0;
+	uv = texcoord;
+}
+";
+
+#[cfg(feature = "render")]
+const ADDITIVE_FRAGMENT_SHADER: &str = "#version 100
+precision lowp float;
+
+varying vec4 color;
+varying vec2 uv;
+
+uniform sampler2D Texture;
+
+void main() {
+	gl_FragColor = color * texture2D(Texture, uv) * vec4(1.0, 1.0, 1.0, 0.8);
+}
+";
+
+#[cfg(feature = "render")]
+const BLOOM_FRAGMENT_SHADER: &str = "#version 100
+precision mediump float;
+
+varying vec4 color;
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 TexelSize;
+
+void main() {
+	vec3 base = texture2D(Texture, uv).rgb;
+
+	// cheap blur over a couple of rings, only keeping the bright parts
+	vec3 glow = vec3(0.0);
+	for (int ring = 1; ring <= 3; ring++) {
+		float distance = float(ring) * 4.0;
+		for (int i = 0; i < 8; i++) {
+			float angle = float(i) * 0.785398;
+			vec2 offset = vec2(cos(angle), sin(angle)) * TexelSize * distance;
+			vec3 tap = texture2D(Texture, uv + offset).rgb;
+			glow += max(tap - vec3(0.4), vec3(0.0)) / float(ring);
+		}
+	}
+
+	gl_FragColor = vec4(base + glow * 0.25, 1.0) * color;
+}
+";
+
+// the PICO-8 palette
+#[cfg(feature = "render")]
+const QUANTIZE_FRAGMENT_SHADER: &str = "#version 100
+precision mediump float;
+
+varying vec4 color;
+varying vec2 uv;
+
+uniform sampler2D Texture;
+
+void pick(vec3 base, vec3 candidate, inout vec3 best, inout float best_distance) {
+	vec3 difference = base - candidate / 255.0;
+	float distance = dot(difference, difference);
+	if (distance < best_distance) {
+		best = candidate / 255.0;
+		best_distance = distance;
+	}
+}
+
+void main() {
+	vec3 base = texture2D(Texture, uv).rgb;
+
+	vec3 best = vec3(0.0);
+	float best_distance = 1000.0;
+	pick(base, vec3(0.0, 0.0, 0.0), best, best_distance);
+	pick(base, vec3(29.0, 43.0, 83.0), best, best_distance);
+	pick(base, vec3(126.0, 37.0, 83.0), best, best_distance);
+	pick(base, vec3(0.0, 135.0, 81.0), best, best_distance);
+	pick(base, vec3(171.0, 82.0, 54.0), best, best_distance);
+	pick(base, vec3(95.0, 87.0, 79.0), best, best_distance);
+	pick(base, vec3(194.0, 195.0, 199.0), best, best_distance);
+	pick(base, vec3(255.0, 241.0, 232.0), best, best_distance);
+	pick(base, vec3(255.0, 0.0, 77.0), best, best_distance);
+	pick(base, vec3(255.0, 163.0, 0.0), best, best_distance);
+	pick(base, vec3(255.0, 236.0, 39.0), best, best_distance);
+	pick(base, vec3(0.0, 228.0, 54.0), best, best_distance);
+	pick(base, vec3(41.0, 173.0, 255.0), best, best_distance);
+	pick(base, vec3(131.0, 118.0, 156.0), best, best_distance);
+	pick(base, vec3(255.0, 119.0, 168.0), best, best_distance);
+	pick(base, vec3(255.0, 204.0, 170.0), best, best_distance);
+
+	gl_FragColor = vec4(best, 1.0) * color;
+}
+";
+
 fn delta_time() -> f32 {
 	get_frame_time() * 60.0
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+use macroquad::prelude::*;
+
+use verlet_integration::Master;
+
+// how close a click has to be to a link to pick it
+pub const LINK_PICK_DISTANCE: f32 = 10.0;
+
+const MEASURE_COLOR: Color = Color::new(1.0, 0.9, 0.3, 1.0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+	// drag from one point to another for the distance between them
+	Ruler,
+	// click the end of one arm, the corner, then the end of the other arm
+	Angle,
+	// click a link to keep an eye on its length
+	Link,
+}
+
+impl Tool {
+	// off, then each tool in turn
+	pub fn next(tool: Option<Tool>) -> Option<Tool> {
+		match tool {
+			None => Some(Tool::Ruler),
+			Some(Tool::Ruler) => Some(Tool::Angle),
+			Some(Tool::Angle) => Some(Tool::Link),
+			Some(Tool::Link) => None,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Tool::Ruler => "RULER",
+			Tool::Angle => "ANGLE",
+			Tool::Link => "LINK LENGTH",
+		}
+	}
+}
+
+// the measuring tools, all in world units. they take over the spawn button while one is picked
+#[derive(Clone, Debug, Default)]
+pub struct Measure {
+	pub tool: Option<Tool>,
+	ruler: Option<(Vec2, Vec2)>,
+	angle: Vec<Vec2>,
+	pub link: Option<usize>,
+}
+
+impl Measure {
+	pub fn set_tool(&mut self, tool: Option<Tool>) {
+		*self = Measure {
+			tool,
+			..Default::default()
+		};
+	}
+
+	pub fn update(&mut self, master: &Master, mouse: Vec2, pressed: bool, down: bool) {
+		match self.tool {
+			Some(Tool::Ruler) => {
+				if pressed {
+					self.ruler = Some((mouse, mouse));
+				} else if down {
+					if let Some((_, end)) = &mut self.ruler {
+						*end = mouse;
+					}
+				}
+			}
+			Some(Tool::Angle) if pressed => {
+				if self.angle.len() == 3 {
+					self.angle.clear();
+				}
+				self.angle.push(mouse);
+			}
+			Some(Tool::Link) if pressed => self.link = closest_link(master, mouse),
+			_ => {}
+		}
+
+		// the link can go away under it
+		if self.link.is_some_and(|id| !master.chain_links.contains_index(id)) {
+			self.link = None;
+		}
+	}
+
+	// zoom is the camera's, so the lines and text stay the same size on screen
+	pub fn draw(&self, master: &Master, mouse: Vec2, zoom: f32) {
+		let thickness = 2.0 / zoom;
+		if let Some((start, end)) = self.ruler {
+			draw_line(start.x, start.y, end.x, end.y, thickness, MEASURE_COLOR);
+			// little ticks across each end
+			let across = (end - start).perp().normalize_or_zero() * 6.0 / zoom;
+			for point in [start, end] {
+				draw_line(point.x - across.x, point.y - across.y, point.x + across.x, point.y + across.y, thickness, MEASURE_COLOR);
+			}
+			label((start + end) * 0.5, zoom, &format!("{:.1}", start.distance(end)));
+		}
+
+		if !self.angle.is_empty() {
+			// the last arm follows the mouse until it's been clicked
+			let mut points = self.angle.clone();
+			if points.len() < 3 {
+				points.push(mouse);
+			}
+			for pair in points.windows(2) {
+				draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, thickness, MEASURE_COLOR);
+			}
+			if points.len() == 3 {
+				let corner = points[1];
+				let angle = (points[0] - corner).angle_between(points[2] - corner).abs().to_degrees();
+				draw_circle_lines(corner.x, corner.y, 16.0 / zoom, thickness * 0.5, MEASURE_COLOR);
+				label(corner + vec2(20.0, -20.0) / zoom, zoom, &format!("{:.1} DEG", angle));
+			}
+		}
+
+		if let Some(chain_link) = self.link.and_then(|id| master.chain_links.get(id)) {
+			let a = master.objects[chain_link.a].position;
+			let b = master.objects[chain_link.b].position;
+			let length = a.distance(b);
+			let strain = (length - chain_link.target_distance) / chain_link.target_distance.max(f32::EPSILON) * 100.0;
+			draw_line(a.x, a.y, b.x, b.y, thickness * 2.0, MEASURE_COLOR);
+			label((a + b) * 0.5, zoom, &format!("{:.1} / {:.1} ({:+.1}%)", length, chain_link.target_distance, strain));
+		}
+	}
+}
+
+fn label(position: Vec2, zoom: f32, text: &str) {
+	draw_text_ex(text, position.x + 8.0 / zoom, position.y - 8.0 / zoom, TextParams {
+		font_size: 24,
+		font_scale: 1.0 / zoom,
+		color: MEASURE_COLOR,
+		..Default::default()
+	});
+}
+
+pub fn closest_link(master: &Master, point: Vec2) -> Option<usize> {
+	master.chain_links.indexed()
+		.map(|(id, chain_link)| {
+			let a = master.objects[chain_link.a].position;
+			let b = master.objects[chain_link.b].position;
+			let t = ((point - a).dot(b - a) / (b - a).length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+			(id, point.distance(a.lerp(b, t)))
+		})
+		.filter(|&(_, distance)| distance <= LINK_PICK_DISTANCE)
+		.min_by(|a, b| a.1.total_cmp(&b.1))
+		.map(|(id, _)| id)
+}
@@ -0,0 +1,201 @@
+use alloc::vec::Vec;
+
+use glam::{vec3, Vec2};
+
+use crate::object::VerletObject;
+use crate::pool::Pool;
+use crate::{N_BODY_SOFTENING, N_BODY_THETA};
+
+// every object pulling every other towards it by strength times its mass over the distance
+// squared, in pixels a frame squared (like a magnet, but everything feels it). softening is
+// added onto the distance (squared, as if it were a third direction) so two right on top of
+// each other don't fling each other off, and theta is how rough Barnes-Hut gets: a far
+// enough group of objects pulls like one object of the whole mass at its middle once it's
+// less than theta times as wide as it is far away. 0 works it out for every pair exactly
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NBody {
+	pub strength: f32,
+	pub softening: f32,
+	pub theta: f32,
+}
+
+impl NBody {
+	pub fn new(strength: f32) -> Self {
+		Self {
+			strength,
+			softening: N_BODY_SOFTENING,
+			theta: N_BODY_THETA,
+		}
+	}
+
+	// what mass at from pulls something at to with
+	pub fn pull(&self, from: Vec2, mass: f32, to: Vec2) -> Vec2 {
+		let offset = from - to;
+		let distance = vec3(offset.x, offset.y, self.softening).length();
+		offset * self.strength * mass / (distance * distance * distance)
+	}
+
+	// sensors and anything without any mass are left out both ways, and pinned objects pull
+	// but don't get pulled
+	pub fn apply(&self, objects: &mut Pool<VerletObject>, tree: &mut QuadTree) {
+		tree.build(objects.indexed()
+			.filter(|(_, object)| !object.sensor && object.mass > 0.0)
+			.map(|(i, object)| (i, object.position, object.mass)));
+
+		for &(i, position, _) in tree.bodies.iter() {
+			if objects[i].kinematic {
+				continue;
+			}
+			let acceleration = tree.pull(self, i, position);
+			objects[i].accelerate(acceleration);
+		}
+	}
+}
+
+// a square split into four again and again until every object's in a square of its own,
+// with every square knowing how much mass is in it and where the middle of it is. it keeps
+// its buffers between builds, so it's only allocating while the scene's growing
+#[derive(Clone, Debug, Default)]
+pub struct QuadTree {
+	nodes: Vec<Node>,
+	// index, position and mass
+	bodies: Vec<(usize, Vec2, f32)>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Node {
+	center: Vec2,
+	half: f32,
+	mass: f32,
+	// the positions added up by mass while it's being built, and then over the mass, for
+	// the middle of it
+	middle: Vec2,
+	// the first of four, in the order quadrant() counts them
+	children: Option<usize>,
+	// which of bodies it holds, when it's a leaf
+	body: Option<usize>,
+}
+
+impl Node {
+	fn new(center: Vec2, half: f32) -> Self {
+		Self { center, half, ..Default::default() }
+	}
+
+	fn quadrant(&self, position: Vec2) -> usize {
+		(position.x >= self.center.x) as usize + 2 * (position.y >= self.center.y) as usize
+	}
+
+	fn add(&mut self, position: Vec2, mass: f32) {
+		self.mass += mass;
+		self.middle += position * mass;
+	}
+}
+
+// past this small a square everything in it is treated as being in the same place, so two
+// objects exactly on top of each other don't split it forever
+const SMALLEST_NODE: f32 = 0.01;
+
+impl QuadTree {
+	pub fn build(&mut self, bodies: impl Iterator<Item = (usize, Vec2, f32)>) {
+		self.nodes.clear();
+		self.bodies.clear();
+		self.bodies.extend(bodies);
+		if self.bodies.is_empty() {
+			return;
+		}
+
+		let (mut min, mut max) = (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN));
+		for &(_, position, _) in self.bodies.iter() {
+			min = min.min(position);
+			max = max.max(position);
+		}
+		let half = ((max - min).max_element() * 0.5).max(SMALLEST_NODE);
+		self.nodes.push(Node::new((min + max) * 0.5, half));
+
+		for body in 0..self.bodies.len() {
+			self.insert(body);
+		}
+		for node in self.nodes.iter_mut().filter(|node| node.mass > 0.0) {
+			node.middle /= node.mass;
+		}
+	}
+
+	fn insert(&mut self, body: usize) {
+		let (_, position, mass) = self.bodies[body];
+		let mut node = 0;
+		loop {
+			self.nodes[node].add(position, mass);
+			if let Some(children) = self.nodes[node].children {
+				node = children + self.nodes[node].quadrant(position);
+				continue;
+			}
+			let Some(other) = self.nodes[node].body else {
+				// an empty leaf, or a smallest one that just piles them up
+				if self.nodes[node].mass == mass {
+					self.nodes[node].body = Some(body);
+				}
+				return;
+			};
+			if self.nodes[node].half <= SMALLEST_NODE {
+				self.nodes[node].body = None;
+				return;
+			}
+
+			// split it and move the one that was there down a level, then carry on down
+			let (center, half) = (self.nodes[node].center, self.nodes[node].half * 0.5);
+			let children = self.nodes.len();
+			for quadrant in 0..4 {
+				let x = if quadrant % 2 == 0 { -half } else { half };
+				let y = if quadrant < 2 { -half } else { half };
+				self.nodes.push(Node::new(center + Vec2::new(x, y), half));
+			}
+			let (_, other_position, other_mass) = self.bodies[other];
+			let child = children + self.nodes[node].quadrant(other_position);
+			self.nodes[child].add(other_position, other_mass);
+			self.nodes[child].body = Some(other);
+			self.nodes[node].body = None;
+			self.nodes[node].children = Some(children);
+			node = children + self.nodes[node].quadrant(position);
+		}
+	}
+
+	// what everything but the object itself (index is its index in the pool) adds up to
+	// pulling it with at position
+	pub fn pull(&self, n_body: &NBody, index: usize, position: Vec2) -> Vec2 {
+		let mut total = Vec2::ZERO;
+		if self.nodes.is_empty() {
+			return total;
+		}
+		let mut stack = [0; 64];
+		let mut depth = 1;
+		while depth > 0 {
+			depth -= 1;
+			let node = &self.nodes[stack[depth]];
+			if node.mass <= 0.0 {
+				continue;
+			}
+			if let Some(body) = node.body {
+				if self.bodies[body].0 != index {
+					total += n_body.pull(node.middle, node.mass, position);
+				}
+				continue;
+			}
+			// squared on both sides, it's the same comparison without a square root
+			let width = node.half * 2.0;
+			let far = width * width < n_body.theta * n_body.theta * node.middle.distance_squared(position);
+			match node.children {
+				Some(children) if !far && depth + 4 <= stack.len() => {
+					for child in children..children + 4 {
+						stack[depth] = child;
+						depth += 1;
+					}
+				}
+				// far enough off to count as one, or a smallest one with several piled up
+				// (which is where this one is too, if it's in there, and adds nothing)
+				_ => total += n_body.pull(node.middle, node.mass, position),
+			}
+		}
+		total
+	}
+}
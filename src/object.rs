@@ -0,0 +1,159 @@
+use glam::Vec2;
+
+use crate::bomb::Bomb;
+use crate::color::Color;
+use crate::damping_curve::DampingCurve;
+use crate::magnet::Magnet;
+use crate::pool::Handle;
+use crate::Palette;
+
+// an object in Master::objects that stays pointing at the same one however many come and go,
+// and points at nothing once it's despawned (see Master::spawn())
+pub type ObjectId = Handle;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerletObject {
+	pub position: Vec2,
+	pub last_position: Vec2,
+	pub acceleration: Vec2,
+	pub radius: f32,
+	// collisions and links move the lighter of two objects more, in proportion. it starts
+	// out as the radius squared (everything the same density), and only the ratio matters
+	pub mass: f32,
+	pub color: Color,
+	pub layer: i32,
+	// the solver never looks at this, it's for games to tell kinds of objects apart
+	// (and for the demo to pick a sprite by)
+	pub tag: u32,
+	// moved by whoever owns it (an animation, a script) instead of by the solver. it still
+	// pushes everything else around, but nothing pushes back and gravity leaves it alone.
+	// left alone it stays put, which is how things get pinned in place
+	pub kinematic: bool,
+	// notices what overlaps it (see Master::sensor_events) but never pushes or gets pushed
+	pub sensor: bool,
+	// how much of the speed it hits something with it bounces back off with, 0 (the default)
+	// for not at all and 1 for all of it. the bouncier of the two goes for a pair of objects
+	pub restitution: f32,
+	// makes it a bumper: whatever runs into it gets knocked away at this much more speed, in
+	// pixels a frame, on top of any bounce. 0 for anything else
+	pub kick: f32,
+	// how much it slows down sliding along whatever it's touching (0 is ice, and the slipperier
+	// of two objects goes) and the fraction of its speed it loses every substep to the air, the
+	// master's when they're None
+	pub friction: Option<f32>,
+	pub damping: Option<f32>,
+	// in place of damping, for one that loses more the faster it goes
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub damping_curve: Option<DampingCurve>,
+	pub magnet: Option<Magnet>,
+	// how many frames it has left before it's despawned, counting down by the delta every
+	// update. None lasts forever
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub lifetime: Option<f32>,
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub bomb: Option<Bomb>,
+	// objects touching this one in the last substep
+	pub contacts: usize,
+	// total collision correction pushed onto this object over the last update
+	pub pressure: f32,
+}
+
+impl VerletObject {
+	pub fn new(position: Vec2, radius: f32) -> Self {
+		Self {
+			position,
+			last_position: position,
+			acceleration: Vec2::ZERO,
+			radius,
+			mass: radius * radius,
+			color: Palette::Random.color(),
+			layer: 0,
+			tag: 0,
+			kinematic: false,
+			sensor: false,
+			restitution: 0.0,
+			kick: 0.0,
+			friction: None,
+			damping: None,
+			damping_curve: None,
+			magnet: None,
+			lifetime: None,
+			bomb: None,
+			contacts: 0,
+			pressure: 0.0,
+		}
+	}
+
+	pub fn with_color(mut self, color: Color) -> Self {
+		self.color = color;
+		self
+	}
+
+	pub fn with_mass(mut self, mass: f32) -> Self {
+		self.mass = mass;
+		self
+	}
+
+	pub fn with_tag(mut self, tag: u32) -> Self {
+		self.tag = tag;
+		self
+	}
+
+	pub fn with_restitution(mut self, restitution: f32) -> Self {
+		self.restitution = restitution;
+		self
+	}
+
+	// pinned in place, knocking things away at kick (see above)
+	pub fn bumper(mut self, kick: f32) -> Self {
+		self.kinematic = true;
+		self.kick = kick;
+		self
+	}
+
+	pub fn with_magnet(mut self, magnet: Magnet) -> Self {
+		self.magnet = Some(magnet);
+		self
+	}
+
+	// in frames
+	pub fn with_lifetime(mut self, lifetime: f32) -> Self {
+		self.lifetime = Some(lifetime);
+		self
+	}
+
+	pub fn bomb(mut self, bomb: Bomb) -> Self {
+		self.bomb = Some(bomb);
+		self
+	}
+
+	pub fn kinematic(mut self) -> Self {
+		self.kinematic = true;
+		self
+	}
+
+	pub fn sensor(mut self) -> Self {
+		self.sensor = true;
+		self
+	}
+
+	// damping is the fraction of velocity lost per step
+	pub fn update_position(&mut self, delta: f32, damping: f32) {
+		let velocity = (self.position - self.last_position) * (1.0 - damping);
+
+		self.last_position = self.position;
+		self.position += velocity + self.acceleration * delta * delta;
+
+		self.acceleration = Vec2::ZERO;
+	}
+
+	// how fast it's going along direction this step, negative when it's going the other way
+	pub fn velocity_towards(&self, direction: Vec2) -> f32 {
+		(self.position - self.last_position).dot(direction)
+	}
+
+	pub fn accelerate(&mut self, acc: Vec2) {
+		self.acceleration += acc;
+	}
+}
@@ -0,0 +1,111 @@
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use macroquad::prelude::*;
+use rosc::{OscPacket, OscType};
+
+use verlet_integration::{EXPLOSION_STRENGTH, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::Command;
+
+pub const DEFAULT_OSC_ADDRESS: &str = "0.0.0.0:9000";
+
+// what incoming messages map to:
+//   /verlet/gravity x y
+//   /verlet/wind x y
+//   /verlet/spawn_rate objects_per_second
+//   /verlet/explode [x y [strength]]   (defaults to the middle of the window)
+pub struct OscListener {
+	pub socket: UdpSocket,
+	buffer: Vec<u8>,
+}
+
+impl OscListener {
+	pub fn bind(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+		let socket = UdpSocket::bind(address)?;
+		socket.set_nonblocking(true)?;
+
+		Ok(Self {
+			socket,
+			buffer: vec![0; rosc::decoder::MTU],
+		})
+	}
+
+	// everything that arrived since the last poll, never blocks
+	pub fn poll(&mut self) -> Vec<Command> {
+		let mut result = vec![];
+
+		while let Ok(size) = self.socket.recv(&mut self.buffer) {
+			if let Ok((_, packet)) = rosc::decoder::decode_udp(&self.buffer[..size]) {
+				collect_commands(packet, &mut result);
+			}
+		}
+
+		result
+	}
+}
+
+fn collect_commands(packet: OscPacket, result: &mut Vec<Command>) {
+	match packet {
+		OscPacket::Bundle(bundle) => {
+			for packet in bundle.content {
+				collect_commands(packet, result);
+			}
+		}
+		OscPacket::Message(message) => {
+			// controllers send all sorts of number types, so just treat everything as f32
+			let args: Vec<f32> = message.args.iter().filter_map(|arg| match arg {
+				OscType::Float(value) => Some(*value),
+				OscType::Double(value) => Some(*value as f32),
+				OscType::Int(value) => Some(*value as f32),
+				OscType::Long(value) => Some(*value as f32),
+				OscType::Bool(value) => Some(*value as u8 as f32),
+				_ => None,
+			}).collect();
+
+			let command = match (message.addr.as_str(), args.as_slice()) {
+				("/verlet/gravity", [x, y, ..]) => Command::SetGravity {
+					gravity: vec2(*x, *y),
+				},
+				("/verlet/wind", [x, y, ..]) => Command::SetWind {
+					wind: vec2(*x, *y),
+				},
+				("/verlet/spawn_rate", [rate, ..]) => Command::SetSpawnRate {
+					rate: *rate,
+				},
+				("/verlet/explode", [x, y, strength, ..]) => Command::Explode {
+					position: vec2(*x, *y),
+					strength: *strength,
+				},
+				("/verlet/explode", [x, y]) => Command::Explode {
+					position: vec2(*x, *y),
+					strength: EXPLOSION_STRENGTH,
+				},
+				("/verlet/explode", _) => Command::Explode {
+					position: vec2(WINDOW_WIDTH * 0.5, WINDOW_HEIGHT * 0.5),
+					strength: EXPLOSION_STRENGTH,
+				},
+				_ => return,
+			};
+
+			result.push(command);
+		}
+	}
+}
+
+// `--osc` listens on the default address, `--osc <address>` on a specific one
+pub fn listener_from_args() -> Option<OscListener> {
+	let args: Vec<String> = std::env::args().collect();
+	let index = args.iter().position(|arg| arg == "--osc")?;
+	let address = args.get(index + 1)
+		.filter(|arg| !arg.starts_with("--"))
+		.map(String::as_str)
+		.unwrap_or(DEFAULT_OSC_ADDRESS);
+
+	match OscListener::bind(address) {
+		Ok(listener) => Some(listener),
+		Err(error) => {
+			eprintln!("couldn't listen for osc on {}: {}", address, error);
+			None
+		}
+	}
+}
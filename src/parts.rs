@@ -0,0 +1,220 @@
+use macroquad::prelude::*;
+
+use verlet_integration::color::Color as ObjectColor;
+use verlet_integration::{generate_blob, ChainLink, Master, Palette, VerletObject};
+
+use crate::scene::{object_of_kind, Scene};
+
+pub const BEAM_RADIUS: f32 = 8.0;
+// the pivots and the stops a lever turns between
+pub const PIVOT_RADIUS: f32 = 6.0;
+pub const DOMINO_RADIUS: f32 = 5.0;
+pub const BUTTON_RADIUS: f32 = 14.0;
+// how far below level a flipper rests and how far up it swings from there, in degrees
+pub const FLIPPER_DROOP: f32 = 30.0;
+pub const FLIPPER_SWING: f32 = 55.0;
+pub const BLOB_RADIUS: f32 = 6.0;
+// how much of its squashed area a blob gets back each substep
+pub const BLOB_PRESSURE: f32 = 0.5;
+// welds only link objects this many diameters apart or closer. linking every pair in a
+// long part fights itself and blows up, and the short links hold well enough
+pub const WELD_REACH: f32 = 2.5;
+
+// a few objects and the links between them, built somewhere in particular. links are
+// between indices into objects, the distance is however far apart they start
+#[derive(Clone, Debug, Default)]
+pub struct Part {
+	pub objects: Vec<VerletObject>,
+	pub links: Vec<(usize, usize)>,
+}
+
+impl Part {
+	// a plank balanced on a pinned pivot, linked to it and to itself so it only ever turns
+	// around it. tilt is in radians, clockwise, and a level one tips over whichever way the
+	// solver happens to lean
+	pub fn seesaw(pivot: Vec2, length: f32, tilt: f32) -> Part {
+		Part::lever(pivot, length * 0.5, length * 0.5, tilt, None)
+	}
+
+	// a plank reaching left and right this far from a pinned pivot. it's two rows thick, one
+	// row bends like a rope. limits are how far it turns either way (min and max tilt), and
+	// put a stop under whichever end comes down to hold it there
+	pub fn lever(pivot: Vec2, left: f32, right: f32, tilt: f32, limits: Option<(f32, f32)>) -> Part {
+		let count = (((left + right) / (BEAM_RADIUS * 2.0)).round() as usize).max(3);
+		let spacing = (left + right - BEAM_RADIUS * 2.0) / (count - 1) as f32;
+		let above = PIVOT_RADIUS + BEAM_RADIUS + 1.0;
+		let rotation = Vec2::from_angle(tilt);
+		let mut part = Part::default();
+		for row in 0..2 {
+			for i in 0..count {
+				let along = BEAM_RADIUS - left + i as f32 * spacing;
+				let up = above + row as f32 * BEAM_RADIUS * 2.0;
+				let position = pivot + rotation.rotate(vec2(along, -up));
+				part.objects.push(VerletObject::new(position, BEAM_RADIUS));
+			}
+		}
+		let beam = part.objects.len();
+		part.weld(0..beam, BEAM_RADIUS * 2.0 * WELD_REACH);
+
+		part.objects.push(object_of_kind("pinned", pivot, PIVOT_RADIUS));
+		for i in 0..beam {
+			part.links.push((i, beam));
+		}
+
+		// right where the underside of the plank is when it's turned that far, most of the
+		// way out along the arm that comes down
+		if let Some((min, max)) = limits {
+			let under = BEAM_RADIUS + PIVOT_RADIUS - above;
+			for (angle, along) in [(min, -left), (max, right)] {
+				let position = pivot + Vec2::from_angle(angle).rotate(vec2(along * 0.75, under));
+				part.objects.push(object_of_kind("pinned", position, PIVOT_RADIUS));
+			}
+		}
+		part
+	}
+
+	// a bat reaching length out from a pinned pivot at one end, pointing angle radians
+	// clockwise from straight right. two rows thick like a lever's plank, but either side of
+	// the pivot instead of over it, so it needs a hinge to hold it up. the plank is the first
+	// objects and the pivot comes after them
+	pub fn flipper(pivot: Vec2, length: f32, angle: f32) -> Part {
+		let start = PIVOT_RADIUS + BEAM_RADIUS;
+		let count = (((length - start) / (BEAM_RADIUS * 2.0)).round() as usize).max(2);
+		let spacing = (length - start - BEAM_RADIUS) / (count - 1) as f32;
+		let rotation = Vec2::from_angle(angle);
+		let mut part = Part::default();
+		for side in [-1.0, 1.0] {
+			for i in 0..count {
+				let along = start + i as f32 * spacing;
+				part.objects.push(VerletObject::new(pivot + rotation.rotate(vec2(along, side * BEAM_RADIUS)), BEAM_RADIUS));
+			}
+		}
+		let bat = part.objects.len();
+		part.weld(0..bat, BEAM_RADIUS * 2.0 * WELD_REACH);
+
+		part.objects.push(object_of_kind("pinned", pivot, PIVOT_RADIUS));
+		for i in 0..bat {
+			part.links.push((i, bat));
+		}
+		part
+	}
+
+	// two columns of objects welded into one stiff block, standing on bottom
+	pub fn domino(bottom: Vec2, height: f32) -> Part {
+		let rows = ((height / (DOMINO_RADIUS * 2.0)).round() as usize).max(2);
+		let mut part = Part::default();
+		for row in 0..rows {
+			for column in [-1.0, 1.0] {
+				let position = bottom + vec2(column * DOMINO_RADIUS, -DOMINO_RADIUS * (1.0 + row as f32 * 2.0));
+				part.objects.push(VerletObject::new(position, DOMINO_RADIUS));
+			}
+		}
+		part.weld(0..part.objects.len(), DOMINO_RADIUS * 2.0 * WELD_REACH);
+		part
+	}
+
+	// dominos standing every spacing along the path, starting at its first point
+	pub fn domino_row(path: &[Vec2], height: f32, spacing: f32) -> Part {
+		let mut part = Part::default();
+		for bottom in spaced_along(path, spacing) {
+			part.append(Part::domino(bottom, height));
+		}
+		part
+	}
+
+	// a ring of objects around center, touching the ones either side, for a SoftBody to fill.
+	// the ring is all of it, so its ids are the part's
+	pub fn blob(center: Vec2, radius: f32) -> Part {
+		let count = (std::f32::consts::TAU * radius / (BLOB_RADIUS * 2.0)).round() as usize;
+		let (objects, chain_links) = generate_blob(center, radius, count, BLOB_RADIUS);
+		Part {
+			objects,
+			links: chain_links.iter().map(|chain_link| (chain_link.a, chain_link.b)).collect(),
+		}
+	}
+
+	// a sensor to hang an on line off
+	pub fn button(position: Vec2) -> Part {
+		Part {
+			objects: vec![object_of_kind("sensor", position, BUTTON_RADIUS)],
+			links: vec![],
+		}
+	}
+
+	fn append(&mut self, other: Part) {
+		let offset = self.objects.len();
+		self.objects.extend(other.objects);
+		self.links.extend(other.links.into_iter().map(|(a, b)| (a + offset, b + offset)));
+	}
+
+	// links each of them to the others up to reach away
+	fn weld(&mut self, ids: std::ops::Range<usize>, reach: f32) {
+		for a in ids.clone() {
+			for b in (a + 1)..ids.end {
+				if self.objects[a].position.distance(self.objects[b].position) <= reach {
+					self.links.push((a, b));
+				}
+			}
+		}
+	}
+
+	// the whole part in one color, so it reads as one thing
+	fn painted(&self, color: ObjectColor) -> impl Iterator<Item = VerletObject> + '_ {
+		self.objects.iter().map(move |object| object.clone().with_color(color))
+	}
+
+	fn chain_link(&self, a: usize, b: usize, offset: usize) -> ChainLink {
+		ChainLink {
+			a: a + offset,
+			b: b + offset,
+			target_distance: self.objects[a].position.distance(self.objects[b].position),
+			priority: 0,
+			spring: None,
+			max_strain: None,
+			group: None,
+		}
+	}
+
+	// returns the id of the first object, the rest come right after it
+	pub fn add_to_scene(&self, scene: &mut Scene) -> usize {
+		let first = scene.objects.len();
+		for &(a, b) in self.links.iter() {
+			scene.chain_links.push(self.chain_link(a, b, first));
+		}
+		scene.objects.extend(self.painted(Palette::Random.color()));
+		first
+	}
+
+	// the ids they got, in order
+	pub fn add_to_master(&self, master: &mut Master) -> Vec<usize> {
+		let ids: Vec<usize> = self.painted(master.random_color())
+			.map(|object| master.objects.push(object).index)
+			.collect();
+		for &(a, b) in self.links.iter() {
+			let mut chain_link = self.chain_link(a, b, 0);
+			chain_link.a = ids[a];
+			chain_link.b = ids[b];
+			master.chain_links.push(chain_link);
+		}
+		ids
+	}
+}
+
+// points every spacing along a line through all of the points, the first one included
+pub fn spaced_along(path: &[Vec2], spacing: f32) -> Vec<Vec2> {
+	let mut result: Vec<Vec2> = path.first().copied().into_iter().collect();
+	if spacing <= 0.0 {
+		return result;
+	}
+	// how far along the current piece the next point goes
+	let mut next = spacing;
+	for pair in path.windows(2) {
+		let length = pair[0].distance(pair[1]);
+		while next <= length {
+			result.push(pair[0].lerp(pair[1], next / length));
+			next += spacing;
+		}
+		next -= length;
+	}
+	result
+}
@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+use verlet_integration::Master;
+
+// how much history the plot keeps, in seconds of simulated time
+pub const PLOT_SECONDS: f32 = 5.0;
+pub const PLOT_SIZE: Vec2 = Vec2::new(320.0, 120.0);
+
+const SPEED_COLOR: Color = Color::new(1.0, 0.85, 0.3, 1.0);
+const FORCE_COLOR: Color = Color::new(0.3, 0.85, 1.0, 1.0);
+
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+	time: f32,
+	// world units per frame
+	speed: f32,
+	// the change in velocity per frame times its mass, which is the net force on it
+	force: f32,
+}
+
+// a little oscilloscope of one object's speed, and the net force on it if asked for, over the
+// last PLOT_SECONDS of simulated time. it only moves on while the simulation does
+#[derive(Clone, Debug, Default)]
+pub struct Plot {
+	pub id: Option<usize>,
+	pub show_force: bool,
+	samples: VecDeque<Sample>,
+	// where the object was and how fast it was going at the last sample
+	last: Option<(f32, Vec2, Vec2)>,
+}
+
+impl Plot {
+	pub fn select(&mut self, id: Option<usize>) {
+		*self = Plot {
+			id,
+			show_force: self.show_force,
+			..Default::default()
+		};
+	}
+
+	// after the master's updated for the frame
+	pub fn update(&mut self, master: &Master) {
+		let Some(object) = self.id.and_then(|id| master.objects.get(id)) else {
+			self.id = None;
+			return;
+		};
+		let time = master.stats.time;
+
+		match self.last {
+			Some((last_time, last_position, last_velocity)) if time > last_time => {
+				let frames = time - last_time;
+				let velocity = (object.position - last_position) / frames;
+				self.samples.push_back(Sample {
+					time,
+					speed: velocity.length(),
+					force: ((velocity - last_velocity) / frames).length() * object.mass,
+				});
+				self.last = Some((time, object.position, velocity));
+			}
+			// asleep or paused, nothing's moved on
+			Some((last_time, ..)) if time == last_time => {}
+			// the first sample, or time went backwards (the time reversal experiment)
+			_ => {
+				self.samples.clear();
+				self.last = Some((time, object.position, object.position - object.last_position));
+			}
+		}
+
+		while self.samples.front().is_some_and(|sample| time - sample.time > PLOT_SECONDS * 60.0) {
+			self.samples.pop_front();
+		}
+	}
+
+	// a ring around the object being plotted, in world space
+	pub fn draw_selection(&self, master: &Master, zoom: f32) {
+		if let Some(object) = self.id.and_then(|id| master.objects.get(id)) {
+			draw_circle_lines(object.position.x, object.position.y, object.radius + 4.0 / zoom, 2.0 / zoom, SPEED_COLOR);
+		}
+	}
+
+	// the plot itself, in the bottom right corner of the screen
+	pub fn draw(&self) {
+		let Some(id) = self.id else {
+			return;
+		};
+		let corner = vec2(screen_width(), screen_height()) - PLOT_SIZE - 20.0;
+		draw_rectangle(corner.x, corner.y, PLOT_SIZE.x, PLOT_SIZE.y, Color::new(0.0, 0.0, 0.0, 0.6));
+		draw_rectangle_lines(corner.x, corner.y, PLOT_SIZE.x, PLOT_SIZE.y, 1.0, GRAY);
+
+		let Some(newest) = self.samples.back() else {
+			draw_text(&format!("OBJECT {}", id), corner.x + 8.0, corner.y + 20.0, 20.0, WHITE);
+			return;
+		};
+
+		// each line is scaled to its own biggest value so they both fill the height
+		let trace = |value: fn(&Sample) -> f32, color: Color| {
+			let most = self.samples.iter().map(value).fold(f32::EPSILON, f32::max);
+			let point = |sample: &Sample| corner + vec2(
+				(1.0 - (newest.time - sample.time) / (PLOT_SECONDS * 60.0)) * PLOT_SIZE.x,
+				(1.0 - value(sample) / most) * PLOT_SIZE.y,
+			);
+			for (a, b) in self.samples.iter().zip(self.samples.iter().skip(1)) {
+				let (a, b) = (point(a), point(b));
+				draw_line(a.x, a.y, b.x, b.y, 1.5, color);
+			}
+			most
+		};
+
+		let most_speed = trace(|sample| sample.speed, SPEED_COLOR);
+		draw_text(
+			&format!("OBJECT {}  SPEED {:.2} (MAX {:.2})", id, newest.speed, most_speed),
+			corner.x + 8.0,
+			corner.y + 20.0,
+			20.0,
+			SPEED_COLOR,
+		);
+
+		if self.show_force {
+			let most_force = trace(|sample| sample.force, FORCE_COLOR);
+			draw_text(
+				&format!("FORCE {:.2} (MAX {:.2})", newest.force, most_force),
+				corner.x + 8.0,
+				corner.y + 40.0,
+				20.0,
+				FORCE_COLOR,
+			);
+		}
+	}
+}
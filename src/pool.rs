@@ -0,0 +1,319 @@
+use alloc::vec::Vec;
+use core::ops::{Index, IndexMut};
+
+// points at one slot in a pool. the generation goes up every time the slot is emptied,
+// so a handle kept around after its object was removed can't reach whatever moved in next
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+	pub index: usize,
+	pub generation: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+	generation: u32,
+	value: Option<T>,
+}
+
+// a vec that leaves a hole when something is removed instead of shifting everything after
+// it down, then fills the holes back in first. indices stay put for as long as the thing
+// in them is alive, so anything holding onto one (like a link) doesn't need fixing up
+#[derive(Clone, Debug)]
+pub struct Pool<T> {
+	slots: Vec<Slot<T>>,
+	free: Vec<usize>,
+	len: usize,
+}
+
+impl<T> Pool<T> {
+	pub const fn new() -> Self {
+		Self {
+			slots: Vec::new(),
+			free: Vec::new(),
+			len: 0,
+		}
+	}
+
+	// how many are alive
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	// one past the highest index that's ever been used, holes included
+	pub fn slot_count(&self) -> usize {
+		self.slots.len()
+	}
+
+	// how many can be alive at once before pushing allocates
+	pub fn capacity(&self) -> usize {
+		self.slots.capacity() - self.slots.len() + self.free.len() + self.len
+	}
+
+	pub fn reserve(&mut self, additional: usize) {
+		let needed = additional.saturating_sub(self.free.len());
+		self.slots.reserve(needed);
+		// so removing never has to grow the free list either
+		self.free.reserve(self.slots.capacity().saturating_sub(self.free.len()));
+	}
+
+	pub fn push(&mut self, value: T) -> Handle {
+		self.len += 1;
+
+		if let Some(index) = self.free.pop() {
+			let slot = &mut self.slots[index];
+			slot.value = Some(value);
+			return Handle {
+				index,
+				generation: slot.generation,
+			};
+		}
+
+		self.slots.push(Slot {
+			generation: 0,
+			value: Some(value),
+		});
+		Handle {
+			index: self.slots.len() - 1,
+			generation: 0,
+		}
+	}
+
+	pub fn remove(&mut self, handle: Handle) -> Option<T> {
+		if !self.contains(handle) {
+			return None;
+		}
+		self.remove_at(handle.index)
+	}
+
+	pub fn remove_at(&mut self, index: usize) -> Option<T> {
+		let slot = self.slots.get_mut(index)?;
+		let value = slot.value.take()?;
+		slot.generation = slot.generation.wrapping_add(1);
+		self.free.push(index);
+		self.len -= 1;
+		Some(value)
+	}
+
+	pub fn clear(&mut self) {
+		for index in 0..self.slots.len() {
+			self.remove_at(index);
+		}
+	}
+
+	pub fn retain(&mut self, mut keep: impl FnMut(&T) -> bool) {
+		for index in 0..self.slots.len() {
+			if self.get(index).is_some_and(|value| !keep(value)) {
+				self.remove_at(index);
+			}
+		}
+	}
+
+	pub fn contains(&self, handle: Handle) -> bool {
+		self.handle(handle.index) == Some(handle)
+	}
+
+	pub fn contains_index(&self, index: usize) -> bool {
+		self.get(index).is_some()
+	}
+
+	// a handle for whatever is alive at this index right now
+	pub fn handle(&self, index: usize) -> Option<Handle> {
+		let slot = self.slots.get(index)?;
+		slot.value.as_ref().map(|_| Handle {
+			index,
+			generation: slot.generation,
+		})
+	}
+
+	pub fn get(&self, index: usize) -> Option<&T> {
+		self.slots.get(index)?.value.as_ref()
+	}
+
+	pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+		self.slots.get_mut(index)?.value.as_mut()
+	}
+
+	// two different slots at once, None unless both are alive
+	pub fn pair_mut(&mut self, a: usize, b: usize) -> Option<(&mut T, &mut T)> {
+		pair_mut(&mut self.slots, a, b).and_then(|(a, b)| Some((a.value.as_mut()?, b.value.as_mut()?)))
+	}
+
+	pub fn lookup(&self, handle: Handle) -> Option<&T> {
+		let slot = self.slots.get(handle.index)?;
+		if slot.generation != handle.generation {
+			return None;
+		}
+		slot.value.as_ref()
+	}
+
+	pub fn lookup_mut(&mut self, handle: Handle) -> Option<&mut T> {
+		let slot = self.slots.get_mut(handle.index)?;
+		if slot.generation != handle.generation {
+			return None;
+		}
+		slot.value.as_mut()
+	}
+
+	// the indices that are alive, in order
+	pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+		self.indexed().map(|(index, _)| index)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &T> {
+		self.slots.iter().filter_map(|slot| slot.value.as_ref())
+	}
+
+	pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+		self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+	}
+
+	// use these over iter().enumerate(), which counts past the holes and gets the indices wrong
+	pub fn indexed(&self) -> impl Iterator<Item = (usize, &T)> {
+		self.slots.iter().enumerate().filter_map(|(index, slot)| slot.value.as_ref().map(|value| (index, value)))
+	}
+
+	pub fn indexed_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+		self.slots.iter_mut().enumerate().filter_map(|(index, slot)| slot.value.as_mut().map(|value| (index, value)))
+	}
+}
+
+// two different elements of a slice at once, None if they're the same one or either is out
+// of range
+pub fn pair_mut<T>(items: &mut [T], a: usize, b: usize) -> Option<(&mut T, &mut T)> {
+	if a == b || a.max(b) >= items.len() {
+		return None;
+	}
+	let (low, high) = items.split_at_mut(a.max(b));
+	match a < b {
+		true => Some((&mut low[a], &mut high[0])),
+		false => Some((&mut high[0], &mut low[b])),
+	}
+}
+
+impl<T> Default for Pool<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> From<Vec<T>> for Pool<T> {
+	fn from(values: Vec<T>) -> Self {
+		values.into_iter().collect()
+	}
+}
+
+impl<T> FromIterator<T> for Pool<T> {
+	fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+		let mut pool = Pool::new();
+		for value in values {
+			pool.push(value);
+		}
+		pool
+	}
+}
+
+impl<T> Extend<T> for Pool<T> {
+	fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+		for value in values {
+			self.push(value);
+		}
+	}
+}
+
+// saved slot by slot, holes as nulls, so every index means the same thing once it's loaded
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Pool<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.slots.iter().map(|slot| slot.value.as_ref()))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Pool<T> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let mut pool = Pool::new();
+		for (index, value) in Vec::<Option<T>>::deserialize(deserializer)?.into_iter().enumerate() {
+			match value {
+				Some(_) => pool.len += 1,
+				None => pool.free.push(index),
+			}
+			pool.slots.push(Slot { generation: 0, value });
+		}
+		// the lowest hole gets filled first, same as if they'd been removed in order
+		pool.free.reverse();
+		Ok(pool)
+	}
+}
+
+// panics on a hole, same as indexing a vec out of bounds
+impl<T> Index<usize> for Pool<T> {
+	type Output = T;
+
+	fn index(&self, index: usize) -> &T {
+		self.get(index).expect("nothing alive at this index")
+	}
+}
+
+impl<T> IndexMut<usize> for Pool<T> {
+	fn index_mut(&mut self, index: usize) -> &mut T {
+		self.get_mut(index).expect("nothing alive at this index")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloc::vec;
+
+	#[test]
+	fn a_handle_goes_stale_once_its_slot_is_reused() {
+		let mut pool: Pool<&str> = vec!["a", "b", "c"].into();
+		let b = pool.handle(1).unwrap();
+		assert_eq!(pool.remove(b), Some("b"));
+		assert_eq!(pool.lookup(b), None);
+
+		let d = pool.push("d");
+		assert_eq!(d.index, 1);
+		assert_eq!(d.generation, b.generation + 1);
+		assert_eq!(pool.lookup(b), None);
+		assert_eq!(pool.remove(b), None);
+		assert_eq!(pool.lookup(d), Some(&"d"));
+		assert!(pool.contains(d) && !pool.contains(b));
+	}
+
+	#[test]
+	fn the_generation_goes_up_every_time_a_slot_is_emptied() {
+		let mut pool = Pool::new();
+		let mut handle = pool.push(0);
+		for i in 1..4 {
+			pool.remove_at(handle.index);
+			// emptying a hole again doesn't count
+			assert_eq!(pool.remove_at(handle.index), None);
+			let next = pool.push(i);
+			assert_eq!((next.index, next.generation), (handle.index, handle.generation + 1));
+			handle = next;
+		}
+	}
+
+	#[test]
+	fn retain_leaves_holes_and_keeps_the_rest_where_they_were() {
+		let mut pool: Pool<i32> = (0..6).collect();
+		let five = pool.handle(5).unwrap();
+		pool.retain(|&value| value % 2 == 1);
+		assert_eq!(pool.len(), 3);
+		assert_eq!(pool.slot_count(), 6);
+		assert_eq!(pool.ids().collect::<Vec<_>>(), vec![1, 3, 5]);
+		assert_eq!(pool.lookup(five), Some(&5));
+		assert!(pool.handle(0).is_none());
+
+		// the last hole made is the first filled
+		assert_eq!(pool.push(10).index, 4);
+		assert_eq!(pool.push(11).index, 2);
+		assert_eq!(pool.push(12).index, 0);
+		assert_eq!(pool.push(13).index, 6);
+	}
+}
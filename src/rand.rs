@@ -0,0 +1,87 @@
+// with std this is just macroquad's generator, so srand() in the app seeds the solver too.
+// quad-rand needs 64 bit atomics, which most microcontrollers don't have, so without std
+// there's a small xorshift instead
+#[cfg(feature = "std")]
+pub use quad_rand::{gen_range, srand};
+
+#[cfg(not(feature = "std"))]
+mod xorshift {
+	use core::sync::atomic::{AtomicU32, Ordering};
+
+	static STATE: AtomicU32 = AtomicU32::new(0x9e3779b9);
+
+	pub fn srand(seed: u64) {
+		// zero would get stuck at zero forever
+		STATE.store((seed as u32 ^ (seed >> 32) as u32).max(1), Ordering::Relaxed);
+	}
+
+	// plain loads and stores instead of fetch_update, so it works on targets without
+	// compare-and-swap. the solver only runs on one core anyway
+	fn next() -> u32 {
+		let mut x = STATE.load(Ordering::Relaxed);
+		x ^= x << 13;
+		x ^= x >> 17;
+		x ^= x << 5;
+		STATE.store(x, Ordering::Relaxed);
+		x
+	}
+
+	pub trait RandomRange {
+		fn gen_range(low: Self, high: Self) -> Self;
+	}
+
+	impl RandomRange for f32 {
+		fn gen_range(low: f32, high: f32) -> f32 {
+			low + (next() >> 8) as f32 / (1 << 24) as f32 * (high - low)
+		}
+	}
+
+	impl RandomRange for usize {
+		fn gen_range(low: usize, high: usize) -> usize {
+			low + next() as usize % (high - low).max(1)
+		}
+	}
+
+	pub fn gen_range<T: RandomRange>(low: T, high: T) -> T {
+		T::gen_range(low, high)
+	}
+}
+
+#[cfg(not(feature = "std"))]
+pub use xorshift::{gen_range, srand, RandomRange};
+
+// a generator of its own, for when the same seed has to give the same numbers however much
+// else pulls from the global one (see Master::rng). xorshift gets stuck on zero, so a zero
+// seed starts from one instead
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rng {
+	state: u64,
+}
+
+impl Rng {
+	pub fn new(seed: u64) -> Self {
+		Self { state: seed.max(1) }
+	}
+
+	// what to seed a new one with for it to carry on from exactly here
+	pub fn seed(&self) -> u64 {
+		self.state
+	}
+
+	pub fn next_u64(&mut self) -> u64 {
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 7;
+		self.state ^= self.state << 17;
+		self.state
+	}
+
+	pub fn range(&mut self, low: f32, high: f32) -> f32 {
+		low + (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32 * (high - low)
+	}
+
+	// from 0 up to but not including count, 0 when it's 0
+	pub fn index(&mut self, count: usize) -> usize {
+		(self.next_u64() % count.max(1) as u64) as usize
+	}
+}
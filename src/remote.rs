@@ -0,0 +1,117 @@
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use tungstenite::Message;
+
+use crate::{Command, StatsReport};
+
+pub const DEFAULT_REMOTE_ADDRESS: &str = "127.0.0.1:9001";
+
+// a command from one of the clients, waiting for the main loop to run it
+pub struct RemoteRequest {
+	pub command: Command,
+	reply: Sender<String>,
+}
+
+// a websocket server taking commands as json text messages, one reply per message:
+//   {"command": "spawn", "position": [480, 300], "radius": 20, "velocity": [3, -2]}   (radius, tag and velocity can be left off)
+//   {"command": "explode", "position": [480, 300], "strength": 20}
+//   {"command": "impulse", "id": 3, "impulse": [0, -10]}
+//   {"command": "repair_link", "index": 0}
+//   {"command": "despawn", "id": 3}
+//   {"command": "fire", "position": [200, 500], "velocity": [4, -3], "radius": 10}   (spin can be left off)
+//   {"command": "set_group", "group": 0, "enabled": false}
+//   {"command": "set_gravity", "gravity": [0, 1]}
+//   {"command": "set_wind", "wind": [0.5, 0]}
+//   {"command": "set_spawn_rate", "rate": 5}
+//   {"command": "grab", "id": 3, "target": [480, 200], "hops": 2}
+//   {"command": "move_grab", "target": [500, 200]}
+//   {"command": "release"}
+//   {"command": "query_stats"}
+// the sockets live on their own threads, the simulation only sees commands through poll()
+pub struct RemoteServer {
+	pub requests: Receiver<RemoteRequest>,
+}
+
+impl RemoteServer {
+	pub fn start(address: impl ToSocketAddrs) -> std::io::Result<Self> {
+		let listener = TcpListener::bind(address)?;
+		let (sender, requests) = mpsc::channel();
+
+		thread::spawn(move || {
+			for stream in listener.incoming().flatten() {
+				let sender = sender.clone();
+				thread::spawn(move || handle_client(stream, sender));
+			}
+		});
+
+		Ok(Self {
+			requests,
+		})
+	}
+
+	// runs every command that came in since the last poll, never blocks
+	pub fn poll(&self, mut run: impl FnMut(Command) -> Option<StatsReport>) {
+		while let Ok(request) = self.requests.try_recv() {
+			let reply = match run(request.command) {
+				Some(report) => serde_json::to_string(&report).unwrap_or_default(),
+				None => r#"{"ok":true}"#.to_string(),
+			};
+
+			// the client might have hung up in the meantime, that's fine
+			let _ = request.reply.send(reply);
+		}
+	}
+}
+
+fn handle_client(stream: TcpStream, requests: Sender<RemoteRequest>) {
+	let Ok(mut socket) = tungstenite::accept(stream) else {
+		return;
+	};
+
+	while let Ok(message) = socket.read() {
+		let text = match &message {
+			Message::Text(text) => text.as_str(),
+			Message::Close(_) => break,
+			_ => continue,
+		};
+
+		let reply = match serde_json::from_str::<Command>(text) {
+			Ok(command) => {
+				let (reply, response) = mpsc::channel();
+				if requests.send(RemoteRequest { command, reply }).is_err() {
+					break;
+				}
+
+				match response.recv() {
+					Ok(reply) => reply,
+					Err(_) => break,
+				}
+			}
+			Err(error) => serde_json::json!({ "error": error.to_string() }).to_string(),
+		};
+
+		if socket.send(Message::text(reply)).is_err() {
+			break;
+		}
+	}
+}
+
+// `--remote` listens on the default address, `--remote <address>` on a specific one
+pub fn server_from_args() -> Option<RemoteServer> {
+	let args: Vec<String> = std::env::args().collect();
+	let index = args.iter().position(|arg| arg == "--remote")?;
+	let address = args.get(index + 1)
+		.filter(|arg| !arg.starts_with("--"))
+		.map(String::as_str)
+		.unwrap_or(DEFAULT_REMOTE_ADDRESS);
+
+	match RemoteServer::start(address) {
+		Ok(server) => Some(server),
+		Err(error) => {
+			eprintln!("couldn't start the remote server on {}: {}", address, error);
+			None
+		}
+	}
+}
@@ -0,0 +1,79 @@
+use std::io;
+use std::path::Path;
+
+use verlet_integration::rand::Rng;
+use verlet_integration::Master;
+
+use crate::editor;
+use crate::scene::{packed_ids, Timeline};
+use crate::Command;
+
+// everything done to the simulation from outside while it's recording (spawns, drags, chaos,
+// the spawner, osc and the remote api), on top of the master and timeline it started from.
+// each command goes into a copy of the timeline as an at line for the moment it came in, so
+// playing it back is only starting over from there and letting the timeline run them
+pub struct Recording {
+	pub start: Master,
+	pub timeline: Timeline,
+	pub commands: usize,
+}
+
+impl Recording {
+	// a master without a generator of its own gets one seeded with seed, so whatever's picked
+	// at random comes out the same again, and so it steps exactly a frame at a time
+	pub fn start(master: &mut Master, timeline: &Timeline, seed: u64) -> Self {
+		master.rng.get_or_insert_with(|| Rng::new(seed));
+		Self {
+			start: master.clone(),
+			timeline: timeline.clone(),
+			commands: 0,
+		}
+	}
+
+	// the spawn rate and stats don't do anything to the simulation themselves
+	pub fn record(&mut self, master: &Master, command: Command) {
+		if matches!(command, Command::SetSpawnRate { .. } | Command::QueryStats) {
+			return;
+		}
+		// after whatever the timeline already had then, that ran first
+		let time = master.stats.time / 60.0;
+		let index = self.timeline.events.partition_point(|&(event_time, _)| event_time <= time);
+		self.timeline.events.insert(index, (time, command));
+		self.commands += 1;
+	}
+
+	pub fn replay(&self) -> (Master, Timeline) {
+		(self.start.clone(), self.timeline.clone())
+	}
+
+	// as a scene that plays it back when it's opened, starting at the same time with the same
+	// seed. only what a scene file keeps comes along (see editor::save()), and objects and
+	// links get their ids packed down, so anything spawned after the start of one that had
+	// holes in it can end up with a different id than the commands say
+	pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		let mut scene = editor::scene(&self.start, &self.timeline);
+		// anything spawned after the start past the end of the pool comes after the packed ones
+		let objects = packed_ids(&self.start);
+		let holes = objects.len() - self.start.objects.len();
+		let object = |id: usize| objects.get(id).map_or(Some(id - holes), |&id| id);
+		let links: Vec<usize> = self.start.chain_links.ids().collect();
+		let link = |id: usize| links.iter().position(|&link| link == id);
+
+		scene.elapsed = Some(self.start.stats.time);
+		scene.timeline.events = self.timeline.events[self.timeline.next..].iter()
+			.filter_map(|&(time, command)| {
+				let command = match command {
+					Command::Grab { id, target, hops } => Command::Grab { id: object(id)?, target, hops },
+					Command::Impulse { id: Some(id), impulse } => Command::Impulse { id: Some(object(id)?), impulse },
+					Command::Despawn { id } => Command::Despawn { id: object(id)? },
+					Command::BreakLink { id } => Command::BreakLink { id: link(id)? },
+					// the ones already broken at the start don't make it into the file
+					Command::RepairLink { index } => Command::RepairLink { index: index.checked_sub(self.start.broken_links.len())? },
+					command => command,
+				};
+				Some((time, command))
+			})
+			.collect();
+		scene.save(path)
+	}
+}
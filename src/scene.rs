@@ -0,0 +1,1681 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use macroquad::prelude::*;
+use verlet_integration::{generate_cloth, Bomb, ChainLink, Constraint, DampingCurve, Flock, Hierarchy, Hinge, KillZone, LinkGroup, Magnet, Master, Motor, NBody, ObjectId, Palette, Pole, SensorEvent, SoftBody, Spring, StaticSegment, VectorField, VerletObject, HIERARCHY_ITERATIONS, HIERARCHY_STRIDE};
+
+use verlet_integration::rand::Rng;
+
+use crate::animation::{Animation, Curve, Easing, Repeat};
+use crate::automation::{Automation, Parameter, Shape};
+use crate::cannon::{Cannon, CANNON_MAX_POWER};
+use crate::game::{Flipper, Game, Goal, Player, Side, FLIPPER_SPEED, FLIPPER_TORQUE};
+use crate::parts::{Part, BLOB_PRESSURE, FLIPPER_DROOP, FLIPPER_SWING};
+use crate::trails::Trail;
+use crate::{run_command, Command, Spawner};
+
+// walls are all drawn the same, so they read as scenery
+pub const WALL_COLOR: verlet_integration::color::Color = verlet_integration::color::Color::new(0.45, 0.45, 0.52, 1.0);
+
+// how hard a bumper kicks when its line doesn't say
+pub const BUMPER_KICK: f32 = 6.0;
+
+// the lines that make an object
+pub const OBJECT_KINDS: [&str; 5] = ["object", "pinned", "wall", "sensor", "bumper"];
+
+// which of those an object would be written out as
+pub fn object_kind(object: &VerletObject) -> &'static str {
+	match (object.kinematic, object.sensor) {
+		(_, true) => "sensor",
+		(true, false) if object.kick > 0.0 => "bumper",
+		(true, false) if object.color == WALL_COLOR => "wall",
+		(true, false) => "pinned",
+		(false, false) => "object",
+	}
+}
+
+// and back again, anything that isn't pinned, a wall or a sensor is a plain object
+pub fn object_of_kind(kind: &str, position: Vec2, radius: f32) -> VerletObject {
+	let object = VerletObject::new(position, radius);
+	match kind {
+		"pinned" => object.kinematic(),
+		"wall" => object.with_color(WALL_COLOR).kinematic(),
+		"sensor" => object.kinematic().sensor(),
+		"bumper" => object.bumper(BUMPER_KICK),
+		_ => object,
+	}
+}
+
+// a scene file is one thing per line, blank lines and anything after a # are skipped:
+//
+//   seed 7                     picks everything at random off this seed and steps exactly one
+//                              frame at a time, like --deterministic
+//   elapsed 750                how many frames (60ths of a second) into the simulation it
+//                              starts at, so at lines before then have already gone by
+//   gravity 0 1
+//   wind 0.2 0
+//   n_body 0.2 softening 10 theta 0.7    everything pulls on everything else this hard, see
+//                              NBody (the last two get the defaults when they're left off)
+//   vector_field 0 0 40 24 15  a grid this many cells across and down from x y, each this big,
+//   vector 3 4 0.5 -0.2        and what the one 3 across and 4 down (counting from 0) pushes
+//                              whatever's in it with, like gravity. the rest push nothing
+//   friction 0.4               how much everything slows sliding along whatever it touches and
+//   damping 0.002              how much of its speed it loses to the air every substep, 0 to 1
+//   container box 180 0 780 600   or circle x y radius, polygon x y x y x y... or none,
+//                                 the default is the demo's circle
+//   object 480 300 10          x y radius
+//   object 480 300 10 2 -1     and how far it moves per frame, when it starts out moving
+//   pinned 270 400 10          an object held where it starts, like the ends of the bridge
+//   wall 300 450 6             a pinned object drawn as scenery, for building static geometry
+//   sensor 480 200 40          a pinned zone that notices what goes in and out but pushes nothing
+//   bumper 480 200 20 8        a pinned object that knocks whatever runs into it away at this
+//                              many pixels a frame (6 when it's left off), on top of bouncing
+//   pinned 480 200 20 bounce 0.9   any of those with bounce on the end bounces things back off
+//                              it with that much of the speed they hit it with (0 to 1), and
+//                              friction and damping there are its own instead of the scene's
+//   object 480 100 6 damping_curve 0 0 4 0.02 12 0.1
+//                              losing more of its speed the faster it goes instead, these
+//                              dampings at these speeds (up to 4 of them) with straight lines
+//                              between, see DampingCurve
+//   object 300 200 8 magnet north 1000 200   a magnet this strong, pulling the other pole in
+//                              and pushing its own away from this far off
+//   object 300 200 5 tag 1     for telling objects apart, a flock goes by it
+//   object 300 200 5 lifetime 4   gone again this many seconds after it starts
+//   object 480 200 12 bomb 3 20 150 debris 8 breaks_links
+//                              goes off 3 seconds in (or with contact instead, the first
+//                              time it hits anything, and with both whichever comes first),
+//                              knocking everything within 150 away this hard like explode,
+//                              flying apart into this many bits and snapping every link there
+//   link 0 1                   ids count up from 0 in the order the objects and walls come in,
+//   link 1 2 40                and the distance defaults to how far apart they start
+//   spring 1 2 0.1 0.05 40     a link that stretches and bounces, this stiff and this damped
+//                              (both 0 to 1), then the distance the same way
+//   link 1 2 40 break 0.2      either of those with break on the end comes apart once it's
+//                              stretched or squashed by more than that much of its length
+//                              (0.2 is a fifth), and a click where it was puts it back
+//   group scaffolding          links that switch off and on together (set_group 0 false), counted
+//   group cables off           up from 0 like hinges. off on the end starts it switched off
+//   link 1 2 40 group 0        a link or spring in one, with group on the end after break
+//   segment 300 500 660 450 8  a line from one end to the other that never moves, this thick
+//   kill_zone 0 600 960 700    a box from one corner to the other that despawns anything not
+//   kill_zone below 650        pinned once it's in, or everything further down than y
+//   hinge 20 21 0-19 limits -30 30 motor 90 5000
+//                              bodies 21 and 0 to 19 turning around object 20, each linked to
+//                              it already (world instead of the first body holds the second one
+//                              to nothing). rest 10 is the angle it rests at (how it starts out
+//                              when it's left off), limits how far it turns either way from
+//                              there and motor how many degrees a second it turns at and how
+//                              hard it can push to get there
+//   seesaw 480 400 160 -20     a plank this long balanced on a pinned pivot at x y, tilted
+//                              this many degrees clockwise (0 when it's left off)
+//   lever 480 400 40 120 0 -25 25   reaching this far left and right of the pivot, and
+//                              turning only between these tilts (it leaves out the stops when
+//                              they're left off)
+//   domino 600 500 70          a stiff block this tall standing at x y
+//   flipper 380 520 80 left    a bat this long on a hinge at x y, reaching right from a left
+//                              one and left from a right one, that swings up while that
+//                              side's flipper key is held
+//   domino_row 70 40 300 500 600 500 700 450
+//                              dominos this tall and this far apart, standing along a line
+//                              through the points after
+//   blob 480 200 50            a ring of small objects this big around x y, filled with air so
+//                              it squashes and springs back out
+//   player 200 400 40          a blob the move keys roll along and jump with
+//   soft_body 0-25 pressure 0.5 area 7800
+//                              the same around objects already linked into a ring, in order,
+//                              keeping this area (how much it has to start with when it's
+//                              left off) and getting this much of it back each substep
+//   flock 1 vision 60 separation 0.6 alignment 0.1 cohesion 0.01 speed 4
+//                              everything tagged 1 flies together like birds, see Flock (any
+//                              that are left off get the defaults)
+//   cloth 300 100 20 12 16     a sheet this many objects across and down, this far apart,
+//                              hanging from its corner at x y with the top row pinned
+//                              (all of these are objects and links like any others, and
+//                              take up as many ids as they have objects)
+//   cloth 300 100 40 30 8 hierarchy 4   with a hierarchy line for all of it after
+//   hierarchy 0-1199 stride 4 iterations 2
+//                              solves those objects coarsely first, about every 4 links, so a
+//                              big cloth or a long chain doesn't stretch. see Hierarchy (both
+//                              get the defaults when they're left off)
+//   at 2 spawn_burst 480 200 12 8
+//   at 5 break_link 7          links count up from 0 the same way
+//   at 8 set_gravity 0 -1
+//   at 9 spawn 480 200 tag 1   and a radius picked at random without one
+//   at 9 spawn 480 200 12 velocity 3 -2   already moving this far a frame
+//   at 10 grab 3 480 200 2     pulling object 3 towards x y like the mouse, fading out over this
+//   at 11 move_grab 500 200    many links from it, until it's moved somewhere else
+//   at 12 release              or let go
+//   at 13 despawn 3            takes object 3 out, along with its links
+//   at 14 fire 200 500 4 -3 10 360   a shot like a cannon's out of x y, moving this far a
+//                              frame, this big and spinning this many degrees a second
+//                              (not at all when it's left off)
+//   path 3 bezier 4 ease_in_out ping_pong 300 300 350 200 450 200 500 300
+//   emitter 200 350 16 3 2 0 count 20 mass 2000
+//                              drops an object this big at x y every 3 seconds, moving this
+//                              far a frame when it comes out, until there have been this many
+//                              (forever without a count). the mass defaults to the radius
+//                              squared, and anything an object line takes after that goes too
+//   cannon 200 560 -30 5 radius 8 spin 90
+//                              something to fire shots out of with the aim keys, pointing
+//                              this many degrees clockwise from right (so up is -90) at this
+//                              many pixels a frame, then the shot's radius and spin like fire
+//                              (10 and none when they're left off)
+//   trail 2 40                 everything tagged 2 leaves a streak behind it through where
+//                              it's been over the last 40 frames
+//   on 4 spawn 200 100 12      runs the command whenever anything goes into sensor 4,
+//   once 4 spawn 200 100 12    or only the first time
+//   goal 4 10                  a sensor that scores 10 for everything that goes into it,
+//   goal 5 0 lose              or ends the game
+//   flip 3 left                the same for a hinge that's already there, with a motor
+//   steer 0                    and for a soft body, counting up from 0 like hinges
+//   launch 0                   pulled back and let go with the mouse to fling it, instead of grabbed
+//   win 50                     the score that wins
+//   time_limit 30              seconds, running out loses when there's a score to reach and
+//                              wins when there isn't
+//
+// every "at <seconds>" line runs a command (see Command) that far into the simulation,
+// with the same fields in the same order as the remote api's json.
+//
+// a path line makes the object kinematic and moves it along the points: the object id,
+// waypoints or bezier, seconds from end to end, the easing (linear, ease_in, ease_out or
+// ease_in_out), what to do at the end (once, loop or ping_pong) and then the points
+#[derive(Clone, Debug, Default)]
+pub struct Scene {
+	pub objects: Vec<VerletObject>,
+	pub chain_links: Vec<ChainLink>,
+	pub groups: Vec<LinkGroup>,
+	pub segments: Vec<StaticSegment>,
+	pub kill_zones: Vec<KillZone>,
+	pub hinges: Vec<Hinge>,
+	pub soft_bodies: Vec<SoftBody>,
+	// with nothing worked out yet, into_master() builds them once the links are in
+	pub hierarchies: Vec<Hierarchy>,
+	pub flocks: Vec<Flock>,
+	pub gravity: Option<Vec2>,
+	pub wind: Option<Vec2>,
+	pub n_body: Option<NBody>,
+	pub vector_field: Option<VectorField>,
+	pub friction: Option<f32>,
+	pub damping: Option<f32>,
+	pub container: Option<Constraint>,
+	pub seed: Option<u64>,
+	// in frames like stats.time, since it has to come back exactly for the at lines to land
+	// on the same frames as before
+	pub elapsed: Option<f32>,
+	pub timeline: Timeline,
+}
+
+// commands to run at set times, in order, objects moving along paths, world settings going up
+// and down, commands to run when something goes into a sensor and the rules, when the scene
+// is a game
+#[derive(Clone, Debug, Default)]
+pub struct Timeline {
+	pub events: Vec<(f32, Command)>,
+	// how many have run so far
+	pub next: usize,
+	pub animations: Vec<Animation>,
+	pub automations: Vec<Automation>,
+	// what an automation's got the time scale at, for the window to run that much faster or
+	// slower. None when nothing's on it
+	pub time_scale: Option<f32>,
+	pub triggers: Vec<Trigger>,
+	pub emitters: Vec<Emitter>,
+	pub cannons: Vec<Cannon>,
+	pub trails: Vec<Trail>,
+	pub game: Option<Game>,
+	// sensor events stay up until the next update, and that doesn't come while the scene
+	// sleeps, so they're only looked at once per simulated moment
+	sensor_time: Option<f32>,
+}
+
+// a command hung off a sensor, like a button
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trigger {
+	pub sensor: usize,
+	pub command: Command,
+	pub once: bool,
+	pub fired: bool,
+}
+
+// copies of an object dropped in every so often where it starts, already moving the way it
+// starts out moving, like traffic onto a bridge. only the color changes from one to the next
+#[derive(Clone, Debug)]
+pub struct Emitter {
+	pub object: VerletObject,
+	// seconds between each one
+	pub interval: f32,
+	// how many before it stops, None keeps going
+	pub count: Option<usize>,
+	pub emitted: usize,
+	// when the next one comes, in seconds, None for straight away
+	pub next: Option<f32>,
+}
+
+impl Emitter {
+	pub fn is_done(&self) -> bool {
+		self.count.is_some_and(|count| self.emitted >= count)
+	}
+
+	pub fn update(&mut self, master: &mut Master, seconds: f32) {
+		while !self.is_done() && self.next.is_none_or(|next| next <= seconds) {
+			let color = master.random_color();
+			master.objects.push(self.object.clone().with_color(color));
+			self.emitted += 1;
+			self.next = Some(self.next.unwrap_or(seconds) + self.interval);
+		}
+	}
+}
+
+impl Timeline {
+	pub fn update(&mut self, master: &mut Master, spawner: &mut Spawner) {
+		// stats.time counts 60ths of a second
+		let seconds = master.stats.time / 60.0;
+		while let Some(&(time, command)) = self.events.get(self.next) {
+			if time > seconds {
+				break;
+			}
+			run_command(master, spawner, command);
+			self.next += 1;
+		}
+
+		// one whose object has gone (into a kill zone, say) has nothing left to move
+		self.animations.retain(|animation| master.object(animation.id).is_some());
+		for animation in self.animations.iter() {
+			animation.apply(master, seconds);
+		}
+		self.time_scale = None;
+		for automation in self.automations.iter_mut() {
+			automation.apply(master, seconds, &mut self.time_scale);
+		}
+		for emitter in self.emitters.iter_mut() {
+			emitter.update(master, seconds);
+		}
+
+		let new_events = self.sensor_time.replace(master.stats.time) != Some(master.stats.time);
+		if new_events && !self.triggers.is_empty() {
+			for event in master.sensor_events.clone() {
+				let SensorEvent::Enter(sensor, _) = event else {
+					continue;
+				};
+				for trigger in self.triggers.iter_mut().filter(|trigger| trigger.sensor == sensor) {
+					if !(trigger.once && trigger.fired) {
+						run_command(master, spawner, trigger.command);
+						trigger.fired = true;
+					}
+				}
+			}
+		}
+
+		if let Some(game) = &mut self.game {
+			game.update(master, new_events);
+		}
+	}
+
+	// whether there's anything left for it to do
+	pub fn is_running(&self) -> bool {
+		self.next < self.events.len()
+			|| !self.animations.is_empty()
+			|| self.automations.iter().any(|automation| automation.enabled)
+			|| self.emitters.iter().any(|emitter| !emitter.is_done())
+			|| self.game.as_ref().is_some_and(|game| game.outcome.is_none())
+	}
+
+	// the path, automate and at lines it came from
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+
+		for animation in self.animations.iter() {
+			let _ = write!(
+				text,
+				"path {} {} {} {} {}",
+				animation.id.index,
+				animation.curve.name(),
+				animation.duration,
+				animation.easing.name(),
+				animation.repeat.name(),
+			);
+			for point in animation.points.iter() {
+				let _ = write!(text, " {} {}", point.x, point.y);
+			}
+			text.push('\n');
+		}
+		for automation in self.automations.iter() {
+			let _ = writeln!(text, "automate {}", automation.to_text());
+		}
+
+		for (time, command) in self.events.iter() {
+			if let Some(command) = command_text(command) {
+				let _ = writeln!(text, "at {} {}", time, command);
+			}
+		}
+		for trigger in self.triggers.iter() {
+			if let Some(command) = command_text(&trigger.command) {
+				let _ = writeln!(text, "{} {} {}", if trigger.once { "once" } else { "on" }, trigger.sensor, command);
+			}
+		}
+		for emitter in self.emitters.iter() {
+			let object = &emitter.object;
+			let _ = write!(text, "emitter {} {} {} {}", object.position.x, object.position.y, object.radius, emitter.interval);
+			let velocity = object.position - object.last_position;
+			if velocity != Vec2::ZERO {
+				let _ = write!(text, " {} {}", velocity.x, velocity.y);
+			}
+			if let Some(count) = emitter.count {
+				let _ = write!(text, " count {}", count);
+			}
+			if object.mass != object.radius * object.radius {
+				let _ = write!(text, " mass {}", object.mass);
+			}
+			text.push_str(&properties_text(object));
+			text.push('\n');
+		}
+		for cannon in self.cannons.iter() {
+			let _ = writeln!(
+				text,
+				"cannon {} {} {} {} radius {} spin {}",
+				cannon.position.x,
+				cannon.position.y,
+				cannon.angle.to_degrees(),
+				cannon.power,
+				cannon.radius,
+				cannon.spin,
+			);
+		}
+		for trail in self.trails.iter() {
+			let _ = writeln!(text, "trail {} {}", trail.tag, trail.length);
+		}
+
+		if let Some(game) = &self.game {
+			text.push_str(&game.to_text());
+		}
+
+		text
+	}
+}
+
+// the other way from Fields::constraint()
+pub fn constraint_text(constraint: &Constraint) -> String {
+	match constraint {
+		Constraint::Circle { center, radius } => format!("circle {} {} {}", center.x, center.y, radius),
+		Constraint::Aabb { min, max } => format!("box {} {} {} {}", min.x, min.y, max.x, max.y),
+		Constraint::ConvexPolygon(points) => {
+			let points: Vec<String> = points.iter().map(|point| format!("{} {}", point.x, point.y)).collect();
+			format!("polygon {}", points.join(" "))
+		}
+		Constraint::None => "none".to_string(),
+	}
+}
+
+// the other way from Fields::segment()
+// the pivot, both bodies and the rest of it, for after "hinge"
+pub fn hinge_text(hinge: &Hinge) -> String {
+	let mut text = format!("{} {} {} rest {}", hinge.pivot, ids_text(&hinge.a), ids_text(&hinge.b), hinge.rest_angle.to_degrees());
+	if let Some((min, max)) = hinge.limits {
+		let _ = write!(text, " limits {} {}", min.to_degrees(), max.to_degrees());
+	}
+	if let Some(motor) = hinge.motor {
+		let _ = write!(text, " motor {} {}", (motor.speed * 60.0).to_degrees(), motor.torque);
+	}
+	text
+}
+
+// for after "group"
+pub fn group_text(group: &LinkGroup) -> String {
+	format!("{}{}", group.name, if group.enabled { "" } else { " off" })
+}
+
+// the ring and the rest of it, for after "soft_body"
+pub fn soft_body_text(soft_body: &SoftBody) -> String {
+	format!("{} pressure {} area {}", ids_text(&soft_body.ids), soft_body.pressure, soft_body.target_area)
+}
+
+// for after "hierarchy"
+pub fn hierarchy_text(hierarchy: &Hierarchy) -> String {
+	format!("{} stride {} iterations {}", ids_text(&hierarchy.ids), hierarchy.stride, hierarchy.iterations)
+}
+
+// the other way from Fields::properties(), with a space in front unless there's nothing
+// to write
+pub fn properties_text(object: &VerletObject) -> String {
+	let mut text = String::new();
+	if object.restitution > 0.0 {
+		let _ = write!(text, " bounce {}", object.restitution);
+	}
+	if let Some(friction) = object.friction {
+		let _ = write!(text, " friction {}", friction);
+	}
+	if let Some(damping) = object.damping {
+		let _ = write!(text, " damping {}", damping);
+	}
+	if let Some(curve) = object.damping_curve {
+		text.push_str(" damping_curve");
+		for point in curve.points() {
+			let _ = write!(text, " {} {}", point.x, point.y);
+		}
+	}
+	if let Some(magnet) = object.magnet {
+		let _ = write!(text, " magnet {} {} {}", magnet.pole.name(), magnet.strength, magnet.reach);
+	}
+	if object.tag != 0 {
+		let _ = write!(text, " tag {}", object.tag);
+	}
+	if let Some(lifetime) = object.lifetime {
+		let _ = write!(text, " lifetime {}", lifetime / 60.0);
+	}
+	if let Some(bomb) = object.bomb {
+		text.push_str(" bomb");
+		if let Some(fuse) = bomb.fuse {
+			let _ = write!(text, " {}", fuse / 60.0);
+		}
+		if bomb.on_contact {
+			text.push_str(" contact");
+		}
+		let _ = write!(text, " {} {}", bomb.strength, bomb.radius);
+		if bomb.debris > 0 {
+			let _ = write!(text, " debris {}", bomb.debris);
+		}
+		if bomb.breaks_links {
+			text.push_str(" breaks_links");
+		}
+	}
+	text
+}
+
+// the rest of a flock's line, for after "flock"
+pub fn flock_text(flock: &Flock) -> String {
+	format!(
+		"{} vision {} separation {} alignment {} cohesion {} speed {}",
+		flock.tag, flock.vision, flock.separation, flock.alignment, flock.cohesion, flock.speed,
+	)
+}
+
+// runs of ids that count up one at a time as first-last, like 0-9,12,14-15, and none at all
+// as world (for a hinge)
+pub fn ids_text(ids: &[usize]) -> String {
+	if ids.is_empty() {
+		return "world".to_string();
+	}
+	let mut runs: Vec<(usize, usize)> = vec![];
+	for &id in ids.iter() {
+		match runs.last_mut() {
+			Some((_, last)) if id == *last + 1 => *last = id,
+			_ => runs.push((id, id)),
+		}
+	}
+	let runs: Vec<String> = runs.into_iter()
+		.map(|(first, last)| if first == last { first.to_string() } else { format!("{}-{}", first, last) })
+		.collect();
+	runs.join(",")
+}
+
+pub fn segment_text(segment: &StaticSegment) -> String {
+	format!("{} {} {} {} {}", segment.a.x, segment.a.y, segment.b.x, segment.b.y, segment.thickness)
+}
+
+// the other way from Fields::n_body()
+pub fn n_body_text(n_body: &NBody) -> String {
+	format!("{} softening {} theta {}", n_body.strength, n_body.softening, n_body.theta)
+}
+
+// its vector_field line and a vector line for every cell that pushes anything
+pub fn vector_field_text(field: &VectorField) -> String {
+	let mut text = format!("vector_field {} {} {} {} {}\n", field.origin.x, field.origin.y, field.cell_size, field.columns, field.rows);
+	for (cell, push) in field.cells.iter().enumerate().filter(|(_, push)| **push != Vec2::ZERO) {
+		let _ = writeln!(text, "vector {} {} {} {}", cell % field.columns, cell / field.columns, push.x, push.y);
+	}
+	text
+}
+
+// the other way from Fields::kill_zone()
+pub fn kill_zone_text(zone: &KillZone) -> String {
+	match zone.is_below() {
+		true => format!("below {}", zone.min.y),
+		false => format!("{} {} {} {}", zone.min.x, zone.min.y, zone.max.x, zone.max.y),
+	}
+}
+
+// the other way from Fields::command(), None for the ones that can't go on a timeline
+fn command_text(command: &Command) -> Option<String> {
+	Some(match *command {
+		Command::Spawn { position, radius, tag, velocity } => {
+			let mut text = format!("spawn {} {}", position.x, position.y);
+			if let Some(radius) = radius {
+				let _ = write!(text, " {}", radius);
+			}
+			if tag != 0 {
+				let _ = write!(text, " tag {}", tag);
+			}
+			if velocity != Vec2::ZERO {
+				let _ = write!(text, " velocity {} {}", velocity.x, velocity.y);
+			}
+			text
+		}
+		Command::SpawnBurst { position, count, radius } => format!("spawn_burst {} {} {} {}", position.x, position.y, count, radius),
+		Command::Explode { position, strength } => format!("explode {} {} {}", position.x, position.y, strength),
+		Command::Impulse { id, impulse } => match id {
+			Some(id) => format!("impulse {} {} {}", id, impulse.x, impulse.y),
+			None => format!("impulse all {} {}", impulse.x, impulse.y),
+		},
+		Command::BreakLink { id } => format!("break_link {}", id),
+		Command::RepairLink { index } => format!("repair_link {}", index),
+		Command::Despawn { id } => format!("despawn {}", id),
+		Command::Fire { position, velocity, radius, spin } => {
+			format!("fire {} {} {} {} {} {}", position.x, position.y, velocity.x, velocity.y, radius, spin)
+		}
+		Command::SetGroup { group, enabled } => format!("set_group {} {}", group, enabled),
+		Command::SetGravity { gravity } => format!("set_gravity {} {}", gravity.x, gravity.y),
+		Command::SetWind { wind } => format!("set_wind {} {}", wind.x, wind.y),
+		Command::SetSpawnRate { rate } => format!("set_spawn_rate {}", rate),
+		Command::Grab { id, target, hops } => format!("grab {} {} {} {}", id, target.x, target.y, hops),
+		Command::MoveGrab { target } => format!("move_grab {} {}", target.x, target.y),
+		Command::Release => "release".to_string(),
+		Command::QueryStats => return None,
+	})
+}
+
+// a scene file, or a .json one saved by Master::save_scene(), which needs the serde feature
+// and never has a timeline
+pub fn open(path: impl AsRef<Path>) -> io::Result<(Master, Timeline)> {
+	let path = path.as_ref();
+	if path.extension().is_some_and(|extension| extension == "json") {
+		#[cfg(feature = "serde")]
+		return Ok((Master::load_scene(path)?, Timeline::default()));
+		#[cfg(not(feature = "serde"))]
+		return Err(io::Error::new(io::ErrorKind::Unsupported, "json scenes need the serde feature"));
+	}
+	Ok(Scene::load(path)?.into_master())
+}
+
+impl Scene {
+	pub fn load(path: impl AsRef<Path>) -> io::Result<Scene> {
+		Scene::parse(&fs::read_to_string(path)?)
+	}
+
+	pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		fs::write(path, self.to_text())
+	}
+
+	pub fn parse(text: &str) -> io::Result<Scene> {
+		let mut scene = Scene::default();
+
+		for (number, line) in text.lines().enumerate() {
+			let line = line.split('#').next().unwrap_or("");
+			let words: Vec<&str> = line.split_whitespace().collect();
+			if words.is_empty() {
+				continue;
+			}
+
+			let error = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, message));
+			scene.parse_line(&words).map_err(error)?;
+		}
+
+		// stable, so events at the same time keep the order they were written in
+		scene.timeline.events.sort_by(|a, b| a.0.total_cmp(&b.0));
+		Ok(scene)
+	}
+
+	// one line that's already been split into words, see the top of the file
+	pub fn parse_line(&mut self, words: &[&str]) -> Result<(), String> {
+		let mut fields = Fields(&words[1..]);
+
+		match words[0] {
+			"gravity" => self.gravity = Some(fields.vec2()?),
+			"n_body" => self.n_body = Some(fields.n_body()?),
+			"vector_field" => {
+				let origin = fields.vec2()?;
+				let cell_size = fields.number()?;
+				let (columns, rows) = (fields.whole()?, fields.whole()?);
+				if cell_size <= 0.0 || columns == 0 || rows == 0 {
+					return Err(format!("a vector field can't be {} by {} cells {} across", columns, rows, cell_size));
+				}
+				self.vector_field = Some(VectorField::new(origin, cell_size, columns, rows));
+			}
+			"vector" => {
+				let (column, row) = (fields.whole()?, fields.whole()?);
+				let Some(field) = &mut self.vector_field else {
+					return Err("a vector line has to come after the vector_field".to_string());
+				};
+				if column >= field.columns || row >= field.rows {
+					return Err(format!("the vector field is only {} by {}, there's no cell {} {}", field.columns, field.rows, column, row));
+				}
+				field.cells[row * field.columns + column] = fields.vec2()?;
+			}
+			"seed" => {
+				let word = fields.word()?;
+				self.seed = Some(word.parse().map_err(|_| format!("{:?} isn't a seed, which is a whole number", word))?);
+			}
+			"elapsed" => self.elapsed = Some(fields.number()?),
+			"wind" => self.wind = Some(fields.vec2()?),
+			kind @ ("friction" | "damping") => {
+				let value = fields.number()?;
+				if !(0.0..=1.0).contains(&value) {
+					return Err(format!("{} {} isn't from 0 to 1", kind, value));
+				}
+				match kind {
+					"friction" => self.friction = Some(value),
+					_ => self.damping = Some(value),
+				}
+			}
+			"container" => self.container = Some(fields.constraint()?),
+			"object" => {
+				let position = fields.vec2()?;
+				let radius = fields.radius()?;
+				let mut object = VerletObject::new(position, radius);
+				if fields.0.first().is_some_and(|word| word.parse::<f32>().is_ok()) {
+					object.last_position = position - fields.vec2()?;
+				}
+				fields.properties(&mut object)?;
+				self.objects.push(object);
+			}
+			kind @ ("pinned" | "wall" | "sensor" | "bumper") => {
+				let position = fields.vec2()?;
+				let radius = fields.radius()?;
+				let mut object = object_of_kind(kind, position, radius);
+				if kind == "bumper" {
+					object.kick = fields.kick()?;
+				}
+				fields.properties(&mut object)?;
+				self.objects.push(object);
+			}
+			kind @ ("link" | "spring") => {
+				let a = fields.whole()?;
+				let b = fields.whole()?;
+				let (Some(from), Some(to)) = (self.objects.get(a), self.objects.get(b)) else {
+					return Err(format!("{} between {} and {}, but there are only {} objects so far", kind, a, b, self.objects.len()));
+				};
+				if a == b {
+					return Err(format!("a {} from {} to itself", kind, a));
+				}
+				let spring = match kind {
+					"spring" => Some(fields.spring()?),
+					_ => None,
+				};
+				let target_distance = match fields.0.first() {
+					Some(word) if word.parse::<f32>().is_ok() => fields.number()?,
+					_ => from.position.distance(to.position),
+				};
+				let max_strain = fields.max_strain()?;
+				let group = fields.group(self.groups.len())?;
+				self.chain_links.push(ChainLink { a, b, target_distance, priority: 0, spring, max_strain, group });
+			}
+			"group" => {
+				let name = fields.word()?.to_string();
+				let enabled = match fields.0.first() {
+					Some(&"off") => {
+						fields.word()?;
+						false
+					}
+					_ => true,
+				};
+				self.groups.push(LinkGroup { name, enabled });
+			}
+			"segment" => self.segments.push(fields.segment()?),
+			"kill_zone" => self.kill_zones.push(fields.kill_zone()?),
+			"seesaw" => {
+				let pivot = fields.vec2()?;
+				let length = fields.number()?;
+				let tilt = match fields.0.is_empty() {
+					true => 0.0,
+					false => fields.number()?,
+				};
+				Part::seesaw(pivot, length, tilt.to_radians()).add_to_scene(self);
+			}
+			"lever" => {
+				let pivot = fields.vec2()?;
+				let (left, right) = (fields.number()?, fields.number()?);
+				let tilt = match fields.0.is_empty() {
+					true => 0.0,
+					false => fields.number()?,
+				};
+				let limits = match fields.0.is_empty() {
+					true => None,
+					false => Some((fields.number()?, fields.number()?)),
+				};
+				if let Some((min, max)) = limits.filter(|&(min, max)| !(min..=max).contains(&tilt)) {
+					return Err(format!("a lever tilted {} degrees doesn't fit between {} and {}", tilt, min, max));
+				}
+				let limits = limits.map(|(min, max)| (min.to_radians(), max.to_radians()));
+				Part::lever(pivot, left, right, tilt.to_radians(), limits).add_to_scene(self);
+			}
+			"domino_row" => {
+				let (height, spacing) = (fields.number()?, fields.number()?);
+				if spacing <= 0.0 {
+					return Err(format!("dominos {} apart would all be in the same place", spacing));
+				}
+				let mut path = vec![];
+				while !fields.0.is_empty() {
+					path.push(fields.vec2()?);
+				}
+				Part::domino_row(&path, height, spacing).add_to_scene(self);
+			}
+			"cloth" => {
+				let origin = fields.vec2()?;
+				let (cols, rows, spacing) = (fields.whole()?, fields.whole()?, fields.number()?);
+				let (objects, chain_links) = generate_cloth(origin, cols, rows, spacing);
+				let first = self.objects.len();
+				let color = Palette::Random.color();
+				self.objects.extend(objects.into_iter().map(|object| object.with_color(color)));
+				self.chain_links.extend(chain_links.into_iter().map(|chain_link| ChainLink { a: chain_link.a + first, b: chain_link.b + first, ..chain_link }));
+				if fields.0.first() == Some(&"hierarchy") {
+					fields.word()?;
+					let stride = if fields.0.is_empty() { HIERARCHY_STRIDE } else { fields.whole()? };
+					self.push_hierarchy((first..self.objects.len()).collect(), stride, HIERARCHY_ITERATIONS)?;
+				}
+			}
+			"hierarchy" => {
+				let ids = fields.ids()?;
+				let (mut stride, mut iterations) = (HIERARCHY_STRIDE, HIERARCHY_ITERATIONS);
+				while !fields.0.is_empty() {
+					match fields.word()? {
+						"stride" => stride = fields.whole()?,
+						"iterations" => iterations = fields.whole()?,
+						other => return Err(format!("{:?} isn't stride or iterations", other)),
+					}
+				}
+				self.push_hierarchy(ids, stride, iterations)?;
+			}
+			kind @ ("blob" | "player") => {
+				let center = fields.vec2()?;
+				let part = Part::blob(center, fields.number()?);
+				let first = part.add_to_scene(self);
+				let mut soft_body = SoftBody { ids: (first..self.objects.len()).collect(), target_area: 0.0, pressure: BLOB_PRESSURE };
+				soft_body.target_area = soft_body.area_with(|id| Some(self.objects[id].position)).unwrap_or_default();
+				self.soft_bodies.push(soft_body);
+				if kind == "player" {
+					let player = Player { soft_body: self.soft_bodies.len() - 1 };
+					self.timeline.game.get_or_insert_with(Game::default).players.push(player);
+				}
+			}
+			"soft_body" => {
+				let ids = fields.ids()?;
+				if let Some(&id) = ids.iter().find(|&&id| id >= self.objects.len()) {
+					return Err(format!("soft body around object {}, but there are only {} objects so far", id, self.objects.len()));
+				}
+				if ids.len() < 3 {
+					return Err(format!("a ring of {} objects has no inside", ids.len()));
+				}
+				let mut soft_body = SoftBody { ids, target_area: 0.0, pressure: BLOB_PRESSURE };
+				let mut area = None;
+				while !fields.0.is_empty() {
+					match fields.word()? {
+						"pressure" => soft_body.pressure = fields.number()?,
+						"area" => area = Some(fields.number()?),
+						other => return Err(format!("{:?} isn't pressure or area", other)),
+					}
+				}
+				if !(0.0..=1.0).contains(&soft_body.pressure) {
+					return Err(format!("pressure {} isn't from 0 to 1", soft_body.pressure));
+				}
+				soft_body.target_area = area.unwrap_or_else(|| soft_body.area_with(|id| Some(self.objects[id].position)).unwrap_or_default());
+				self.soft_bodies.push(soft_body);
+			}
+			"flock" => {
+				let mut flock = Flock::new(fields.tag()?);
+				while !fields.0.is_empty() {
+					match fields.word()? {
+						"vision" => flock.vision = fields.number()?,
+						"separation" => flock.separation = fields.number()?,
+						"alignment" => flock.alignment = fields.number()?,
+						"cohesion" => flock.cohesion = fields.number()?,
+						"speed" => flock.speed = fields.number()?,
+						other => return Err(format!("{:?} isn't vision, separation, alignment, cohesion or speed", other)),
+					}
+				}
+				self.flocks.push(flock);
+			}
+			"flipper" => {
+				let pivot = fields.vec2()?;
+				let length = fields.number()?;
+				let side = fields.side()?;
+				let droop = FLIPPER_DROOP.to_radians();
+				let angle = match side {
+					Side::Left => droop,
+					Side::Right => std::f32::consts::PI - droop,
+				};
+				let first = Part::flipper(pivot, length, angle).add_to_scene(self);
+				let pivot = self.objects.len() - 1;
+				let swing = FLIPPER_SWING.to_radians() * side.up();
+				let mut hinge = Hinge {
+					pivot,
+					a: vec![],
+					b: (first..pivot).collect(),
+					rest_angle: 0.0,
+					limits: Some((swing.min(0.0), swing.max(0.0))),
+					motor: Some(Motor { speed: -side.up() * FLIPPER_SPEED, torque: FLIPPER_TORQUE }),
+				};
+				hinge.rest_angle = hinge.angle(self.objects[pivot].position, None, self.objects[first].position);
+				self.hinges.push(hinge);
+				let flipper = Flipper { hinge: self.hinges.len() - 1, side };
+				self.timeline.game.get_or_insert_with(Game::default).flippers.push(flipper);
+			}
+			"domino" => {
+				let bottom = fields.vec2()?;
+				Part::domino(bottom, fields.number()?).add_to_scene(self);
+			}
+			"hinge" => {
+				let pivot = fields.whole()?;
+				let (a, b) = (fields.ids()?, fields.ids()?);
+				if let Some(id) = a.iter().chain(b.iter()).copied().chain([pivot]).find(|&id| id >= self.objects.len()) {
+					return Err(format!("hinge on object {}, but there are only {} objects so far", id, self.objects.len()));
+				}
+				let Some(&first) = b.first() else {
+					return Err("a hinge's second body can't be the world".to_string());
+				};
+				let mut hinge = Hinge { pivot, a, b, rest_angle: 0.0, limits: None, motor: None };
+				// where it rests defaults to how it starts out
+				let a = hinge.a.first().map(|&id| self.objects[id].position);
+				hinge.rest_angle = hinge.angle(self.objects[pivot].position, a, self.objects[first].position);
+				while !fields.0.is_empty() {
+					match fields.word()? {
+						"rest" => hinge.rest_angle = fields.number()?.to_radians(),
+						"limits" => {
+							let (min, max) = (fields.number()?, fields.number()?);
+							if min > max {
+								return Err(format!("hinge limits from {} to {} are the wrong way round", min, max));
+							}
+							hinge.limits = Some((min.to_radians(), max.to_radians()));
+						}
+						"motor" => hinge.motor = Some(Motor { speed: fields.number()?.to_radians() / 60.0, torque: fields.number()? }),
+						other => return Err(format!("{:?} isn't rest, limits or motor", other)),
+					}
+				}
+				self.hinges.push(hinge);
+			}
+			"path" => {
+				let id = fields.whole()?;
+				if id >= self.objects.len() {
+					return Err(format!("path for object {}, but there are only {} objects so far", id, self.objects.len()));
+				}
+				let curve = match fields.word()? {
+					"waypoints" => Curve::Waypoints,
+					"bezier" => Curve::Bezier,
+					other => return Err(format!("{:?} isn't waypoints or bezier", other)),
+				};
+				let duration = fields.number()?;
+				let easing = match fields.word()? {
+					"linear" => Easing::Linear,
+					"ease_in" => Easing::EaseIn,
+					"ease_out" => Easing::EaseOut,
+					"ease_in_out" => Easing::EaseInOut,
+					other => return Err(format!("{:?} isn't an easing", other)),
+				};
+				let repeat = fields.repeat()?;
+				let mut points = vec![];
+				while !fields.0.is_empty() {
+					points.push(fields.vec2()?);
+				}
+
+				// nothing in a scene has been taken out yet, so every object is on its first
+				// generation until into_master() hands out the real handles
+				let id = ObjectId { index: id, generation: 0 };
+				let animation = Animation { id, curve, points, duration, easing, repeat };
+				if !animation.is_valid() {
+					return Err("wrong number of points for the path, waypoints need 2 or more and bezier needs 4, 7, 10...".to_string());
+				}
+				self.timeline.animations.push(animation);
+			}
+			"automate" => {
+				let automation = fields.automation()?;
+				self.timeline.automations.push(automation);
+			}
+			"at" => {
+				let time = fields.number()?;
+				let command = fields.command()?;
+				self.timeline.events.push((time, command));
+			}
+			"emitter" => {
+				let position = fields.vec2()?;
+				let radius = fields.radius()?;
+				let interval = fields.number()?;
+				if interval <= 0.0 {
+					return Err(format!("an emitter can't drop one every {} seconds", interval));
+				}
+				let mut object = VerletObject::new(position, radius);
+				if fields.0.first().is_some_and(|word| word.parse::<f32>().is_ok()) {
+					object.last_position = position - fields.vec2()?;
+				}
+				let mut count = None;
+				if fields.0.first() == Some(&"count") {
+					fields.word()?;
+					count = Some(fields.whole()?);
+				}
+				if fields.0.first() == Some(&"mass") {
+					fields.word()?;
+					object.mass = fields.number()?;
+				}
+				fields.properties(&mut object)?;
+				self.timeline.emitters.push(Emitter { object, interval, count, emitted: 0, next: None });
+			}
+			"cannon" => {
+				let mut cannon = Cannon::new(fields.vec2()?);
+				cannon.angle = fields.number()?.to_radians();
+				cannon.power = fields.number()?;
+				if cannon.power <= 0.0 || cannon.power > CANNON_MAX_POWER {
+					return Err(format!("a cannon fires at between 0 and {} pixels a frame, not {}", CANNON_MAX_POWER, cannon.power));
+				}
+				if fields.0.first() == Some(&"radius") {
+					fields.word()?;
+					cannon.radius = fields.radius()?;
+				}
+				if fields.0.first() == Some(&"spin") {
+					fields.word()?;
+					cannon.spin = fields.number()?;
+				}
+				self.timeline.cannons.push(cannon);
+			}
+			"trail" => {
+				let tag = fields.tag()?;
+				let length = fields.whole()?;
+				if length < 2 {
+					return Err(format!("a trail {} frames long is too short to see", length));
+				}
+				self.timeline.trails.push(Trail::new(tag, length));
+			}
+			kind @ ("on" | "once") => {
+				let sensor = fields.whole()?;
+				if !self.objects.get(sensor).is_some_and(|object| object.sensor) {
+					return Err(format!("{} {} isn't a sensor", kind, sensor));
+				}
+				let command = fields.command()?;
+				self.timeline.triggers.push(Trigger { sensor, command, once: kind == "once", fired: false });
+			}
+			"goal" => {
+				let sensor = fields.whole()?;
+				if !self.objects.get(sensor).is_some_and(|object| object.sensor) {
+					return Err(format!("goal {} isn't a sensor", sensor));
+				}
+				let points = fields.integer()?;
+				let lose = fields.0.first() == Some(&"lose");
+				if lose {
+					fields.word()?;
+				}
+				self.timeline.game.get_or_insert_with(Game::default).goals.push(Goal { sensor, points, lose });
+			}
+			"launch" => {
+				let id = fields.whole()?;
+				if id >= self.objects.len() {
+					return Err(format!("launch for object {}, but there are only {} objects so far", id, self.objects.len()));
+				}
+				self.timeline.game.get_or_insert_with(Game::default).launchers.push(id);
+			}
+			"flip" => {
+				let hinge = fields.whole()?;
+				if hinge >= self.hinges.len() {
+					return Err(format!("flip for hinge {}, but there are only {} hinges so far", hinge, self.hinges.len()));
+				}
+				let side = fields.side()?;
+				self.timeline.game.get_or_insert_with(Game::default).flippers.push(Flipper { hinge, side });
+			}
+			"steer" => {
+				let soft_body = fields.whole()?;
+				if soft_body >= self.soft_bodies.len() {
+					return Err(format!("steer for soft body {}, but there are only {} soft bodies so far", soft_body, self.soft_bodies.len()));
+				}
+				self.timeline.game.get_or_insert_with(Game::default).players.push(Player { soft_body });
+			}
+			"win" => self.timeline.game.get_or_insert_with(Game::default).win_score = Some(fields.integer()?),
+			"time_limit" => self.timeline.game.get_or_insert_with(Game::default).time_limit = Some(fields.number()?),
+			other => return Err(format!("don't know what {:?} is", other)),
+		}
+
+		match fields.0 {
+			[] => Ok(()),
+			extra => Err(format!("too much on this line: {:?}", extra)),
+		}
+	}
+
+	fn push_hierarchy(&mut self, ids: Vec<usize>, stride: usize, iterations: usize) -> Result<(), String> {
+		if let Some(&id) = ids.iter().find(|&&id| id >= self.objects.len()) {
+			return Err(format!("hierarchy over object {}, but there are only {} objects so far", id, self.objects.len()));
+		}
+		if stride < 2 {
+			return Err(format!("a stride of {} is no coarser than the links themselves", stride));
+		}
+		self.hierarchies.push(Hierarchy::new(ids, stride, iterations));
+		Ok(())
+	}
+
+	// a scene file that parses back into the same scene. links always get their distance
+	// written out, so they don't depend on where the objects start
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+
+		if let Some(seed) = self.seed {
+			let _ = writeln!(text, "seed {}", seed);
+		}
+		if let Some(elapsed) = self.elapsed {
+			let _ = writeln!(text, "elapsed {}", elapsed);
+		}
+		if let Some(gravity) = self.gravity {
+			let _ = writeln!(text, "gravity {} {}", gravity.x, gravity.y);
+		}
+		if let Some(wind) = self.wind {
+			let _ = writeln!(text, "wind {} {}", wind.x, wind.y);
+		}
+		if let Some(n_body) = &self.n_body {
+			let _ = writeln!(text, "n_body {}", n_body_text(n_body));
+		}
+		if let Some(field) = &self.vector_field {
+			text.push_str(&vector_field_text(field));
+		}
+		if let Some(friction) = self.friction {
+			let _ = writeln!(text, "friction {}", friction);
+		}
+		if let Some(damping) = self.damping {
+			let _ = writeln!(text, "damping {}", damping);
+		}
+		if let Some(container) = &self.container {
+			let _ = writeln!(text, "container {}", constraint_text(container));
+		}
+		for object in self.objects.iter() {
+			let _ = write!(text, "{} {} {} {}", object_kind(object), object.position.x, object.position.y, object.radius);
+			if object_kind(object) == "bumper" {
+				let _ = write!(text, " {}", object.kick);
+			}
+			let velocity = object.position - object.last_position;
+			if !object.kinematic && velocity != Vec2::ZERO {
+				let _ = write!(text, " {} {}", velocity.x, velocity.y);
+			}
+			text.push_str(&properties_text(object));
+			text.push('\n');
+		}
+		for group in self.groups.iter() {
+			let _ = writeln!(text, "group {}", group_text(group));
+		}
+		for chain_link in self.chain_links.iter() {
+			let _ = match chain_link.spring {
+				Some(spring) => write!(text, "spring {} {} {} {} {}", chain_link.a, chain_link.b, spring.stiffness, spring.damping, chain_link.target_distance),
+				None => write!(text, "link {} {} {}", chain_link.a, chain_link.b, chain_link.target_distance),
+			};
+			if let Some(max_strain) = chain_link.max_strain {
+				let _ = write!(text, " break {}", max_strain);
+			}
+			if let Some(group) = chain_link.group {
+				let _ = write!(text, " group {}", group);
+			}
+			text.push('\n');
+		}
+		for segment in self.segments.iter() {
+			let _ = writeln!(text, "segment {}", segment_text(segment));
+		}
+		for zone in self.kill_zones.iter() {
+			let _ = writeln!(text, "kill_zone {}", kill_zone_text(zone));
+		}
+		for hinge in self.hinges.iter() {
+			let _ = writeln!(text, "hinge {}", hinge_text(hinge));
+		}
+		for soft_body in self.soft_bodies.iter() {
+			let _ = writeln!(text, "soft_body {}", soft_body_text(soft_body));
+		}
+		for hierarchy in self.hierarchies.iter() {
+			let _ = writeln!(text, "hierarchy {}", hierarchy_text(hierarchy));
+		}
+		for flock in self.flocks.iter() {
+			let _ = writeln!(text, "flock {}", flock_text(flock));
+		}
+		text.push_str(&self.timeline.to_text());
+
+		text
+	}
+
+	// everything in the master as it is right now, ids packed down to count up from 0 again.
+	// anything the timeline was going to do is gone, it only has what's already happened
+	pub fn from_master(master: &Master) -> Scene {
+		let ids = packed_ids(master);
+		Scene {
+			objects: master.objects.iter().cloned().collect(),
+			chain_links: master.chain_links.iter()
+				.filter_map(|chain_link| Some(ChainLink { a: ids[chain_link.a]?, b: ids[chain_link.b]?, ..chain_link.clone() }))
+				.collect(),
+			groups: master.groups.clone(),
+			segments: master.segments.clone(),
+			kill_zones: master.kill_zones.clone(),
+			hinges: master.hinges.iter()
+				.filter_map(|hinge| {
+					let body = |body: &[usize]| -> Vec<usize> { body.iter().filter_map(|&id| ids.get(id).copied().flatten()).collect() };
+					let hinge = Hinge { pivot: ids.get(hinge.pivot).copied().flatten()?, a: body(&hinge.a), b: body(&hinge.b), ..hinge.clone() };
+					(!hinge.a.is_empty() && !hinge.b.is_empty()).then_some(hinge)
+				})
+				.collect(),
+			// a ring with anything missing from it doesn't have an inside any more
+			soft_bodies: master.soft_bodies.iter()
+				.filter_map(|soft_body| {
+					let ids = soft_body.ids.iter().map(|&id| ids.get(id).copied().flatten()).collect::<Option<Vec<usize>>>()?;
+					(!ids.is_empty()).then(|| SoftBody { ids, ..soft_body.clone() })
+				})
+				.collect(),
+			hierarchies: master.hierarchies.iter()
+				.filter_map(|hierarchy| {
+					let ids: Vec<usize> = hierarchy.ids.iter().filter_map(|&id| ids.get(id).copied().flatten()).collect();
+					(!ids.is_empty()).then(|| Hierarchy::new(ids, hierarchy.stride, hierarchy.iterations))
+				})
+				.collect(),
+			flocks: master.flocks.clone(),
+			gravity: Some(master.gravity),
+			wind: Some(master.wind),
+			n_body: master.n_body,
+			vector_field: master.vector_field.clone(),
+			friction: Some(master.friction),
+			damping: Some(master.damping),
+			container: (master.constraint != Constraint::default()).then(|| master.constraint.clone()),
+			seed: master.rng.as_ref().map(Rng::seed),
+			elapsed: None,
+			timeline: Timeline::default(),
+		}
+	}
+
+	pub fn into_master(mut self) -> (Master, Timeline) {
+		for animation in self.timeline.animations.iter() {
+			let object = &mut self.objects[animation.id.index];
+			object.kinematic = true;
+			object.position = animation.position(0.0);
+			object.last_position = object.position;
+		}
+
+		let mut master = Master::new(self.objects, self.chain_links);
+		master.groups = self.groups;
+		master.segments = self.segments;
+		master.kill_zones = self.kill_zones;
+		master.hinges = self.hinges;
+		master.soft_bodies = self.soft_bodies;
+		for hierarchy in self.hierarchies {
+			master.add_hierarchy(hierarchy.ids, hierarchy.stride, hierarchy.iterations);
+		}
+		master.flocks = self.flocks;
+		if let Some(gravity) = self.gravity {
+			master.gravity = gravity;
+		}
+		if let Some(wind) = self.wind {
+			master.wind = wind;
+		}
+		master.n_body = self.n_body;
+		master.vector_field = self.vector_field;
+		if let Some(friction) = self.friction {
+			master.friction = friction;
+		}
+		if let Some(damping) = self.damping {
+			master.damping = damping;
+		}
+		if let Some(container) = self.container {
+			master.constraint = container;
+		}
+		master.rng = self.seed.map(Rng::new);
+		if let Some(elapsed) = self.elapsed {
+			master.stats.time = elapsed;
+		}
+		for animation in self.timeline.animations.iter_mut() {
+			if let Some(id) = master.object_id(animation.id.index) {
+				animation.id = id;
+			}
+		}
+		(master, self.timeline)
+	}
+}
+
+// the id each object gets in Scene::from_master(), by its id in the master
+pub fn packed_ids(master: &Master) -> Vec<Option<usize>> {
+	let mut ids = vec![None; master.objects.slot_count()];
+	for (new_id, id) in master.objects.ids().enumerate() {
+		ids[id] = Some(new_id);
+	}
+	ids
+}
+
+// the words left on a line, taken off the front one at a time
+pub struct Fields<'a>(pub &'a [&'a str]);
+
+impl Fields<'_> {
+	pub fn word(&mut self) -> Result<&str, String> {
+		let (&word, rest) = self.0.split_first().ok_or("not enough numbers on this line")?;
+		self.0 = rest;
+		Ok(word)
+	}
+
+	pub fn number(&mut self) -> Result<f32, String> {
+		let word = self.word()?;
+		word.parse().map_err(|_| format!("{:?} isn't a number", word))
+	}
+
+	// a number past 0, nothing can be a point or inside out
+	pub fn radius(&mut self) -> Result<f32, String> {
+		let radius = self.number()?;
+		match radius > 0.0 {
+			true => Ok(radius),
+			false => Err(format!("a radius of {} is too small to collide with anything", radius)),
+		}
+	}
+
+	pub fn whole(&mut self) -> Result<usize, String> {
+		let word = self.word()?;
+		word.parse().map_err(|_| format!("{:?} isn't a whole number", word))
+	}
+
+	pub fn tag(&mut self) -> Result<u32, String> {
+		let word = self.word()?;
+		word.parse().map_err(|_| format!("{:?} isn't a tag, which is a whole number", word))
+	}
+
+	// a whole number that can be negative
+	pub fn integer(&mut self) -> Result<i32, String> {
+		let word = self.word()?;
+		word.parse().map_err(|_| format!("{:?} isn't a whole number", word))
+	}
+
+	pub fn side(&mut self) -> Result<Side, String> {
+		match self.word()? {
+			"left" => Ok(Side::Left),
+			"right" => Ok(Side::Right),
+			other => Err(format!("{:?} isn't left or right", other)),
+		}
+	}
+
+	// "break" and then the strain a link comes apart at, if it's there
+	pub fn max_strain(&mut self) -> Result<Option<f32>, String> {
+		if self.0.first() != Some(&"break") {
+			return Ok(None);
+		}
+		self.word()?;
+		let max_strain = self.number()?;
+		if max_strain <= 0.0 {
+			return Err(format!("a link that breaks at a strain of {} never holds together", max_strain));
+		}
+		Ok(Some(max_strain))
+	}
+
+	// the group on the end of a link, out of however many there are so far
+	pub fn group(&mut self, groups: usize) -> Result<Option<usize>, String> {
+		if self.0.first() != Some(&"group") {
+			return Ok(None);
+		}
+		self.word()?;
+		let group = self.whole()?;
+		if group >= groups {
+			return Err(format!("group {}, but there are only {} groups so far", group, groups));
+		}
+		Ok(Some(group))
+	}
+
+	// a bumper's kick, the default when it's left off
+	pub fn kick(&mut self) -> Result<f32, String> {
+		if self.0.first().is_none_or(|&word| word.parse::<f32>().is_err()) {
+			return Ok(BUMPER_KICK);
+		}
+		let kick = self.number()?;
+		if kick <= 0.0 {
+			return Err(format!("a bumper with a kick of {} is just a pinned object", kick));
+		}
+		Ok(kick)
+	}
+
+	// any of bounce, friction and damping (each one from 0 to 1), damping_curve (see
+	// damping_curve()), magnet north|south strength reach, tag n, lifetime seconds and bomb
+	// (see bomb()) on the end of an object's line. whatever's left off stays the way it was
+	pub fn properties(&mut self, object: &mut VerletObject) -> Result<(), String> {
+		while let Some(&word) = self.0.first().filter(|&&word| ["bounce", "friction", "damping", "damping_curve", "magnet", "tag", "lifetime", "bomb"].contains(&word)) {
+			self.word()?;
+			match word {
+				"magnet" => {
+					object.magnet = Some(self.magnet()?);
+					continue;
+				}
+				"damping_curve" => {
+					object.damping_curve = Some(self.damping_curve()?);
+					continue;
+				}
+				"tag" => {
+					object.tag = self.tag()?;
+					continue;
+				}
+				"bomb" => {
+					object.bomb = Some(self.bomb()?);
+					continue;
+				}
+				"lifetime" => {
+					let seconds = self.number()?;
+					if seconds <= 0.0 {
+						return Err(format!("a lifetime of {} seconds would be gone before it started", seconds));
+					}
+					object.lifetime = Some(seconds * 60.0);
+					continue;
+				}
+				_ => {}
+			}
+			let value = self.number()?;
+			if !(0.0..=1.0).contains(&value) {
+				return Err(format!("{} {} isn't from 0 to 1", word, value));
+			}
+			match word {
+				"bounce" => object.restitution = value,
+				"friction" => object.friction = Some(value),
+				_ => object.damping = Some(value),
+			}
+		}
+		Ok(())
+	}
+
+	// the fuse in seconds, contact for one that goes off when it hits something (or both), the
+	// strength and the radius, then debris n and breaks_links if it has those
+	pub fn bomb(&mut self) -> Result<Bomb, String> {
+		let fuse = match self.0.first() {
+			Some(&"contact") => None,
+			_ => Some(self.number()? * 60.0),
+		};
+		let on_contact = self.0.first() == Some(&"contact");
+		if on_contact {
+			self.word()?;
+		}
+		let mut bomb = Bomb::new(fuse, on_contact);
+		bomb.strength = self.number()?;
+		bomb.radius = self.number()?;
+		if bomb.strength < 0.0 || bomb.radius <= 0.0 {
+			return Err(format!("a bomb needs a strength of 0 or more and some radius, not {} and {}", bomb.strength, bomb.radius));
+		}
+		if self.0.first() == Some(&"debris") {
+			self.word()?;
+			bomb.debris = self.whole()?;
+		}
+		if self.0.first() == Some(&"breaks_links") {
+			self.word()?;
+			bomb.breaks_links = true;
+		}
+		Ok(bomb)
+	}
+
+	// speed and damping pairs for as long as there are numbers, slowest first
+	pub fn damping_curve(&mut self) -> Result<DampingCurve, String> {
+		let mut points = vec![];
+		while self.0.first().is_some_and(|word| word.parse::<f32>().is_ok()) {
+			points.push(self.vec2()?);
+		}
+		if let Some(point) = points.iter().find(|point| !(0.0..=1.0).contains(&point.y)) {
+			return Err(format!("damping {} at speed {} isn't from 0 to 1", point.y, point.x));
+		}
+		DampingCurve::new(&points).ok_or_else(|| {
+			format!("a damping curve needs 1 to {} speed and damping pairs from slowest to fastest", verlet_integration::damping_curve::DAMPING_CURVE_POINTS)
+		})
+	}
+
+	pub fn magnet(&mut self) -> Result<Magnet, String> {
+		let pole = match self.word()? {
+			"north" | "n" => Pole::North,
+			"south" | "s" => Pole::South,
+			other => return Err(format!("{:?} isn't north or south", other)),
+		};
+		let strength = self.number()?;
+		let reach = self.number()?;
+		if strength < 0.0 || reach <= 0.0 {
+			return Err(format!("a magnet needs a strength of 0 or more and some reach, not {} and {}", strength, reach));
+		}
+		Ok(Magnet { pole, strength, reach })
+	}
+
+	// a list of ids the way ids_text() writes them
+	pub fn ids(&mut self) -> Result<Vec<usize>, String> {
+		let word = self.word()?;
+		if word == "world" {
+			return Ok(vec![]);
+		}
+		let whole = |word: &str| word.parse::<usize>().map_err(|_| format!("{:?} isn't a whole number", word));
+		let mut ids = vec![];
+		for run in word.split(',') {
+			match run.split_once('-') {
+				Some((first, last)) => ids.extend(whole(first)?..=whole(last)?),
+				None => ids.push(whole(run)?),
+			}
+		}
+		Ok(ids)
+	}
+
+	pub fn vec2(&mut self) -> Result<Vec2, String> {
+		Ok(vec2(self.number()?, self.number()?))
+	}
+
+	pub fn segment(&mut self) -> Result<StaticSegment, String> {
+		Ok(StaticSegment::new(self.vec2()?, self.vec2()?, self.number()?))
+	}
+
+	// two corners, or below y
+	pub fn kill_zone(&mut self) -> Result<KillZone, String> {
+		if self.0.first() == Some(&"below") {
+			self.word()?;
+			return Ok(KillZone::below(self.number()?));
+		}
+		Ok(KillZone::new(self.vec2()?, self.vec2()?))
+	}
+
+	pub fn n_body(&mut self) -> Result<NBody, String> {
+		let mut n_body = NBody::new(self.number()?);
+		if self.0.first() == Some(&"softening") {
+			self.word()?;
+			n_body.softening = self.number()?;
+		}
+		if self.0.first() == Some(&"theta") {
+			self.word()?;
+			n_body.theta = self.number()?;
+		}
+		if n_body.softening < 0.0 || n_body.theta < 0.0 {
+			return Err(format!("n_body's softening and theta can't be negative, not {} and {}", n_body.softening, n_body.theta));
+		}
+		Ok(n_body)
+	}
+
+	pub fn repeat(&mut self) -> Result<Repeat, String> {
+		Ok(match self.word()? {
+			"once" => Repeat::Once,
+			"loop" => Repeat::Loop,
+			"ping_pong" => Repeat::PingPong,
+			other => return Err(format!("{:?} isn't once, loop or ping_pong", other)),
+		})
+	}
+
+	// the parameter, the shape and what it needs, and off on the end for one that starts
+	// switched off
+	pub fn automation(&mut self) -> Result<Automation, String> {
+		let name = self.word()?;
+		let parameter = Parameter::from_name(name).ok_or_else(|| {
+			let names: Vec<&str> = Parameter::ALL.iter().map(|parameter| parameter.name()).collect();
+			format!("{:?} isn't one of {}", name, names.join(", "))
+		})?;
+		let shape = match self.word()? {
+			"sine" => {
+				let (center, amplitude, period) = (self.number()?, self.number()?, self.number()?);
+				let phase = match self.0.first() {
+					Some(&"phase") => {
+						self.word()?;
+						self.number()?
+					}
+					_ => 0.0,
+				};
+				Shape::Sine { center, amplitude, period, phase }
+			}
+			"ramp" => Shape::Ramp { from: self.number()?, to: self.number()?, duration: self.number()?, repeat: self.repeat()? },
+			"steps" => {
+				let step = self.number()?;
+				let mut values = vec![];
+				while self.0.first().is_some_and(|&word| word != "off") {
+					values.push(self.number()?);
+				}
+				if values.is_empty() {
+					return Err("steps needs a value to hold for every step after how long each one is".to_string());
+				}
+				Shape::Steps { step, values }
+			}
+			other => return Err(format!("{:?} isn't sine, ramp or steps", other)),
+		};
+		let period = match shape {
+			Shape::Sine { period, .. } => period,
+			Shape::Ramp { duration, .. } => duration,
+			Shape::Steps { step, .. } => step,
+		};
+		if period <= 0.0 {
+			return Err(format!("an automation can't go round every {} seconds", period));
+		}
+		let mut automation = Automation::new(parameter, shape);
+		if self.0.first() == Some(&"off") {
+			self.word()?;
+			automation.enabled = false;
+		}
+		Ok(automation)
+	}
+
+	// stiffness and then damping, anything past 1 overshoots and blows up
+	pub fn spring(&mut self) -> Result<Spring, String> {
+		let (stiffness, damping) = (self.number()?, self.number()?);
+		if !(0.0..=1.0).contains(&stiffness) || !(0.0..=1.0).contains(&damping) {
+			return Err(format!("a spring's stiffness and damping go from 0 to 1, not {} and {}", stiffness, damping));
+		}
+		Ok(Spring { stiffness, damping })
+	}
+
+	pub fn constraint(&mut self) -> Result<Constraint, String> {
+		Ok(match self.word()? {
+			"circle" => Constraint::Circle { center: self.vec2()?, radius: self.number()? },
+			"box" => Constraint::Aabb { min: self.vec2()?, max: self.vec2()? },
+			"polygon" => {
+				let mut points = vec![];
+				while !self.0.is_empty() {
+					points.push(self.vec2()?);
+				}
+				if points.len() < 3 {
+					return Err("a polygon needs at least 3 corners".to_string());
+				}
+				Constraint::ConvexPolygon(points)
+			}
+			"none" => Constraint::None,
+			other => return Err(format!("{:?} isn't circle, box, polygon or none", other)),
+		})
+	}
+
+	pub fn command(&mut self) -> Result<Command, String> {
+		Ok(match self.word()? {
+			"spawn" => {
+				// the radius is picked at random when it's left off
+				let position = self.vec2()?;
+				let radius = match self.0.first() {
+					Some(word) if word.parse::<f32>().is_ok() => Some(self.radius()?),
+					_ => None,
+				};
+				let tag = match self.0.first() {
+					Some(&"tag") => {
+						self.word()?;
+						self.tag()?
+					}
+					_ => 0,
+				};
+				let velocity = match self.0.first() {
+					Some(&"velocity") => {
+						self.word()?;
+						self.vec2()?
+					}
+					_ => Vec2::ZERO,
+				};
+				Command::Spawn { position, radius, tag, velocity }
+			}
+			"spawn_burst" => Command::SpawnBurst { position: self.vec2()?, count: self.whole()?, radius: self.number()? },
+			"explode" => Command::Explode { position: self.vec2()?, strength: self.number()? },
+			"impulse" => {
+				// "all" for every object
+				let id = match self.0.first() {
+					Some(&"all") => {
+						self.word()?;
+						None
+					}
+					_ => Some(self.whole()?),
+				};
+				Command::Impulse { id, impulse: self.vec2()? }
+			}
+			"break_link" => Command::BreakLink { id: self.whole()? },
+			"repair_link" => Command::RepairLink { index: self.whole()? },
+			"despawn" => Command::Despawn { id: self.whole()? },
+			"fire" => {
+				let (position, velocity, radius) = (self.vec2()?, self.vec2()?, self.number()?);
+				let spin = if self.0.is_empty() { 0.0 } else { self.number()? };
+				Command::Fire { position, velocity, radius, spin }
+			}
+			"set_group" => {
+				let group = self.whole()?;
+				let word = self.word()?;
+				let enabled = word.parse().map_err(|_| format!("{:?} isn't true or false", word))?;
+				Command::SetGroup { group, enabled }
+			}
+			"set_gravity" => Command::SetGravity { gravity: self.vec2()? },
+			"set_wind" => Command::SetWind { wind: self.vec2()? },
+			"set_spawn_rate" => Command::SetSpawnRate { rate: self.number()? },
+			"grab" => Command::Grab { id: self.whole()?, target: self.vec2()?, hops: self.whole()? },
+			"move_grab" => Command::MoveGrab { target: self.vec2()? },
+			"release" => Command::Release,
+			other => return Err(format!("{:?} isn't a command that can go on the timeline", other)),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// two objects and a link between them, for the lines being tested to point at
+	const PAIR: &str = "object 100 100 10\nobject 130 100 10\nlink 0 1\n";
+
+	fn error(text: &str) -> String {
+		Scene::parse(text).expect_err(text).to_string()
+	}
+
+	#[test]
+	fn what_it_writes_reads_back() {
+		let scene = Scene::parse(&format!("{}path 0 bezier 4 ease_in_out ping_pong 300 300 350 200 450 200 500 300\nat 2 spawn 480 200 12\n", PAIR)).unwrap();
+		let again = Scene::parse(&scene.to_text()).unwrap();
+		assert_eq!(again.objects.len(), 2);
+		assert_eq!(again.chain_links.len(), 1);
+		assert_eq!(again.timeline.animations.len(), 1);
+		assert_eq!(again.timeline.events.len(), 1);
+	}
+
+	#[test]
+	fn ids_past_the_objects_so_far_are_rejected() {
+		for line in ["link 0 2", "spring 5 1 0.5 0.1", "soft_body 0-3", "hierarchy 0-2", "hinge 0 1 2", "path 4 waypoints 1 linear once 0 0 10 10", "launch 2"] {
+			let message = error(&format!("{}{}\n", PAIR, line));
+			// pointing at the line it's on
+			assert!(message.starts_with("line 4: ") && message.ends_with("so far"), "{}: {}", line, message);
+		}
+		// and it's the objects so far, not in the whole file
+		error("link 0 1\nobject 100 100 10\nobject 130 100 10\n");
+		error(&format!("{}flip 0 left\n", PAIR));
+		error(&format!("{}steer 0\n", PAIR));
+	}
+
+	#[test]
+	fn counts_that_make_no_sense_are_rejected() {
+		error("vector_field 0 0 40 0 15\n");
+		error("vector_field 0 0 40 2 2\nvector 2 0 1 1\n");
+		error("vector 0 0 1 1\n");
+		error("container polygon 0 0 100 0\n");
+		error(&format!("{}soft_body 0-1\n", PAIR));
+		error(&format!("{}hierarchy 0-1 stride 1\n", PAIR));
+		error("object 100 100\n");
+		error("object 100 100 10 2 -1 3\n");
+		error("object 100 100 0\n");
+		error("wall 100 100 -4\n");
+		error(&format!("{}link 0 1 30 extra\n", PAIR));
+		error(&format!("{}link 1 1\n", PAIR));
+		error("bend 1 2\n");
+	}
+
+	#[test]
+	fn paths_need_the_right_number_of_points() {
+		let path = |curve: &str, points: usize| {
+			let points: Vec<String> = (0..points).map(|i| format!("{} 0", i * 10)).collect();
+			Scene::parse(&format!("{}path 0 {} 2 linear once {}\n", PAIR, curve, points.join(" ")))
+		};
+		assert!(path("waypoints", 1).is_err());
+		assert!(path("waypoints", 2).is_ok());
+		assert!(path("waypoints", 5).is_ok());
+		for points in [0, 1, 2, 3, 5, 6, 8] {
+			assert!(path("bezier", points).is_err(), "{} points", points);
+		}
+		for points in [4, 7, 10] {
+			assert!(path("bezier", points).is_ok(), "{} points", points);
+		}
+	}
+}
@@ -0,0 +1,733 @@
+use std::fmt::Write as _;
+use std::io;
+
+use macroquad::prelude::*;
+use verlet_integration::{Bomb, ChainLink, Constraint, DampingCurve, KillZone, LinkGroup, Magnet, NBody, StaticSegment, VectorField, VerletObject};
+
+use crate::scene::{constraint_text, flock_text, group_text, hierarchy_text, hinge_text, kill_zone_text, n_body_text, object_kind, object_of_kind, properties_text, segment_text, soft_body_text, vector_field_text, Fields, Scene, Timeline, OBJECT_KINDS};
+
+// what changed from one scene to another, small enough to pass around instead of the whole
+// scene. objects and links are matched up by the longest run of them that's the same in both,
+// so taking one out of the middle is one removal rather than everything after it moving.
+// as text it's one change per line:
+//
+//   remove_object 3            ids in the old scene
+//   change_object 5 480 300 10 position and radius, with "pinned", "wall", "sensor" or "bumper 6"
+//                              on the end for those and then the same bounce, friction,
+//                              damping, magnet, tag, lifetime and bomb as an object line in
+//                              a scene
+//   add_object 12 480 200 8    ids in the new scene, in order
+//   remove_link 7              link ids in the old scene, after the ones that went with
+//                              removed objects are gone
+//   add_link 9 4 5 40          link id in the new scene, then a b distance in new ids (and
+//                              stiffness and damping on the end for a spring, then break and
+//                              the strain for one that breaks, then group and its number)
+//   clear_groups               all of the link groups go, the ones added after it are the new
+//   add_group scaffolding off  scene's, the same as a group line in a scene
+//   remove_segment 2           segment ids in the old scene, the rest stay in order
+//   add_segment 0 90 900 90 4  added after them
+//   clear_kill_zones           all of the kill zones go, the ones added after it are the new
+//   add_kill_zone below 650    scene's, the same as a kill_zone line in a scene
+//   gravity 0 1                or gravity none, and the same for wind, friction, damping
+//                              and n_body
+//   vector_field none          or the new scene's whole vector field, with its vector lines
+//                              after it the same as in a scene
+//   container box 0 0 960 600  or container default
+//   clear_hinges               all of the hinges go, the ones added after it are the new scene's
+//   add_hinge 20 21 0-19       the same as a hinge line in a scene, in new ids
+//   clear_soft_bodies          and the same for soft bodies
+//   add_soft_body 0-25 pressure 0.5 area 7800
+//   clear_hierarchies          and hierarchies
+//   add_hierarchy 0-1199 stride 4 iterations 2
+//   clear_flocks               and flocks
+//   add_flock 1 vision 60 separation 0.6 alignment 0.1 cohesion 0.01 speed 4
+//   timeline                   the new scene's whole timeline follows, replacing the old one
+//   at 2 spawn_burst 480 200 12 8
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SceneDiff {
+	pub removed_objects: Vec<usize>,
+	pub changed_objects: Vec<(usize, Shape)>,
+	pub added_objects: Vec<(usize, Shape)>,
+	pub removed_links: Vec<usize>,
+	pub added_links: Vec<(usize, ChainLink)>,
+	// all of the new scene's
+	pub groups: Option<Vec<LinkGroup>>,
+	pub removed_segments: Vec<usize>,
+	pub added_segments: Vec<StaticSegment>,
+	// all of the new scene's
+	pub kill_zones: Option<Vec<KillZone>>,
+	pub gravity: Option<Option<Vec2>>,
+	pub wind: Option<Option<Vec2>>,
+	pub n_body: Option<Option<NBody>>,
+	pub vector_field: Option<Option<VectorField>>,
+	pub friction: Option<Option<f32>>,
+	pub damping: Option<Option<f32>>,
+	pub container: Option<Option<Constraint>>,
+	// all of the new scene's hinges, as what comes after "hinge" on their lines, in new ids
+	pub hinges: Option<Vec<String>>,
+	pub soft_bodies: Option<Vec<String>>,
+	pub hierarchies: Option<Vec<String>>,
+	pub flocks: Option<Vec<String>>,
+	// its path and at lines, paths in new object ids
+	pub timeline: Option<String>,
+}
+
+// everything a scene file says about an object
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shape {
+	pub position: Vec2,
+	pub radius: f32,
+	// the word that starts its line in a scene file, see scene::object_kind()
+	pub kind: &'static str,
+	// only for bumpers
+	pub kick: f32,
+	// see scene::properties_text()
+	pub restitution: f32,
+	pub friction: Option<f32>,
+	pub damping: Option<f32>,
+	pub damping_curve: Option<DampingCurve>,
+	pub magnet: Option<Magnet>,
+	pub tag: u32,
+	pub lifetime: Option<f32>,
+	pub bomb: Option<Bomb>,
+}
+
+impl Shape {
+	fn of(object: &VerletObject) -> Shape {
+		Shape {
+			position: object.position,
+			radius: object.radius,
+			kind: object_kind(object),
+			kick: object.kick,
+			restitution: object.restitution,
+			friction: object.friction,
+			damping: object.damping,
+			damping_curve: object.damping_curve,
+			magnet: object.magnet,
+			tag: object.tag,
+			lifetime: object.lifetime,
+			bomb: object.bomb,
+		}
+	}
+
+	fn object(&self) -> VerletObject {
+		let mut object = object_of_kind(self.kind, self.position, self.radius).with_restitution(self.restitution);
+		if self.kind == "bumper" {
+			object.kick = self.kick;
+		}
+		object.friction = self.friction;
+		object.damping = self.damping;
+		object.damping_curve = self.damping_curve;
+		object.magnet = self.magnet;
+		object.tag = self.tag;
+		object.lifetime = self.lifetime;
+		object.bomb = self.bomb;
+		object
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edit {
+	Keep(usize, usize),
+	Remove(usize),
+	Add(usize),
+}
+
+// the fewest removals and additions to get from old to new, off the longest common subsequence
+fn edits<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+	let width = new.len() + 1;
+	// common[i * width + j] is how long the longest common run of old[i..] and new[j..] is
+	let mut common = vec![0u32; (old.len() + 1) * width];
+	for i in (0..old.len()).rev() {
+		for j in (0..new.len()).rev() {
+			common[i * width + j] = match old[i] == new[j] {
+				true => common[(i + 1) * width + j + 1] + 1,
+				false => common[(i + 1) * width + j].max(common[i * width + j + 1]),
+			};
+		}
+	}
+
+	let mut result = vec![];
+	let (mut i, mut j) = (0, 0);
+	while i < old.len() || j < new.len() {
+		if i < old.len() && j < new.len() && old[i] == new[j] {
+			result.push(Edit::Keep(i, j));
+			i += 1;
+			j += 1;
+		} else if j == new.len() || (i < old.len() && common[(i + 1) * width + j] >= common[i * width + j + 1]) {
+			result.push(Edit::Remove(i));
+			i += 1;
+		} else {
+			result.push(Edit::Add(j));
+			j += 1;
+		}
+	}
+	result
+}
+
+// where each object in the old scene ends up in the new one. the diff and the patch both
+// work this out the same way, which is what lets the link ids in the diff line up
+fn object_ids(old_count: usize, removed: &[usize], added: &[usize]) -> Vec<Option<usize>> {
+	let mut kept: Vec<Option<usize>> = (0..old_count).filter(|id| !removed.contains(id)).map(Some).collect();
+	for &id in added {
+		kept.insert(id.min(kept.len()), None);
+	}
+
+	let mut ids = vec![None; old_count];
+	for (new_id, old_id) in kept.into_iter().enumerate() {
+		if let Some(old_id) = old_id {
+			ids[old_id] = Some(new_id);
+		}
+	}
+	ids
+}
+
+// the old scene's links with their ends in new ids, along with their old link ids. links to
+// objects that are gone go with them
+fn moved_links(scene: &Scene, ids: &[Option<usize>]) -> Vec<(usize, ChainLink)> {
+	scene.chain_links.iter().enumerate()
+		.filter_map(|(id, chain_link)| Some((id, ChainLink {
+			a: ids[chain_link.a]?,
+			b: ids[chain_link.b]?,
+			..chain_link.clone()
+		})))
+		.collect()
+}
+
+impl SceneDiff {
+	pub fn between(old: &Scene, new: &Scene) -> SceneDiff {
+		let mut diff = SceneDiff::default();
+
+		let old_shapes: Vec<Shape> = old.objects.iter().map(Shape::of).collect();
+		let new_shapes: Vec<Shape> = new.objects.iter().map(Shape::of).collect();
+
+		// removals and additions between the same two kept objects pair up as changes, in order
+		let mut removed = vec![];
+		let mut added = vec![];
+		let flush = |removed: &mut Vec<usize>, added: &mut Vec<usize>, diff: &mut SceneDiff| {
+			let paired = removed.len().min(added.len());
+			for (&old_id, &new_id) in removed.iter().zip(added.iter()) {
+				diff.changed_objects.push((old_id, new_shapes[new_id]));
+			}
+			diff.removed_objects.extend(removed.drain(..).skip(paired));
+			diff.added_objects.extend(added.drain(..).skip(paired).map(|id| (id, new_shapes[id])));
+		};
+		for edit in edits(&old_shapes, &new_shapes) {
+			match edit {
+				Edit::Keep(..) => flush(&mut removed, &mut added, &mut diff),
+				Edit::Remove(id) => removed.push(id),
+				Edit::Add(id) => added.push(id),
+			}
+		}
+		flush(&mut removed, &mut added, &mut diff);
+		diff.removed_objects.sort_unstable();
+		diff.added_objects.sort_by_key(|&(id, ..)| id);
+
+		let added_ids: Vec<usize> = diff.added_objects.iter().map(|&(id, ..)| id).collect();
+		let ids = object_ids(old.objects.len(), &diff.removed_objects, &added_ids);
+		let moved = moved_links(old, &ids);
+		let moved_only: Vec<ChainLink> = moved.iter().map(|(_, chain_link)| chain_link.clone()).collect();
+		for edit in edits(&moved_only, &new.chain_links) {
+			match edit {
+				Edit::Keep(..) => {}
+				Edit::Remove(index) => diff.removed_links.push(moved[index].0),
+				Edit::Add(id) => diff.added_links.push((id, new.chain_links[id].clone())),
+			}
+		}
+
+		// they're all the same to the solver, so there's no point matching runs of them up
+		diff.removed_segments = (0..old.segments.len()).filter(|&id| !new.segments.contains(&old.segments[id])).collect();
+		diff.added_segments = new.segments.iter().filter(|segment| !old.segments.contains(segment)).copied().collect();
+
+		diff.gravity = (old.gravity != new.gravity).then_some(new.gravity);
+		diff.wind = (old.wind != new.wind).then_some(new.wind);
+		diff.n_body = (old.n_body != new.n_body).then_some(new.n_body);
+		diff.vector_field = (old.vector_field != new.vector_field).then(|| new.vector_field.clone());
+		diff.friction = (old.friction != new.friction).then_some(new.friction);
+		diff.damping = (old.damping != new.damping).then_some(new.damping);
+		diff.container = (old.container != new.container).then(|| new.container.clone());
+		diff.groups = (old.groups != new.groups).then(|| new.groups.clone());
+		diff.kill_zones = (old.kill_zones != new.kill_zones).then(|| new.kill_zones.clone());
+		diff.hinges = (old.hinges != new.hinges).then(|| new.hinges.iter().map(hinge_text).collect());
+		diff.soft_bodies = (old.soft_bodies != new.soft_bodies).then(|| new.soft_bodies.iter().map(soft_body_text).collect());
+		diff.hierarchies = (old.hierarchies != new.hierarchies).then(|| new.hierarchies.iter().map(hierarchy_text).collect());
+		diff.flocks = (old.flocks != new.flocks).then(|| new.flocks.iter().map(flock_text).collect());
+		let timeline = new.timeline.to_text();
+		diff.timeline = (old.timeline.to_text() != timeline).then_some(timeline);
+
+		diff
+	}
+
+	pub fn is_empty(&self) -> bool {
+		*self == SceneDiff::default()
+	}
+
+	// the scene the diff was made against, changed the same way. errors if it doesn't fit
+	pub fn apply(&self, scene: &Scene) -> io::Result<Scene> {
+		let error = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+		let count = scene.objects.len();
+		if let Some(&id) = self.removed_objects.iter().chain(self.changed_objects.iter().map(|(id, ..)| id)).find(|&&id| id >= count) {
+			return Err(error(format!("the patch changes object {}, but the scene only has {}", id, count)));
+		}
+		if let Some(&id) = self.removed_links.iter().find(|&&id| id >= scene.chain_links.len()) {
+			return Err(error(format!("the patch removes link {}, but the scene only has {}", id, scene.chain_links.len())));
+		}
+		if let Some(&id) = self.removed_segments.iter().find(|&&id| id >= scene.segments.len()) {
+			return Err(error(format!("the patch removes segment {}, but the scene only has {}", id, scene.segments.len())));
+		}
+
+		let mut objects = scene.objects.clone();
+		for (id, shape) in self.changed_objects.iter() {
+			objects[*id] = shape.object();
+		}
+		let mut objects: Vec<VerletObject> = objects.into_iter().enumerate()
+			.filter(|(id, _)| !self.removed_objects.contains(id))
+			.map(|(_, object)| object)
+			.collect();
+		for (id, shape) in self.added_objects.iter() {
+			objects.insert((*id).min(objects.len()), shape.object());
+		}
+
+		let added_ids: Vec<usize> = self.added_objects.iter().map(|&(id, ..)| id).collect();
+		let ids = object_ids(count, &self.removed_objects, &added_ids);
+		let mut chain_links: Vec<ChainLink> = moved_links(scene, &ids).into_iter()
+			.filter(|(id, _)| !self.removed_links.contains(id))
+			.map(|(_, chain_link)| chain_link)
+			.collect();
+		for (id, chain_link) in self.added_links.iter() {
+			if chain_link.a.max(chain_link.b) >= objects.len() {
+				return Err(error(format!("the patch links {} and {}, but there are only {} objects", chain_link.a, chain_link.b, objects.len())));
+			}
+			chain_links.insert((*id).min(chain_links.len()), chain_link.clone());
+		}
+		let groups = self.groups.clone().unwrap_or_else(|| scene.groups.clone());
+		if let Some(group) = chain_links.iter().filter_map(|chain_link| chain_link.group).find(|&group| group >= groups.len()) {
+			return Err(error(format!("the patch puts a link in group {}, but there are only {} groups", group, groups.len())));
+		}
+
+		let segments = scene.segments.iter().enumerate()
+			.filter(|(id, _)| !self.removed_segments.contains(id))
+			.map(|(_, segment)| *segment)
+			.chain(self.added_segments.iter().copied())
+			.collect();
+
+		let mut patched = Scene {
+			objects,
+			chain_links,
+			groups,
+			segments,
+			kill_zones: self.kill_zones.clone().unwrap_or_else(|| scene.kill_zones.clone()),
+			gravity: self.gravity.unwrap_or(scene.gravity),
+			wind: self.wind.unwrap_or(scene.wind),
+			n_body: self.n_body.unwrap_or(scene.n_body),
+			vector_field: self.vector_field.clone().unwrap_or_else(|| scene.vector_field.clone()),
+			friction: self.friction.unwrap_or(scene.friction),
+			damping: self.damping.unwrap_or(scene.damping),
+			container: self.container.clone().unwrap_or_else(|| scene.container.clone()),
+			hinges: scene.hinges.clone(),
+			soft_bodies: scene.soft_bodies.clone(),
+			hierarchies: scene.hierarchies.clone(),
+			flocks: scene.flocks.clone(),
+			seed: scene.seed,
+			elapsed: scene.elapsed,
+			timeline: scene.timeline.clone(),
+		};
+
+		// the same goes for hinges, the old ones would be in old ids anyway
+		if let Some(hinges) = &self.hinges {
+			patched.hinges.clear();
+			for hinge in hinges.iter() {
+				let words: Vec<&str> = ["hinge"].into_iter().chain(hinge.split_whitespace()).collect();
+				patched.parse_line(&words).map_err(error)?;
+			}
+		}
+		if let Some(soft_bodies) = &self.soft_bodies {
+			patched.soft_bodies.clear();
+			for soft_body in soft_bodies.iter() {
+				let words: Vec<&str> = ["soft_body"].into_iter().chain(soft_body.split_whitespace()).collect();
+				patched.parse_line(&words).map_err(error)?;
+			}
+		}
+		if let Some(hierarchies) = &self.hierarchies {
+			patched.hierarchies.clear();
+			for hierarchy in hierarchies.iter() {
+				let words: Vec<&str> = ["hierarchy"].into_iter().chain(hierarchy.split_whitespace()).collect();
+				patched.parse_line(&words).map_err(error)?;
+			}
+		}
+		if let Some(flocks) = &self.flocks {
+			patched.flocks.clear();
+			for flock in flocks.iter() {
+				let words: Vec<&str> = ["flock"].into_iter().chain(flock.split_whitespace()).collect();
+				patched.parse_line(&words).map_err(error)?;
+			}
+		}
+
+		// paths check their object ids, so this has to wait for the new objects
+		if let Some(text) = &self.timeline {
+			patched.timeline = Timeline::default();
+			for line in text.lines() {
+				let words: Vec<&str> = line.split_whitespace().collect();
+				if !words.is_empty() {
+					patched.parse_line(&words).map_err(error)?;
+				}
+			}
+			patched.timeline.events.sort_by(|a, b| a.0.total_cmp(&b.0));
+		}
+
+		Ok(patched)
+	}
+
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+		let optional = |value: Option<Vec2>| value.map_or("none".to_string(), |value| format!("{} {}", value.x, value.y));
+
+		for id in self.removed_objects.iter() {
+			let _ = writeln!(text, "remove_object {}", id);
+		}
+		let shape = |shape: &Shape| format!(
+			"{} {} {}{}{}",
+			shape.position.x,
+			shape.position.y,
+			shape.radius,
+			match shape.kind {
+				"object" => String::new(),
+				"bumper" => format!(" bumper {}", shape.kick),
+				kind => format!(" {}", kind),
+			},
+			properties_text(&shape.object()),
+		);
+		for (id, changed) in self.changed_objects.iter() {
+			let _ = writeln!(text, "change_object {} {}", id, shape(changed));
+		}
+		for (id, added) in self.added_objects.iter() {
+			let _ = writeln!(text, "add_object {} {}", id, shape(added));
+		}
+		for id in self.removed_links.iter() {
+			let _ = writeln!(text, "remove_link {}", id);
+		}
+		for (id, chain_link) in self.added_links.iter() {
+			let _ = write!(text, "add_link {} {} {} {}", id, chain_link.a, chain_link.b, chain_link.target_distance);
+			if let Some(spring) = chain_link.spring {
+				let _ = write!(text, " {} {}", spring.stiffness, spring.damping);
+			}
+			if let Some(max_strain) = chain_link.max_strain {
+				let _ = write!(text, " break {}", max_strain);
+			}
+			if let Some(group) = chain_link.group {
+				let _ = write!(text, " group {}", group);
+			}
+			text.push('\n');
+		}
+		if let Some(groups) = &self.groups {
+			let _ = writeln!(text, "clear_groups");
+			for group in groups.iter() {
+				let _ = writeln!(text, "add_group {}", group_text(group));
+			}
+		}
+		for id in self.removed_segments.iter() {
+			let _ = writeln!(text, "remove_segment {}", id);
+		}
+		for segment in self.added_segments.iter() {
+			let _ = writeln!(text, "add_segment {}", segment_text(segment));
+		}
+		if let Some(kill_zones) = &self.kill_zones {
+			let _ = writeln!(text, "clear_kill_zones");
+			for zone in kill_zones.iter() {
+				let _ = writeln!(text, "add_kill_zone {}", kill_zone_text(zone));
+			}
+		}
+		if let Some(gravity) = self.gravity {
+			let _ = writeln!(text, "gravity {}", optional(gravity));
+		}
+		if let Some(wind) = self.wind {
+			let _ = writeln!(text, "wind {}", optional(wind));
+		}
+		if let Some(n_body) = self.n_body {
+			let _ = writeln!(text, "n_body {}", n_body.as_ref().map_or("none".to_string(), n_body_text));
+		}
+		if let Some(field) = &self.vector_field {
+			match field {
+				Some(field) => text.push_str(&vector_field_text(field)),
+				None => text.push_str("vector_field none\n"),
+			}
+		}
+		if let Some(friction) = self.friction {
+			let _ = writeln!(text, "friction {}", friction.map_or("none".to_string(), |friction| friction.to_string()));
+		}
+		if let Some(damping) = self.damping {
+			let _ = writeln!(text, "damping {}", damping.map_or("none".to_string(), |damping| damping.to_string()));
+		}
+		if let Some(container) = &self.container {
+			let _ = writeln!(text, "container {}", container.as_ref().map_or("default".to_string(), constraint_text));
+		}
+		if let Some(hinges) = &self.hinges {
+			let _ = writeln!(text, "clear_hinges");
+			for hinge in hinges.iter() {
+				let _ = writeln!(text, "add_hinge {}", hinge);
+			}
+		}
+		if let Some(soft_bodies) = &self.soft_bodies {
+			let _ = writeln!(text, "clear_soft_bodies");
+			for soft_body in soft_bodies.iter() {
+				let _ = writeln!(text, "add_soft_body {}", soft_body);
+			}
+		}
+		if let Some(hierarchies) = &self.hierarchies {
+			let _ = writeln!(text, "clear_hierarchies");
+			for hierarchy in hierarchies.iter() {
+				let _ = writeln!(text, "add_hierarchy {}", hierarchy);
+			}
+		}
+		if let Some(flocks) = &self.flocks {
+			let _ = writeln!(text, "clear_flocks");
+			for flock in flocks.iter() {
+				let _ = writeln!(text, "add_flock {}", flock);
+			}
+		}
+		if let Some(timeline) = &self.timeline {
+			let _ = writeln!(text, "timeline");
+			text.push_str(timeline);
+		}
+
+		text
+	}
+
+	pub fn parse(text: &str) -> io::Result<SceneDiff> {
+		let mut diff = SceneDiff::default();
+
+		let mut lines = text.lines().enumerate();
+		for (number, line) in lines.by_ref() {
+			let line = line.split('#').next().unwrap_or("");
+			let words: Vec<&str> = line.split_whitespace().collect();
+			if words.is_empty() {
+				continue;
+			}
+			// everything after this is the timeline, it gets checked once there are objects for it
+			if words == ["timeline"] {
+				diff.timeline = Some(String::new());
+				break;
+			}
+
+			diff.parse_line(&words)
+				.map_err(|message| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, message)))?;
+		}
+
+		if let Some(timeline) = &mut diff.timeline {
+			for (_, line) in lines {
+				timeline.push_str(line);
+				timeline.push('\n');
+			}
+		}
+
+		Ok(diff)
+	}
+
+	fn parse_line(&mut self, words: &[&str]) -> Result<(), String> {
+		let mut fields = Fields(&words[1..]);
+		let optional = |fields: &mut Fields| match fields.0 {
+			["none"] => {
+				fields.0 = &[];
+				Ok(None)
+			}
+			_ => fields.vec2().map(Some),
+		};
+		let optional_number = |fields: &mut Fields| match fields.0 {
+			["none"] => {
+				fields.0 = &[];
+				Ok(None)
+			}
+			_ => fields.number().map(Some),
+		};
+		let shape = |fields: &mut Fields| {
+			let position = fields.vec2()?;
+			let radius = fields.number()?;
+			let kind = OBJECT_KINDS.into_iter().find(|&kind| fields.0.first() == Some(&kind)).unwrap_or("object");
+			if kind != "object" {
+				fields.word()?;
+			}
+			let kick = match kind {
+				"bumper" => fields.kick()?,
+				_ => 0.0,
+			};
+			let mut object = object_of_kind(kind, position, radius);
+			fields.properties(&mut object)?;
+			Ok::<_, String>(Shape {
+				position,
+				radius,
+				kind,
+				kick,
+				restitution: object.restitution,
+				friction: object.friction,
+				damping: object.damping,
+				damping_curve: object.damping_curve,
+				magnet: object.magnet,
+				tag: object.tag,
+				lifetime: object.lifetime,
+				bomb: object.bomb,
+			})
+		};
+
+		match words[0] {
+			"remove_object" => self.removed_objects.push(fields.whole()?),
+			"change_object" => self.changed_objects.push((fields.whole()?, shape(&mut fields)?)),
+			"add_object" => self.added_objects.push((fields.whole()?, shape(&mut fields)?)),
+			"remove_link" => self.removed_links.push(fields.whole()?),
+			"add_link" => {
+				let id = fields.whole()?;
+				let (a, b, target_distance) = (fields.whole()?, fields.whole()?, fields.number()?);
+				let spring = match fields.0.first() {
+					None | Some(&"break") | Some(&"group") => None,
+					Some(_) => Some(fields.spring()?),
+				};
+				let max_strain = fields.max_strain()?;
+				// checked against the groups when it's applied
+				let group = fields.group(usize::MAX)?;
+				let chain_link = ChainLink { a, b, target_distance, priority: 0, spring, max_strain, group };
+				self.added_links.push((id, chain_link));
+			}
+			"remove_segment" => self.removed_segments.push(fields.whole()?),
+			"add_segment" => self.added_segments.push(fields.segment()?),
+			"clear_kill_zones" => self.kill_zones = Some(vec![]),
+			"add_kill_zone" => self.kill_zones.get_or_insert_with(Vec::new).push(fields.kill_zone()?),
+			"clear_groups" => self.groups = Some(vec![]),
+			"add_group" => {
+				let mut scene = Scene::default();
+				scene.parse_line(&[&["group"], fields.0].concat())?;
+				self.groups.get_or_insert_with(Vec::new).extend(scene.groups);
+				fields.0 = &[];
+			}
+			"clear_hinges" => self.hinges = Some(vec![]),
+			"add_hinge" => {
+				self.hinges.get_or_insert_with(Vec::new).push(fields.0.join(" "));
+				fields.0 = &[];
+			}
+			"clear_soft_bodies" => self.soft_bodies = Some(vec![]),
+			"add_soft_body" => {
+				self.soft_bodies.get_or_insert_with(Vec::new).push(fields.0.join(" "));
+				fields.0 = &[];
+			}
+			"clear_hierarchies" => self.hierarchies = Some(vec![]),
+			"add_hierarchy" => {
+				self.hierarchies.get_or_insert_with(Vec::new).push(fields.0.join(" "));
+				fields.0 = &[];
+			}
+			"clear_flocks" => self.flocks = Some(vec![]),
+			"add_flock" => {
+				self.flocks.get_or_insert_with(Vec::new).push(fields.0.join(" "));
+				fields.0 = &[];
+			}
+			"gravity" => self.gravity = Some(optional(&mut fields)?),
+			"wind" => self.wind = Some(optional(&mut fields)?),
+			"vector_field" if fields.0 == ["none"] => {
+				self.vector_field = Some(None);
+				fields.0 = &[];
+			}
+			// the same as in a scene, on one with nothing else
+			"vector_field" | "vector" => {
+				let mut scene = Scene { vector_field: self.vector_field.clone().flatten(), ..Default::default() };
+				scene.parse_line(words)?;
+				self.vector_field = Some(scene.vector_field);
+				fields.0 = &[];
+			}
+			"n_body" => self.n_body = Some(match fields.0 {
+				["none"] => {
+					fields.0 = &[];
+					None
+				}
+				_ => Some(fields.n_body()?),
+			}),
+			"friction" => self.friction = Some(optional_number(&mut fields)?),
+			"damping" => self.damping = Some(optional_number(&mut fields)?),
+			"container" => self.container = Some(match fields.0 {
+				["default"] => {
+					fields.0 = &[];
+					None
+				}
+				_ => Some(fields.constraint()?),
+			}),
+			other => return Err(format!("don't know what {:?} is", other)),
+		}
+
+		match fields.0 {
+			[] => Ok(()),
+			extra => Err(format!("too much on this line: {:?}", extra)),
+		}
+	}
+}
+
+// --diff-scenes <old> <new> [patch], to stdout without a patch path
+pub fn diff_files(old: &str, new: &str, patch: Option<&str>) -> io::Result<()> {
+	let diff = SceneDiff::between(&Scene::load(old)?, &Scene::load(new)?);
+	if diff.is_empty() {
+		eprintln!("{} and {} are the same scene", old, new);
+	}
+	match patch {
+		Some(path) => std::fs::write(path, diff.to_text()),
+		None => {
+			print!("{}", diff.to_text());
+			Ok(())
+		}
+	}
+}
+
+// --patch-scene <scene> <patch> <out>
+pub fn patch_file(scene: &str, patch: &str, out: &str) -> io::Result<()> {
+	let diff = SceneDiff::parse(&std::fs::read_to_string(patch)?)?;
+	diff.apply(&Scene::load(scene)?)?.save(out)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const OLD: &str = "\
+gravity 0 1
+container box 0 0 960 600
+object 100 100 10
+object 130 100 10
+object 160 100 10
+object 190 100 10
+pinned 220 100 10
+link 0 1
+link 1 2
+link 2 3
+link 3 4
+segment 0 500 960 500 4
+kill_zone below 650
+soft_body 0-3
+";
+
+	// an object gone (and its links with it), others moved or changed, one added, a group, a
+	// hierarchy and different gravity
+	const NEW: &str = "\
+gravity 0 0.5
+wind 0.1 0
+container circle 480 300 300
+object 100 100 10
+object 130 120 12
+bumper 190 100 10 8
+pinned 220 100 10
+object 250 100 6 bounce 0.5
+group ropes off
+link 0 1
+link 2 3
+link 3 4 30 break 0.5 group 0
+spring 1 4 0.5 0.1
+segment 0 500 960 500 4
+segment 0 0 0 600 2
+hierarchy 0-3 stride 2
+";
+
+	#[test]
+	fn patching_with_the_diff_gives_the_new_scene() {
+		let (old, new) = (Scene::parse(OLD).unwrap(), Scene::parse(NEW).unwrap());
+		let diff = SceneDiff::between(&old, &new);
+		assert!(!diff.is_empty());
+
+		// through text and back, the way --diff-scenes and --patch-scene pass it along
+		let diff = SceneDiff::parse(&diff.to_text()).unwrap();
+		assert_eq!(diff.apply(&old).unwrap().to_text(), new.to_text());
+		assert!(SceneDiff::between(&new, &new).is_empty());
+	}
+}
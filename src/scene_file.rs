@@ -0,0 +1,201 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::pool::Pool;
+use crate::rand::Rng;
+use crate::{ChainLink, Constraint, Flock, Hierarchy, Hinge, KillZone, LinkGroup, LongRangeAttachment, Master, NBody, SoftBody, StaticSegment, VectorField, VerletObject};
+
+// everything in a master that makes up the scene itself, without what it works out while
+// it's running (the broadphase, contacts, events, stats) or what only lives in memory (the
+// pair hook, forces, drags). objects and links keep their ids, holes and all. anything left
+// out of a file comes out the same as in a new master, so a hand written one only needs what
+// it uses
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scene {
+	pub objects: Pool<VerletObject>,
+	pub chain_links: Pool<ChainLink>,
+	pub broken_links: Vec<ChainLink>,
+	pub groups: Vec<LinkGroup>,
+	pub attachments: Vec<LongRangeAttachment>,
+	pub hinges: Vec<Hinge>,
+	pub soft_bodies: Vec<SoftBody>,
+	pub hierarchies: Vec<Hierarchy>,
+	pub flocks: Vec<Flock>,
+	pub constraint: Constraint,
+	pub segments: Vec<StaticSegment>,
+	pub kill_zones: Vec<KillZone>,
+	pub gravity: Vec2,
+	pub wind: Vec2,
+	pub n_body: Option<NBody>,
+	pub vector_field: Option<VectorField>,
+	pub substeps: usize,
+	pub damping: f32,
+	pub friction: f32,
+	pub link_stiffness: f32,
+	pub impact_speed: f32,
+	pub fixed_point: bool,
+	pub rng: Option<Rng>,
+}
+
+impl Default for Scene {
+	fn default() -> Self {
+		Scene::from_master(&Master::new(vec![], vec![]))
+	}
+}
+
+impl Scene {
+	pub fn from_master(master: &Master) -> Self {
+		Self {
+			objects: master.objects.clone(),
+			chain_links: master.chain_links.clone(),
+			broken_links: master.broken_links.clone(),
+			groups: master.groups.clone(),
+			attachments: master.attachments.clone(),
+			hinges: master.hinges.clone(),
+			soft_bodies: master.soft_bodies.clone(),
+			hierarchies: master.hierarchies.clone(),
+			flocks: master.flocks.clone(),
+			constraint: master.constraint.clone(),
+			segments: master.segments.clone(),
+			kill_zones: master.kill_zones.clone(),
+			gravity: master.gravity,
+			wind: master.wind,
+			n_body: master.n_body,
+			vector_field: master.vector_field.clone(),
+			substeps: master.substeps,
+			damping: master.damping,
+			friction: master.friction,
+			link_stiffness: master.link_stiffness,
+			impact_speed: master.impact_speed,
+			fixed_point: master.fixed_point,
+			rng: master.rng.clone(),
+		}
+	}
+
+	// whether everything that points at an object points at one that's there, which a hand
+	// written file doesn't have to. the solver takes that for granted and would panic halfway
+	// through a step instead
+	pub fn check(&self) -> Result<(), String> {
+		let object = |what: &str, id: usize| match self.objects.contains_index(id) {
+			true => Ok(()),
+			false => Err(format!("{} points at object {}, which isn't there", what, id)),
+		};
+		for (index, chain_link) in self.chain_links.indexed() {
+			object(&format!("link {}", index), chain_link.a)?;
+			object(&format!("link {}", index), chain_link.b)?;
+		}
+		for (index, attachment) in self.attachments.iter().enumerate() {
+			object(&format!("attachment {}", index), attachment.object)?;
+			object(&format!("attachment {}", index), attachment.anchor)?;
+		}
+		for (index, hinge) in self.hinges.iter().enumerate() {
+			for &id in [hinge.pivot].iter().chain(hinge.a.iter()).chain(hinge.b.iter()) {
+				object(&format!("hinge {}", index), id)?;
+			}
+		}
+		for (index, soft_body) in self.soft_bodies.iter().enumerate() {
+			for &id in soft_body.ids.iter() {
+				object(&format!("soft body {}", index), id)?;
+			}
+		}
+		for (index, hierarchy) in self.hierarchies.iter().enumerate() {
+			for &id in hierarchy.ids.iter().chain(hierarchy.clusters.iter().flatten()) {
+				object(&format!("hierarchy {}", index), id)?;
+			}
+			if let Some(link) = hierarchy.links.iter().find(|link| link.a.max(link.b) >= hierarchy.clusters.len()) {
+				return Err(format!("hierarchy {} links cluster {} and {}, but it only has {}", index, link.a, link.b, hierarchy.clusters.len()));
+			}
+		}
+		Ok(())
+	}
+
+	pub fn into_master(self) -> Master {
+		let mut master = Master::new(vec![], vec![]);
+		master.objects = self.objects;
+		master.chain_links = self.chain_links;
+		master.broken_links = self.broken_links;
+		master.groups = self.groups;
+		master.attachments = self.attachments;
+		master.hinges = self.hinges;
+		master.soft_bodies = self.soft_bodies;
+		master.hierarchies = self.hierarchies;
+		master.flocks = self.flocks;
+		master.constraint = self.constraint;
+		master.segments = self.segments;
+		master.kill_zones = self.kill_zones;
+		master.gravity = self.gravity;
+		master.wind = self.wind;
+		master.n_body = self.n_body;
+		master.vector_field = self.vector_field;
+		master.substeps = self.substeps;
+		master.damping = self.damping;
+		master.friction = self.friction;
+		master.link_stiffness = self.link_stiffness;
+		master.impact_speed = self.impact_speed;
+		master.fixed_point = self.fixed_point;
+		master.rng = self.rng;
+		master
+	}
+}
+
+impl Master {
+	// as json, see Scene for what's kept
+	pub fn save_scene(&self, path: impl AsRef<Path>) -> io::Result<()> {
+		fs::write(path, serde_json::to_string_pretty(&Scene::from_master(self))?)
+	}
+
+	pub fn load_scene(path: impl AsRef<Path>) -> io::Result<Master> {
+		let scene: Scene = serde_json::from_str(&fs::read_to_string(path)?)?;
+		scene.check().map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+		Ok(scene.into_master())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use glam::vec2;
+
+	fn linked_pair() -> Master {
+		let objects = vec![VerletObject::new(vec2(100.0, 100.0), 10.0), VerletObject::new(vec2(130.0, 100.0), 10.0)];
+		let chain_links = vec![ChainLink { a: 0, b: 1, target_distance: 30.0, priority: 0, spring: None, max_strain: None, group: None }];
+		Master::new(objects, chain_links)
+	}
+
+	#[test]
+	fn saved_scenes_load_back() {
+		let path = std::env::temp_dir().join("verlet_scene_file_round_trip.json");
+		linked_pair().save_scene(&path).unwrap();
+		let master = Master::load_scene(&path).unwrap();
+		assert_eq!(master.objects.len(), 2);
+		assert_eq!(master.chain_links.len(), 1);
+	}
+
+	#[test]
+	fn links_to_missing_objects_are_rejected() {
+		let mut master = linked_pair();
+		master.remove_object(1);
+		let mut scene = Scene::from_master(&master);
+		scene.chain_links.push(ChainLink { a: 0, b: 1, target_distance: 30.0, priority: 0, spring: None, max_strain: None, group: None });
+		assert!(scene.check().is_err());
+
+		let path = std::env::temp_dir().join("verlet_scene_file_hole.json");
+		fs::write(&path, serde_json::to_string(&scene).unwrap()).unwrap();
+		assert_eq!(Master::load_scene(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+		let mut scene = Scene::from_master(&linked_pair());
+		scene.soft_bodies.push(SoftBody { ids: vec![0, 1, 7], target_area: 100.0, pressure: 0.5 });
+		assert!(scene.check().is_err());
+		let mut scene = Scene::from_master(&linked_pair());
+		scene.hinges.push(Hinge { pivot: 9, a: vec![], b: vec![0], rest_angle: 0.0, limits: None, motor: None });
+		assert!(scene.check().is_err());
+		let mut scene = Scene::from_master(&linked_pair());
+		scene.hierarchies.push(Hierarchy::new(vec![0, 1, 2], 2, 1));
+		assert!(scene.check().is_err());
+	}
+}
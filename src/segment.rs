@@ -0,0 +1,56 @@
+use glam::Vec2;
+
+use crate::constraint::Overlap;
+
+// a line that never moves, for floors, ramps and platforms. objects bounce off either side
+// and off the rounded ends, thickness is how wide the line is drawn and collided
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StaticSegment {
+	pub a: Vec2,
+	pub b: Vec2,
+	pub thickness: f32,
+}
+
+impl StaticSegment {
+	pub fn new(a: Vec2, b: Vec2, thickness: f32) -> Self {
+		Self { a, b, thickness }
+	}
+
+	// the point on the middle of the line nearest to the position
+	pub fn closest_point(&self, position: Vec2) -> Vec2 {
+		let along = self.b - self.a;
+		let length_squared = along.length_squared();
+		if length_squared == 0.0 {
+			return self.a;
+		}
+		let t = ((position - self.a).dot(along) / length_squared).clamp(0.0, 1.0);
+		self.a + along * t
+	}
+
+	// for a circle this big, None when it isn't touching. the normal points away from the line
+	pub fn overlap(&self, position: Vec2, radius: f32) -> Option<Overlap> {
+		let reach = radius + self.thickness * 0.5;
+		let closest = self.closest_point(position);
+		let offset = position - closest;
+		let distance = offset.length();
+		if distance >= reach {
+			return None;
+		}
+
+		// right on the line there's no telling which side it came from, so it goes out the top
+		// (or the left for a vertical one)
+		let normal = match distance {
+			distance if distance > 0.0 => offset / distance,
+			_ => {
+				let perp = (self.b - self.a).normalize_or_zero().perp();
+				if perp.y > 0.0 || (perp.y == 0.0 && perp.x > 0.0) { -perp } else { perp }
+			}
+		};
+		Some(Overlap {
+			position: closest + normal * reach,
+			normal,
+			depth: reach - distance,
+		})
+	}
+}
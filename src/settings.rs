@@ -0,0 +1,193 @@
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use verlet_integration::{WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::input::{Action, Binding, InputMap};
+use crate::grid::GRID_SPACING;
+
+// everything the demo remembers between runs. it's saved as soon as anything in it changes,
+// as one "name value" per line, and names it doesn't know (or values it can't read) are
+// skipped so an old or hand edited file never stops it from starting
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+	pub window_width: i32,
+	pub window_height: i32,
+	// only takes effect on the next start, the window can't change it once it's open
+	pub vsync: bool,
+	// frames per second to stay under, on top of vsync
+	pub fps_cap: Option<u32>,
+	// opened again next time if there's no --scene
+	pub last_scene: Option<PathBuf>,
+	pub debug_overlay: bool,
+	pub histograms: bool,
+	pub grid: bool,
+	pub grid_spacing: f32,
+	pub field_lines: bool,
+	pub ropes: bool,
+	pub soft_bodies: bool,
+	pub cloth: bool,
+	pub impact_effects: bool,
+	pub reduced_motion: bool,
+	pub high_contrast: bool,
+	// only what's been changed from the defaults gets written, as "bind <action> <key>"
+	pub input: InputMap,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			window_width: WINDOW_WIDTH as i32,
+			window_height: WINDOW_HEIGHT as i32,
+			vsync: true,
+			fps_cap: None,
+			last_scene: None,
+			debug_overlay: false,
+			histograms: false,
+			grid: false,
+			grid_spacing: GRID_SPACING,
+			field_lines: false,
+			ropes: false,
+			soft_bodies: false,
+			cloth: false,
+			impact_effects: false,
+			reduced_motion: false,
+			high_contrast: false,
+			input: InputMap::default(),
+		}
+	}
+}
+
+// where the os wants settings kept, the folder for the demo goes in here
+pub fn config_dir() -> Option<PathBuf> {
+	let home = env::var_os("HOME").map(PathBuf::from);
+
+	if cfg!(target_os = "windows") {
+		env::var_os("APPDATA").map(PathBuf::from)
+	} else if cfg!(target_os = "macos") {
+		home.map(|home| home.join("Library").join("Application Support"))
+	} else {
+		env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| home.map(|home| home.join(".config")))
+	}
+}
+
+pub fn settings_path() -> Option<PathBuf> {
+	config_dir().map(|dir| dir.join("verlet_integration").join("settings.txt"))
+}
+
+impl Settings {
+	// the defaults when there's no file yet (or nowhere to keep one)
+	pub fn load() -> Settings {
+		let Some(path) = settings_path() else {
+			return Settings::default();
+		};
+
+		match fs::read_to_string(&path) {
+			Ok(text) => Settings::parse(&text),
+			Err(error) if error.kind() == io::ErrorKind::NotFound => Settings::default(),
+			Err(error) => {
+				eprintln!("couldn't read the settings at {}: {}", path.display(), error);
+				Settings::default()
+			}
+		}
+	}
+
+	pub fn save(&self) -> io::Result<()> {
+		let Some(path) = settings_path() else {
+			return Ok(());
+		};
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(path, self.to_text())
+	}
+
+	pub fn parse(text: &str) -> Settings {
+		let mut settings = Settings::default();
+
+		for line in text.lines() {
+			let Some((name, value)) = line.trim().split_once(' ') else {
+				continue;
+			};
+			let value = value.trim();
+
+			let flag = |setting: &mut bool| {
+				if let Ok(value) = value.parse() {
+					*setting = value;
+				}
+			};
+			match name {
+				"window_width" => settings.window_width = value.parse().unwrap_or(settings.window_width),
+				"window_height" => settings.window_height = value.parse().unwrap_or(settings.window_height),
+				"vsync" => flag(&mut settings.vsync),
+				"fps_cap" => settings.fps_cap = value.parse().ok(),
+				"last_scene" => settings.last_scene = Some(PathBuf::from(value)),
+				"debug_overlay" => flag(&mut settings.debug_overlay),
+				"histograms" => flag(&mut settings.histograms),
+				"grid" => flag(&mut settings.grid),
+				"grid_spacing" => settings.grid_spacing = value.parse().unwrap_or(settings.grid_spacing),
+				"field_lines" => flag(&mut settings.field_lines),
+				"ropes" => flag(&mut settings.ropes),
+				"soft_bodies" => flag(&mut settings.soft_bodies),
+				"cloth" => flag(&mut settings.cloth),
+				"impact_effects" => flag(&mut settings.impact_effects),
+				"reduced_motion" => flag(&mut settings.reduced_motion),
+				"high_contrast" => flag(&mut settings.high_contrast),
+				"bind" => {
+					let binding = value.split_once(' ')
+						.and_then(|(action, binding)| Some((Action::from_name(action)?, Binding::from_name(binding.trim())?)));
+					if let Some((action, binding)) = binding {
+						settings.input.bindings.insert(action, binding);
+					}
+				}
+				_ => {}
+			}
+		}
+		for (action, binding) in settings.input.drop_duplicates() {
+			eprintln!("couldn't put {} on {}, something else already is", action.name(), binding.name());
+		}
+
+		settings
+	}
+
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+
+		let _ = writeln!(text, "window_width {}", self.window_width);
+		let _ = writeln!(text, "window_height {}", self.window_height);
+		let _ = writeln!(text, "vsync {}", self.vsync);
+		match self.fps_cap {
+			Some(fps) => {
+				let _ = writeln!(text, "fps_cap {}", fps);
+			}
+			None => {
+				let _ = writeln!(text, "fps_cap none");
+			}
+		}
+		if let Some(path) = &self.last_scene {
+			let _ = writeln!(text, "last_scene {}", path.display());
+		}
+		let _ = writeln!(text, "debug_overlay {}", self.debug_overlay);
+		let _ = writeln!(text, "histograms {}", self.histograms);
+		let _ = writeln!(text, "grid {}", self.grid);
+		let _ = writeln!(text, "grid_spacing {}", self.grid_spacing);
+		let _ = writeln!(text, "field_lines {}", self.field_lines);
+		let _ = writeln!(text, "ropes {}", self.ropes);
+		let _ = writeln!(text, "soft_bodies {}", self.soft_bodies);
+		let _ = writeln!(text, "cloth {}", self.cloth);
+		let _ = writeln!(text, "impact_effects {}", self.impact_effects);
+		let _ = writeln!(text, "reduced_motion {}", self.reduced_motion);
+		let _ = writeln!(text, "high_contrast {}", self.high_contrast);
+		for action in Action::ALL {
+			let binding = self.input.binding(action);
+			if binding != action.default_binding() {
+				let _ = writeln!(text, "bind {} {}", action.name(), binding.name());
+			}
+		}
+
+		text
+	}
+}
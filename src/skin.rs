@@ -0,0 +1,393 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+
+use macroquad::models::{draw_mesh, Mesh, Vertex};
+use macroquad::prelude::*;
+
+use verlet_integration::{Bomb, Master, VerletObject};
+
+// what to draw instead of a plain circle
+#[derive(Clone)]
+pub enum Sprite {
+	// stretched over the circle, untinted
+	Texture(Texture2D),
+	// gets the object and the color it would have been drawn in
+	Draw(Rc<DrawObject>),
+}
+
+pub type DrawObject = dyn Fn(&VerletObject, Color);
+
+impl fmt::Debug for Sprite {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Sprite::Texture(texture) => f.debug_tuple("Texture").field(texture).finish(),
+			Sprite::Draw(_) => f.write_str("Draw(..)"),
+		}
+	}
+}
+
+// sprites for single objects win over sprites for their tag
+#[derive(Clone, Debug, Default)]
+pub struct Sprites {
+	pub objects: HashMap<usize, Sprite>,
+	pub tags: HashMap<u32, Sprite>,
+}
+
+impl Sprites {
+	pub fn get(&self, id: usize, object: &VerletObject) -> Option<&Sprite> {
+		self.objects.get(&id).or_else(|| self.tags.get(&object.tag))
+	}
+}
+
+pub fn draw_object(object: &VerletObject, color: Color, sprite: Option<&Sprite>) {
+	match sprite {
+		// see-through, so what's inside still shows
+		None if object.sensor => {
+			draw_circle(object.position.x, object.position.y, object.radius, Color { a: 0.15, ..color });
+			draw_circle_lines(object.position.x, object.position.y, object.radius, 2.0, Color { a: 0.6, ..color });
+		}
+		None => draw_circle(object.position.x, object.position.y, object.radius, color),
+		Some(Sprite::Texture(texture)) => draw_texture_ex(
+			*texture,
+			object.position.x - object.radius,
+			object.position.y - object.radius,
+			WHITE,
+			DrawTextureParams {
+				dest_size: Some(Vec2::splat(object.radius * 2.0)),
+				..Default::default()
+			},
+		),
+		Some(Sprite::Draw(draw)) => draw(object, color),
+	}
+}
+
+// a black band round it, and a spark on top that blinks faster as the fuse burns down (red
+// and steady for a bomb that's only waiting for a hit)
+pub fn draw_bomb(object: &VerletObject, bomb: &Bomb) {
+	let (x, y, radius) = (object.position.x, object.position.y, object.radius);
+	draw_circle_lines(x, y, radius, (radius * 0.25).max(1.5), BLACK);
+
+	let lit = match bomb.fuse {
+		// on half the time, a blink a second with a long way to go and ten a second at the end
+		Some(fuse) => (fuse / (fuse * 0.25).clamp(3.0, 30.0)) as i32 % 2 == 0,
+		None => true,
+	};
+	if lit {
+		let color = if bomb.fuse.is_some() { YELLOW } else { RED };
+		draw_circle(x, y - radius, (radius * 0.3).max(2.0), color);
+	}
+}
+
+pub const HIGH_CONTRAST_OUTLINE: f32 = 3.0;
+// plain, stripes, a dot, a cross, a ring
+pub const PATTERN_COUNT: usize = 5;
+
+// which linked body each object belongs to, numbered from 1 in order of their lowest id.
+// objects without any links are all group 0
+pub fn link_groups(master: &Master) -> Vec<usize> {
+	let neighbours = link_neighbours(master);
+	let mut groups = vec![0; neighbours.len()];
+	let mut next = 1;
+
+	for start in 0..neighbours.len() {
+		if groups[start] != 0 || neighbours[start].is_empty() {
+			continue;
+		}
+
+		let mut stack = vec![start];
+		groups[start] = next;
+		while let Some(id) = stack.pop() {
+			for &other in neighbours[id].iter() {
+				if groups[other] == 0 {
+					groups[other] = next;
+					stack.push(other);
+				}
+			}
+		}
+		next += 1;
+	}
+
+	groups
+}
+
+// a thick light outline and a dark pattern on top of the object, so groups can be told
+// apart by more than their color
+pub fn draw_high_contrast(object: &VerletObject, group: usize) {
+	let (x, y, radius) = (object.position.x, object.position.y, object.radius);
+	let width = (radius * 0.2).clamp(1.0, HIGH_CONTRAST_OUTLINE);
+
+	match group % PATTERN_COUNT {
+		1 => {
+			for step in -2..=2 {
+				let offset = step as f32 * radius / 3.0;
+				let half = (radius * radius - offset * offset).max(0.0).sqrt();
+				draw_line(x - half, y + offset, x + half, y + offset, width, BLACK);
+			}
+		}
+		2 => draw_circle(x, y, radius * 0.35, BLACK),
+		3 => {
+			let arm = radius * 0.7;
+			draw_line(x - arm, y - arm, x + arm, y + arm, width * 1.5, BLACK);
+			draw_line(x + arm, y - arm, x - arm, y + arm, width * 1.5, BLACK);
+		}
+		4 => draw_circle_lines(x, y, radius * 0.55, width * 1.5, BLACK),
+		_ => {}
+	}
+
+	draw_circle_lines(x, y, radius, HIGH_CONTRAST_OUTLINE, WHITE);
+}
+
+// samples per link when drawing a rope as a spline
+pub const SPLINE_SUBDIVISIONS: usize = 8;
+
+// a run of linked objects, in order along the links
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rope {
+	pub ids: Vec<usize>,
+	// the last object links back to the first
+	pub closed: bool,
+}
+
+// the links that are switched on per object, by object id
+pub fn link_neighbours(master: &Master) -> Vec<Vec<usize>> {
+	let mut neighbours = vec![vec![]; master.objects.slot_count()];
+	for chain_link in master.chain_links.iter().filter(|chain_link| chain_link.is_enabled(&master.groups)) {
+		neighbours[chain_link.a].push(chain_link.b);
+		neighbours[chain_link.b].push(chain_link.a);
+	}
+	neighbours
+}
+
+// splits the link graph into ropes that only run through objects with exactly two links.
+// an object with one link ends a rope and one with three or more (a junction) ends all the
+// ropes meeting there, so every link ends up in exactly one rope
+pub fn find_ropes(master: &Master) -> Vec<Rope> {
+	let neighbours = link_neighbours(master);
+	let mut ropes = vec![];
+	// each link gets walked once, from whichever end gets to it first
+	let mut walked = HashSet::new();
+
+	let walk = |start: usize, next: usize, walked: &mut HashSet<(usize, usize)>| {
+		let mut ids = vec![start];
+		let (mut previous, mut current) = (start, next);
+		walked.insert((start.min(next), start.max(next)));
+
+		loop {
+			ids.push(current);
+			if current == start {
+				ids.pop();
+				return Rope { ids, closed: true };
+			}
+			if neighbours[current].len() != 2 {
+				return Rope { ids, closed: false };
+			}
+
+			let following = if neighbours[current][0] == previous { neighbours[current][1] } else { neighbours[current][0] };
+			walked.insert((current.min(following), current.max(following)));
+			(previous, current) = (current, following);
+		}
+	};
+
+	for (start, links) in neighbours.iter().enumerate() {
+		if links.is_empty() || links.len() == 2 {
+			continue;
+		}
+		for &next in links.iter() {
+			if !walked.contains(&(start.min(next), start.max(next))) {
+				ropes.push(walk(start, next, &mut walked));
+			}
+		}
+	}
+
+	// whatever's left is rings where everything has two links
+	for (start, links) in neighbours.iter().enumerate() {
+		if links.len() == 2 {
+			let next = links[0];
+			if !walked.contains(&(start.min(next), start.max(next))) {
+				ropes.push(walk(start, next, &mut walked));
+			}
+		}
+	}
+
+	ropes
+}
+
+// a point on the catmull-rom curve between b and c, t from 0 to 1
+fn catmull_rom(a: Vec2, b: Vec2, c: Vec2, d: Vec2, t: f32) -> Vec2 {
+	let t2 = t * t;
+	let t3 = t2 * t;
+	0.5 * (2.0 * b + (c - a) * t + (2.0 * a - 5.0 * b + 4.0 * c - d) * t2 + (3.0 * b - a - 3.0 * c + d) * t3)
+}
+
+// the rope as a smooth line through every object's position
+// (by id, see Renderer::draw())
+pub fn rope_points(positions: &[Vec2], rope: &Rope) -> Vec<Vec2> {
+	let positions: Vec<Vec2> = rope.ids.iter().map(|&id| positions[id]).collect();
+	let count = positions.len();
+	let point = |i: isize| {
+		if rope.closed {
+			positions[i.rem_euclid(count as isize) as usize]
+		} else {
+			positions[i.clamp(0, count as isize - 1) as usize]
+		}
+	};
+
+	let segments = if rope.closed { count } else { count - 1 };
+	let mut result = Vec::with_capacity(segments * SPLINE_SUBDIVISIONS + 1);
+	for i in 0..segments as isize {
+		for step in 0..SPLINE_SUBDIVISIONS {
+			let t = step as f32 / SPLINE_SUBDIVISIONS as f32;
+			result.push(catmull_rom(point(i - 1), point(i), point(i + 1), point(i + 2), t));
+		}
+	}
+	result.push(point(segments as isize));
+
+	result
+}
+
+// draws the rope as one thick line as wide as its objects, round at every joint so the
+// pieces blend together. with a texture it gets tiled along the length instead
+pub fn draw_rope(master: &Master, positions: &[Vec2], rope: &Rope, colors: &[Color], texture: Option<Texture2D>) {
+	let points = rope_points(positions, rope);
+	let thickness = rope.ids.iter().map(|&id| master.objects[id].radius).sum::<f32>() / rope.ids.len() as f32 * 2.0;
+	// which object each sample is closest to along the rope, for its color
+	let color_at = |sample: usize| colors[rope.ids[(sample + SPLINE_SUBDIVISIONS / 2) / SPLINE_SUBDIVISIONS % rope.ids.len()]];
+
+	let mut along = 0.0;
+	for (i, pair) in points.windows(2).enumerate() {
+		let (a, b) = (pair[0], pair[1]);
+		let length = a.distance(b);
+
+		match texture {
+			Some(texture) => {
+				// one copy of the texture every this many pixels, keeping its aspect ratio
+				let tile_length = thickness * texture.width() / texture.height();
+				let middle = (a + b) * 0.5;
+				draw_texture_ex(
+					texture,
+					middle.x - length * 0.5,
+					middle.y - thickness * 0.5,
+					WHITE,
+					DrawTextureParams {
+						dest_size: Some(vec2(length, thickness)),
+						source: Some(Rect::new(
+							along % tile_length / tile_length * texture.width(),
+							0.0,
+							length / tile_length * texture.width(),
+							texture.height(),
+						)),
+						rotation: (b - a).y.atan2((b - a).x),
+						..Default::default()
+					},
+				);
+			}
+			None => {
+				let color = color_at(i);
+				draw_line(a.x, a.y, b.x, b.y, thickness, color);
+				draw_circle(b.x, b.y, thickness * 0.5, color);
+			}
+		}
+
+		along += length;
+	}
+
+	if texture.is_none() && !rope.closed {
+		draw_circle(points[0].x, points[0].y, thickness * 0.5, color_at(0));
+	}
+}
+
+pub const SOFT_BODY_OUTLINE: f32 = 3.0;
+
+// a closed rope filled in as a smooth blob with a darker outline. it's filled as a fan from
+// the middle, which is right for anything roughly round (balloons, jelly) and only goes
+// wrong for rings squashed badly enough to fold in on themselves
+pub fn draw_soft_body(positions: &[Vec2], rope: &Rope, colors: &[Color]) {
+	let points = rope_points(positions, rope);
+	let center = points.iter().fold(Vec2::ZERO, |sum, &point| sum + point) / points.len() as f32;
+
+	let mut color = Color::new(0.0, 0.0, 0.0, 0.0);
+	for &id in rope.ids.iter() {
+		color.r += colors[id].r / rope.ids.len() as f32;
+		color.g += colors[id].g / rope.ids.len() as f32;
+		color.b += colors[id].b / rope.ids.len() as f32;
+		color.a += colors[id].a / rope.ids.len() as f32;
+	}
+	let outline = Color::new(color.r * 0.5, color.g * 0.5, color.b * 0.5, color.a);
+
+	for pair in points.windows(2) {
+		draw_triangle(center, pair[0], pair[1], color);
+	}
+	for pair in points.windows(2) {
+		draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, SOFT_BODY_OUTLINE, outline);
+		draw_circle(pair[1].x, pair[1].y, SOFT_BODY_OUTLINE * 0.5, outline);
+	}
+}
+
+// every triangle in the link graph, plus every quad without a diagonal split into two. that's
+// what a cloth grid (with or without shear links) or a glued blob turns into
+pub fn find_faces(master: &Master) -> Vec<[usize; 3]> {
+	let neighbours = link_neighbours(master);
+	let linked = |a: usize, b: usize| neighbours[a].contains(&b);
+	let mut faces = vec![];
+
+	// each face is found from its lowest id, going around it one way only
+	for (a, links) in neighbours.iter().enumerate() {
+		for &b in links.iter().filter(|&&b| b > a) {
+			for &c in neighbours[b].iter().filter(|&&c| c > a && c != b) {
+				if c > b && linked(a, c) {
+					faces.push([a, b, c]);
+				}
+
+				for &d in neighbours[c].iter().filter(|&&d| d > b && d != c) {
+					if linked(a, d) && !linked(a, c) && !linked(b, d) {
+						faces.push([a, b, c]);
+						faces.push([a, c, d]);
+					}
+				}
+			}
+		}
+	}
+
+	faces
+}
+
+// where each object sits on the texture, from how the faces are laid out right now. taking
+// this once and keeping it makes the texture stretch with the cloth instead of sliding over it
+pub fn face_uvs(master: &Master, faces: &[[usize; 3]]) -> HashMap<usize, Vec2> {
+	let mut min = Vec2::splat(f32::INFINITY);
+	let mut max = Vec2::splat(f32::NEG_INFINITY);
+	for &id in faces.iter().flatten() {
+		min = min.min(master.objects[id].position);
+		max = max.max(master.objects[id].position);
+	}
+
+	let size = (max - min).max(Vec2::ONE);
+	faces.iter().flatten().map(|&id| (id, (master.objects[id].position - min) / size)).collect()
+}
+
+// the faces filled in, blending the colors of the objects at the corners. objects that came
+// along after the uvs were taken fall back to where they are on screen
+pub fn draw_faces(positions: &[Vec2], faces: &[[usize; 3]], colors: &[Color], texture: Option<Texture2D>, uvs: &HashMap<usize, Vec2>) {
+	let screen = vec2(screen_width(), screen_height());
+
+	// indices are u16, so big meshes go in a few batches
+	for batch in faces.chunks(u16::MAX as usize / 3) {
+		let mut mesh = Mesh {
+			vertices: Vec::with_capacity(batch.len() * 3),
+			indices: Vec::with_capacity(batch.len() * 3),
+			texture,
+		};
+
+		for &id in batch.iter().flatten() {
+			mesh.indices.push(mesh.vertices.len() as u16);
+			mesh.vertices.push(Vertex {
+				position: positions[id].extend(0.0),
+				uv: uvs.get(&id).copied().unwrap_or(positions[id] / screen),
+				color: if texture.is_some() { WHITE } else { colors[id] },
+			});
+		}
+
+		draw_mesh(&mesh);
+	}
+}
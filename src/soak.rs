@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use macroquad::prelude::*;
+use macroquad::rand::srand;
+
+use verlet_integration::rand::Rng;
+use verlet_integration::Master;
+
+use crate::chaos::Chaos;
+use crate::scene::{Scene, Timeline};
+use crate::sweep::EXPLODED_SPEED;
+use crate::{run_command, Spawner};
+
+pub const SOAK_HOURS: f32 = 1.0;
+// how much of the run leading up to an anomaly gets written out, and how far apart
+pub const SOAK_HISTORY_SECONDS: f32 = 10.0;
+pub const SOAK_SNAPSHOT_SECONDS: f32 = 0.5;
+// chaos explosions on their own get things going nearly as fast as the sweep's idea of
+// blowing up, so this is double that
+pub const SOAK_MAX_SPEED: f32 = EXPLODED_SPEED * 2.0;
+// far past anything the watchdog lets through on a bad frame
+pub const SOAK_MAX_PENETRATION: f32 = 40.0;
+// the container pushes things back in before they move, so anything under SOAK_MAX_SPEED
+// can end a frame this far outside it without anything being wrong
+pub const SOAK_ESCAPE_MARGIN: f32 = SOAK_MAX_SPEED;
+
+// runs the scene headless with chaos mode going for hours of simulated time, one frame at a
+// time so the same seed always plays out the same way. the moment something breaks it writes
+// the last few seconds to a folder and returns it
+pub fn run(mut master: Master, mut timeline: Timeline, seed: u64, args: &[String]) -> io::Result<Option<PathBuf>> {
+	let value = |flag: &str| args.iter().position(|arg| arg == flag).and_then(|index| args.get(index + 1));
+	let hours = value("--soak-hours").and_then(|hours| hours.parse().ok()).unwrap_or(SOAK_HOURS);
+	let directory = value("--soak-dir").map_or(PathBuf::from("soak"), PathBuf::from);
+
+	// spawning picks positions and sizes off the master's own generator, and colors for
+	// anything else off the shared one, so both need seeding
+	srand(seed);
+	master.rng = Some(Rng::new(seed));
+	let mut chaos = Chaos::with_args(seed, args);
+	let mut spawner = Spawner::default();
+
+	let frames = (hours * 3600.0 * 60.0) as usize;
+	let snapshot_every = (SOAK_SNAPSHOT_SECONDS * 60.0).max(1.0) as usize;
+	let history = (SOAK_HISTORY_SECONDS / SOAK_SNAPSHOT_SECONDS).ceil() as usize;
+	let mut snapshots: VecDeque<Master> = VecDeque::new();
+
+	println!("soaking for {} hours with seed {}", hours, seed);
+	for frame in 0..frames {
+		if frame % snapshot_every == 0 {
+			if snapshots.len() >= history {
+				snapshots.pop_front();
+			}
+			snapshots.push_back(master.clone());
+		}
+		// once a simulated minute
+		if frame % 3600 == 0 && frame > 0 {
+			println!("{} minutes, {} objects, {} chaos events", frame / 3600, master.objects.len(), chaos.events);
+		}
+
+		timeline.update(&mut master, &mut spawner);
+		let mut commands = vec![];
+		chaos.update(&master, &mut commands);
+		for command in commands {
+			run_command(&mut master, &mut spawner, command);
+		}
+		master.update(1.0);
+
+		if let Some(anomaly) = check(&master) {
+			let path = directory.join(format!("seed_{}", seed));
+			save(&path, seed, args, &anomaly, &master, &chaos, &snapshots)?;
+			println!("{} after {:.1} seconds, wrote {}", anomaly, master.stats.time / 60.0, path.display());
+			return Ok(Some(path));
+		}
+	}
+
+	println!("nothing went wrong in {} hours", hours);
+	Ok(None)
+}
+
+// what's wrong with the simulation, if anything
+pub fn check(master: &Master) -> Option<String> {
+	for (id, object) in master.objects.indexed() {
+		if !object.position.is_finite() || !object.last_position.is_finite() {
+			return Some(format!("object {} isn't anywhere", id));
+		}
+		let speed = (object.position - object.last_position).length();
+		if speed > SOAK_MAX_SPEED {
+			return Some(format!("object {} is going {:.1} a frame", id, speed));
+		}
+		let outside = master.constraint.overlap(object.position, 0.0).map_or(0.0, |overlap| overlap.depth);
+		if !object.kinematic && outside > SOAK_ESCAPE_MARGIN {
+			return Some(format!("object {} got out of the container", id));
+		}
+	}
+
+	if master.stats.max_penetration > SOAK_MAX_PENETRATION {
+		return Some(format!("objects are {:.1} inside each other", master.stats.max_penetration));
+	}
+
+	None
+}
+
+// the snapshots as scene files oldest first, the broken frame itself, and what it takes to
+// get back there
+fn save(path: &Path, seed: u64, args: &[String], anomaly: &str, master: &Master, chaos: &Chaos, snapshots: &VecDeque<Master>) -> io::Result<()> {
+	fs::create_dir_all(path)?;
+
+	for (i, snapshot) in snapshots.iter().enumerate() {
+		Scene::from_master(snapshot).save(path.join(format!("snapshot_{:03}.scene", i)))?;
+	}
+	Scene::from_master(master).save(path.join("anomaly.scene"))?;
+
+	let mut text = String::new();
+	let _ = writeln!(text, "seed {}", seed);
+	let _ = writeln!(text, "anomaly {}", anomaly);
+	let _ = writeln!(text, "time {} seconds", master.stats.time / 60.0);
+	let _ = writeln!(text, "chaos events {}, the last one {}", chaos.events, chaos.last.map_or("none", |event| event.name()));
+	let _ = writeln!(text, "snapshots every {} seconds, snapshot_{:03}.scene was the last one before it", SOAK_SNAPSHOT_SECONDS, snapshots.len().saturating_sub(1));
+	// with the seed written in, when it was picked at random
+	let mut rerun: Vec<String> = args.iter().skip(1).cloned().collect();
+	if let Some(index) = rerun.iter().position(|arg| arg == "--soak") {
+		if rerun.get(index + 1) != Some(&seed.to_string()) {
+			rerun.insert(index + 1, seed.to_string());
+		}
+	}
+	let _ = writeln!(text, "run again with: {}", rerun.join(" "));
+	fs::write(path.join("seed.txt"), text)
+}
@@ -0,0 +1,35 @@
+use alloc::vec::Vec;
+use glam::Vec2;
+
+use crate::object::VerletObject;
+use crate::pool::Pool;
+
+// a ring of objects, held together around the outside by links of its own, that keeps its
+// area like a balloon keeps its air. squash it and it pushes back out, fill it with more
+// than target_area and it pulls in
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftBody {
+	// in order around the ring
+	pub ids: Vec<usize>,
+	pub target_area: f32,
+	// how much of the difference it makes up each substep, 0 to 1
+	pub pressure: f32,
+}
+
+impl SoftBody {
+	// inside the ring, positive when it goes around clockwise on screen. None when any of it
+	// is gone
+	pub fn area(&self, objects: &Pool<VerletObject>) -> Option<f32> {
+		self.area_with(|id| objects.get(id).map(|object| object.position))
+	}
+
+	// the same, wherever the positions are kept
+	pub fn area_with(&self, position: impl Fn(usize) -> Option<Vec2>) -> Option<f32> {
+		let mut area = 0.0;
+		for (i, &id) in self.ids.iter().enumerate() {
+			area += position(id)?.perp_dot(position(self.ids[(i + 1) % self.ids.len()])?);
+		}
+		Some(area * 0.5)
+	}
+}
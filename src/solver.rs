@@ -0,0 +1,2138 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use glam::{vec2, Vec2};
+
+use crate::bomb::Detonation;
+use crate::broadphase::SpatialHash;
+use crate::color::Color;
+use crate::constraint::{Constraint, KillZone, Overlap};
+use crate::flock::Flock;
+use crate::force::Force;
+use crate::hierarchy::{Hierarchy, HierarchyScratch};
+use crate::fixed::{Fixed, FixedVec2};
+use crate::link::{ChainLink, Hinge, LinkGroup, LongRangeAttachment, Motor};
+use crate::magnet::Magnet;
+use crate::n_body::{NBody, QuadTree};
+use crate::object::{ObjectId, VerletObject};
+use crate::pool::Pool;
+use crate::rand::{gen_range, Rng};
+use crate::segment::StaticSegment;
+use crate::soft_body::SoftBody;
+use crate::vector_field::VectorField;
+#[cfg(feature = "parallel")]
+use crate::PARALLEL_OBJECTS;
+use crate::{
+	Palette, BOMB_DEBRIS_LIFETIME, BOMB_DEBRIS_SIZE, BOMB_DEBRIS_SPEED, BROADPHASE_BUCKETS, BROADPHASE_CELL_SIZE,
+	BROADPHASE_MIN_CELL_SIZE, DAMPING, DRAG_FORCE_HISTORY, FRICTION, DRAG_STIFFNESS, GRAVITY, IMPACT_SPEED,
+	LINK_STIFFNESS, PHYSICS_SUBSTEPS, WATCHDOG_HOLD_FRAMES, WATCHDOG_MAX_SUBSTEPS, WATCHDOG_PENETRATION,
+};
+
+#[cfg(feature = "parallel")]
+mod parallel;
+
+#[derive(Clone, Debug)]
+pub struct Drag {
+	pub id: usize,
+	pub target: Vec2,
+	// (object, weight) for the grabbed object and everything within the falloff
+	pub weights: Vec<(usize, f32)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+	// total simulated time
+	pub time: f32,
+	// (time, force) samples of the pull applied through the drag constraint,
+	// averaged over each update and capped at DRAG_FORCE_HISTORY entries
+	pub drag_force: VecDeque<(f32, Vec2)>,
+	// deepest overlap the collision pass found in the last substep, which is the closest
+	// thing to how much is still overlapping at the end of an update without another pass
+	pub max_penetration: f32,
+	// substeps the last update actually ran with, watchdog included
+	pub substeps: usize,
+	// how many times the watchdog has stepped in
+	pub escalations: usize,
+	// whether the watchdog stepped in on the last update
+	pub escalated: bool,
+	// how long the last update took
+	pub update_time: Duration,
+	// whether the last update had to run fewer substeps to stay in the frame budget
+	pub degraded: bool,
+	pub degraded_updates: usize,
+	// how long each part of an update has taken altogether, while Master::profile is on
+	pub phases: PhaseTimes,
+}
+
+// gravity is everything that accelerates things (gravity, wind, forces, magnets, n-body, the
+// vector field and flocks), links are hierarchies, chain links, attachments, hinges and soft
+// bodies, and positions is drag plus moving everything. the rest is what happens once after
+// the substeps, sensors, breaking links, bombs and despawning
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhaseTimes {
+	pub gravity: Duration,
+	pub constraint: Duration,
+	pub collisions: Duration,
+	pub links: Duration,
+	pub positions: Duration,
+	pub rest: Duration,
+}
+
+impl PhaseTimes {
+	pub fn total(&self) -> Duration {
+		self.gravity + self.constraint + self.collisions + self.links + self.positions + self.rest
+	}
+}
+
+// adds the time since the last lap to whichever phase just finished. without std there's no
+// clock and it never adds anything
+struct Stopwatch(#[cfg(feature = "std")] Option<Instant>);
+
+impl Stopwatch {
+	fn start(running: bool) -> Self {
+		#[cfg(feature = "std")]
+		return Self(running.then(Instant::now));
+		#[cfg(not(feature = "std"))]
+		{
+			let _ = running;
+			Self()
+		}
+	}
+
+	fn lap(&mut self, phase: &mut Duration) {
+		#[cfg(feature = "std")]
+		if let Some(last) = &mut self.0 {
+			let now = Instant::now();
+			*phase += now - *last;
+			*last = now;
+		}
+		#[cfg(not(feature = "std"))]
+		let _ = phase;
+	}
+}
+
+// watches the worst penetration after every update and, when something violent makes it
+// go over the threshold, doubles the substeps for a while until things calm down
+#[derive(Clone, Debug)]
+pub struct Watchdog {
+	pub enabled: bool,
+	pub threshold: f32,
+	pub max_substeps: usize,
+	// how long to keep the extra substeps after the last time it went over
+	pub hold_frames: usize,
+	pub multiplier: usize,
+	pub frames_left: usize,
+}
+
+impl Default for Watchdog {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			threshold: WATCHDOG_PENETRATION,
+			max_substeps: WATCHDOG_MAX_SUBSTEPS,
+			hold_frames: WATCHDOG_HOLD_FRAMES,
+			multiplier: 1,
+			frames_left: 0,
+		}
+	}
+}
+
+impl Watchdog {
+	pub fn substeps(&self, substeps: usize) -> usize {
+		(substeps * self.multiplier).min(self.max_substeps.max(substeps))
+	}
+
+	// returns true when it escalated
+	pub fn observe(&mut self, substeps: usize, max_penetration: f32) -> bool {
+		if !self.enabled {
+			self.multiplier = 1;
+			return false;
+		}
+
+		if max_penetration > self.threshold {
+			self.frames_left = self.hold_frames;
+			if self.substeps(substeps) < self.max_substeps {
+				self.multiplier *= 2;
+				return true;
+			}
+		} else if self.frames_left > 0 {
+			self.frames_left -= 1;
+		} else {
+			self.multiplier = 1;
+		}
+
+		false
+	}
+}
+
+// two objects (or an object and the container or a segment, when b is None) hitting each other hard.
+// speed is how fast they were closing in, per frame, and normal points from b towards a
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Impact {
+	pub a: usize,
+	pub b: Option<usize>,
+	pub point: Vec2,
+	pub normal: Vec2,
+	pub speed: f32,
+}
+
+// two objects (or an object and the container or a segment, when b is None) overlapping, before the solver
+// pushes them apart. normal points from b towards a like an impact's, and point is on b's edge
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+	pub a: usize,
+	pub b: Option<usize>,
+	pub point: Vec2,
+	pub normal: Vec2,
+	pub depth: f32,
+}
+
+// a bumper knocking an object away during the last update, for sounds, lights and scores.
+// normal points from the bumper towards the object and point is on the bumper's edge
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BumperEvent {
+	pub bumper: usize,
+	pub object: usize,
+	pub point: Vec2,
+	pub normal: Vec2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SensorEvent {
+	// (sensor, object) started overlapping during the last update
+	Enter(usize, usize),
+	// and stopped, or one of them was removed
+	Exit(usize, usize),
+}
+
+// what to do about two objects overlapping, see Master::pair_hook
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PairResponse {
+	// let them pass through each other this time
+	Skip,
+	// push them apart like any other pair
+	Default,
+	// push a by this much instead, and b the other way. it's split between them the same way
+	// the default push is, so kinematic objects still don't budge
+	Custom(Vec2),
+}
+
+// gets the contact and both objects (a then b) for every overlapping pair before it's solved.
+// each pair comes up twice per substep, once from each side
+pub type PairHook = fn(&Contact, &VerletObject, &VerletObject) -> PairResponse;
+
+// how much the scene can grow to before updating (or adding to it) has to allocate
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capacity {
+	pub objects: usize,
+	pub chain_links: usize,
+}
+
+// buffers update() reuses between calls instead of allocating them every time
+#[derive(Clone, Debug, Default)]
+struct Scratch {
+	positions: Vec<FixedVec2>,
+	last_positions: Vec<FixedVec2>,
+	radii: Vec<Fixed>,
+	accelerations: Vec<FixedVec2>,
+	candidates: Vec<usize>,
+	magnets: Vec<usize>,
+	quad_tree: QuadTree,
+	hierarchy: HierarchyScratch,
+	#[cfg(feature = "parallel")]
+	parallel: parallel::Scratch,
+}
+
+impl Scratch {
+	fn reserve(&mut self, objects: usize) {
+		self.positions.reserve(objects.saturating_sub(self.positions.len()));
+		self.last_positions.reserve(objects.saturating_sub(self.last_positions.len()));
+		self.radii.reserve(objects.saturating_sub(self.radii.len()));
+		self.accelerations.reserve(objects.saturating_sub(self.accelerations.len()));
+		self.candidates.reserve(objects.saturating_sub(self.candidates.len()));
+		#[cfg(feature = "parallel")]
+		self.parallel.reserve(objects);
+	}
+
+	fn capacity(&self) -> usize {
+		self.positions.capacity()
+			.min(self.last_positions.capacity())
+			.min(self.radii.capacity())
+			.min(self.accelerations.capacity())
+			.min(self.candidates.capacity())
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Master {
+	// pools rather than vecs so removing something never moves anything else, ids stay
+	// valid for as long as the object behind them is alive
+	pub objects: Pool<VerletObject>,
+	pub chain_links: Pool<ChainLink>,
+	// every link that's broken so far, oldest first, for putting back with repair_link().
+	// nothing takes them out of here but that, or the objects at either end going away
+	pub broken_links: Vec<ChainLink>,
+	// what ChainLink::group counts into, see set_group_enabled()
+	pub groups: Vec<LinkGroup>,
+	// solved right after the links, see attach_long_range()
+	pub attachments: Vec<LongRangeAttachment>,
+	// solved after the attachments, see add_hinge()
+	pub hinges: Vec<Hinge>,
+	// and then these, see add_soft_body()
+	pub soft_bodies: Vec<SoftBody>,
+	// solved before the chain links, see add_hierarchy()
+	pub hierarchies: Vec<Hierarchy>,
+	// groups of objects steering like birds, by tag
+	pub flocks: Vec<Flock>,
+	// what holds everything in
+	pub constraint: Constraint,
+	// floors, ramps and platforms, collided with after the objects
+	pub segments: Vec<StaticSegment>,
+	// anything that goes into one is despawned at the end of the update
+	pub kill_zones: Vec<KillZone>,
+	// kept up to date by solve_collisions(), by object id
+	pub broadphase: SpatialHash,
+	// how big the broadphase cells are, None fits them to the biggest object so a query only
+	// has to look at the cells right around it
+	pub cell_size: Option<f32>,
+	// every impact faster than impact_speed during the last update, the fastest one per pair
+	pub impacts: Vec<Impact>,
+	pub impact_speed: f32,
+	// every overlap in the last substep, see contacts()
+	contacts: Vec<Contact>,
+	// decides what to do about each pair instead of always pushing them apart, for one way
+	// platforms, things that phase through each other and the like. pairs it skips don't
+	// count as contacts or impacts
+	pub pair_hook: Option<PairHook>,
+	// everything that went into or came out of a sensor during the last update
+	pub sensor_events: Vec<SensorEvent>,
+	// every hit off a bumper during the last update, once per pair
+	pub bumper_events: Vec<BumperEvent>,
+	// every bomb that went off during the last update
+	pub detonations: Vec<Detonation>,
+	// (sensor, object) pairs overlapping at some point in the last update, sorted
+	sensor_overlaps: Vec<(usize, usize)>,
+	last_sensor_overlaps: Vec<(usize, usize)>,
+	pub drag: Option<Drag>,
+	// more drags on top of that one, like one per finger on a touch screen. they pull the same
+	// way but don't show up in the drag force stats
+	pub touches: Vec<Drag>,
+	pub stats: Stats,
+	pub palette: Palette,
+	pub gravity: Vec2,
+	// a constant push on top of gravity
+	pub wind: Vec2,
+	// anything else pushing on everything, after gravity and wind, see Force
+	pub forces: Vec<Box<dyn Force>>,
+	// everything pulling on everything else like planets when it's set, see NBody
+	pub n_body: Option<NBody>,
+	// pushes whatever's in each of its cells that cell's way, see VectorField
+	pub vector_field: Option<VectorField>,
+	pub substeps: usize,
+	// for every object that doesn't have its own, see VerletObject
+	pub damping: f32,
+	pub friction: f32,
+	// how much of the error each link corrects per substep, 1.0 is fully rigid
+	pub link_stiffness: f32,
+	pub watchdog: Watchdog,
+	// when set, each update runs as many substeps as it can fit in this much time (up to what
+	// it'd normally run, never less than one), judged by how long substeps took last update.
+	// there's no clock without std, so then it never trims anything
+	pub frame_budget: Option<Duration>,
+	// times every phase of every update into stats.phases, for --bench. it's a clock read
+	// between each one, so it's off unless something's going to look
+	pub profile: bool,
+	// runs the solver in 32.32 fixed point so the same scene gives bit-identical results on
+	// any machine, see update_fixed() for what it leaves out
+	pub fixed_point: bool,
+	// when set, everything the master picks at random (colors, and sizes for anyone spawning
+	// through random_range()) comes out of this instead of the global generator, so a seeded
+	// run picks the same every time whatever else is random
+	pub rng: Option<Rng>,
+	substep_time: Duration,
+	// for turning per substep velocities into per frame ones
+	sub_delta: f32,
+	scratch: Scratch,
+	// chain link ids sorted by priority, the pool itself can't be sorted without moving ids
+	solve_order: Vec<usize>,
+}
+
+impl Master {
+	pub fn new(objects: Vec<VerletObject>, chain_links: Vec<ChainLink>) -> Self {
+		Self {
+			objects: Pool::from(objects),
+			chain_links: Pool::from(chain_links),
+			broken_links: vec![],
+			groups: vec![],
+			attachments: vec![],
+			hinges: vec![],
+			soft_bodies: vec![],
+			hierarchies: vec![],
+			flocks: vec![],
+			constraint: Constraint::default(),
+			segments: vec![],
+			kill_zones: vec![],
+			broadphase: SpatialHash::new(BROADPHASE_CELL_SIZE, BROADPHASE_BUCKETS),
+			cell_size: None,
+			impacts: vec![],
+			impact_speed: IMPACT_SPEED,
+			contacts: vec![],
+			pair_hook: None,
+			sensor_events: vec![],
+			bumper_events: vec![],
+			detonations: vec![],
+			sensor_overlaps: vec![],
+			last_sensor_overlaps: vec![],
+			drag: None,
+			touches: vec![],
+			stats: Stats::default(),
+			palette: Palette::default(),
+			gravity: vec2(0.0, GRAVITY),
+			wind: Vec2::ZERO,
+			forces: vec![],
+			n_body: None,
+			vector_field: None,
+			substeps: PHYSICS_SUBSTEPS,
+			damping: DAMPING,
+			friction: FRICTION,
+			link_stiffness: LINK_STIFFNESS,
+			watchdog: Watchdog::default(),
+			frame_budget: None,
+			profile: false,
+			fixed_point: false,
+			rng: None,
+			substep_time: Duration::ZERO,
+			sub_delta: 1.0,
+			scratch: Scratch::default(),
+			solve_order: vec![],
+		}
+	}
+
+	// makes room for a scene of this size up front. once it fits, update() never touches the
+	// heap, as long as nothing adds a hierarchy after. (with the parallel feature rayon's own
+	// job queues still allocate now and then, everything of ours is reused)
+	pub fn reserve(&mut self, capacity: Capacity) {
+		self.objects.reserve(capacity.objects.saturating_sub(self.objects.len()));
+		self.chain_links.reserve(capacity.chain_links.saturating_sub(self.chain_links.len()));
+		// the scratch buffers are laid out like the pool, holes and all
+		self.scratch.reserve(capacity.objects.max(self.objects.slot_count()));
+		self.broadphase.reserve(capacity.objects.max(self.objects.slot_count()));
+		for hierarchy in self.hierarchies.iter() {
+			self.scratch.hierarchy.reserve(hierarchy);
+		}
+		// there's no real upper bound, but this many impacts in one update is already a lot
+		self.impacts.reserve(capacity.objects.saturating_sub(self.impacts.len()));
+		self.sensor_events.reserve(capacity.objects.saturating_sub(self.sensor_events.len()));
+		self.sensor_overlaps.reserve(capacity.objects.saturating_sub(self.sensor_overlaps.len()));
+		self.last_sensor_overlaps.reserve(capacity.objects.saturating_sub(self.last_sensor_overlaps.len()));
+		// packed circles touch six others, and each pair only counts once
+		self.contacts.reserve((capacity.objects * 3).saturating_sub(self.contacts.len()));
+		self.solve_order.reserve(capacity.chain_links.saturating_sub(self.solve_order.len()));
+		self.stats.drag_force.reserve(DRAG_FORCE_HISTORY.saturating_sub(self.stats.drag_force.len()));
+	}
+
+	pub fn capacity(&self) -> Capacity {
+		Capacity {
+			objects: self.objects.capacity().min(self.scratch.capacity()),
+			chain_links: self.chain_links.capacity().min(self.solve_order.capacity()),
+		}
+	}
+
+	pub fn update(&mut self, delta: f32) {
+		if self.fixed_point {
+			self.update_fixed(delta);
+			return;
+		}
+
+		let wanted_substeps = self.watchdog.substeps(self.substeps.max(1));
+		let substeps = match self.frame_budget {
+			Some(budget) if !self.substep_time.is_zero() => {
+				let affordable = (budget.as_secs_f64() / self.substep_time.as_secs_f64()) as usize;
+				affordable.clamp(1, wanted_substeps)
+			}
+			_ => wanted_substeps,
+		};
+		self.stats.degraded = substeps < wanted_substeps;
+		if self.stats.degraded {
+			self.stats.degraded_updates += 1;
+		}
+
+		#[cfg(feature = "std")]
+		let start = self.frame_budget.map(|_| Instant::now());
+		let sub_delta = delta / substeps as f32;
+		self.sub_delta = sub_delta;
+		self.impacts.clear();
+		self.bumper_events.clear();
+		self.detonations.clear();
+		core::mem::swap(&mut self.sensor_overlaps, &mut self.last_sensor_overlaps);
+		self.sensor_overlaps.clear();
+
+		for object in self.objects.iter_mut() {
+			object.pressure = 0.0;
+		}
+
+		let mut drag_force = Vec2::ZERO;
+		let mut stopwatch = Stopwatch::start(self.profile);
+		for _ in 0..substeps {
+			self.contacts.clear();
+			self.apply_gravity();
+			self.apply_forces(sub_delta);
+			self.apply_magnets();
+			self.apply_n_body();
+			self.apply_vector_field();
+			self.apply_flocks();
+			stopwatch.lap(&mut self.stats.phases.gravity);
+			self.apply_constraint();
+			stopwatch.lap(&mut self.stats.phases.constraint);
+			self.stats.max_penetration = self.solve_collisions();
+			stopwatch.lap(&mut self.stats.phases.collisions);
+			self.apply_hierarchies();
+			self.apply_chain_links();
+			self.apply_attachments();
+			self.apply_hinges(sub_delta);
+			self.apply_soft_bodies();
+			stopwatch.lap(&mut self.stats.phases.links);
+			drag_force += self.apply_drag(sub_delta);
+
+			self.update_positions(sub_delta);
+			stopwatch.lap(&mut self.stats.phases.positions);
+		}
+
+		#[cfg(feature = "std")]
+		if let Some(start) = start {
+			self.stats.update_time = start.elapsed();
+			self.substep_time = self.stats.update_time / substeps as u32;
+		}
+
+		self.stats.substeps = substeps;
+		self.stats.escalated = self.watchdog.observe(self.substeps.max(1), self.stats.max_penetration);
+		if self.stats.escalated {
+			self.stats.escalations += 1;
+		}
+
+		self.update_sensor_events();
+		self.break_links();
+		self.detonate_bombs(delta);
+		self.despawn_objects(delta);
+		stopwatch.lap(&mut self.stats.phases.rest);
+
+		self.stats.time += delta;
+		if self.drag.is_some() {
+			// dropping the oldest first keeps it from ever growing past the history length
+			if self.stats.drag_force.len() >= DRAG_FORCE_HISTORY {
+				self.stats.drag_force.pop_front();
+			}
+			self.stats.drag_force.push_back((self.stats.time, drag_force / substeps as f32));
+		}
+	}
+
+	// the same substep loop as update() with every position in fixed point. floats only show
+	// up when converting in and out, which is deterministic, so it's the math in between that
+	// has to be integer. it only does gravity, wind, the container, collisions and links,
+	// things like dragging, the watchdog, the frame budget, the pair hook, forces, n-body, the
+	// vector field and sensor events stay float only
+	pub fn update_fixed(&mut self, delta: f32) {
+		let substeps = self.substeps.max(1);
+		let sub_delta = Fixed::from_f32(delta) / Fixed::from_int(substeps as i32);
+		let gravity = FixedVec2::from_vec2(self.gravity + self.wind);
+		let keep_velocity = Fixed::ONE - Fixed::from_f32(self.damping);
+		let link_stiffness = Fixed::from_f32(self.link_stiffness);
+
+		self.sort_chain_links();
+
+		// a circle works out the same as in update(), anything with straight sides gets pushed
+		// back in one side at a time
+		let circle = match self.constraint {
+			Constraint::Circle { center, radius } => Some((FixedVec2::from_vec2(center), Fixed::from_f32(radius))),
+			_ => None,
+		};
+		let constraint = &self.constraint;
+		let edges = (0..constraint.edge_count()).map(move |i| {
+			let (point, normal) = constraint.edge(i);
+			(FixedVec2::from_vec2(point), FixedVec2::from_vec2(normal))
+		});
+
+		let segments = self.segments.iter().map(|segment|
+			(FixedVec2::from_vec2(segment.a), FixedVec2::from_vec2(segment.b), Fixed::from_f32(segment.thickness * 0.5))
+		);
+
+		let Scratch { positions, last_positions, radii, accelerations, .. } = &mut self.scratch;
+
+		// one entry per slot so the ids line up. the holes get moved around with everything
+		// else, which is harmless, but they're kept out of collisions and never copied back
+		let slot_count = self.objects.slot_count();
+		positions.clear();
+		positions.resize(slot_count, FixedVec2::ZERO);
+		last_positions.clear();
+		last_positions.resize(slot_count, FixedVec2::ZERO);
+		radii.clear();
+		radii.resize(slot_count, Fixed::ZERO);
+		accelerations.clear();
+		accelerations.resize(slot_count, FixedVec2::ZERO);
+		for (i, object) in self.objects.indexed() {
+			positions[i] = FixedVec2::from_vec2(object.position);
+			last_positions[i] = FixedVec2::from_vec2(object.last_position);
+			radii[i] = Fixed::from_f32(object.radius);
+			// anything pushed with accelerate() only counts for the first substep, like in update()
+			accelerations[i] = FixedVec2::from_vec2(object.acceleration);
+		}
+
+		for _ in 0..substeps {
+			for i in 0..positions.len() {
+				if self.objects.get(i).is_some_and(|object| object.kinematic) {
+					continue;
+				}
+
+				if let Some((center, constraint_radius)) = circle {
+					let to_object = positions[i] - center;
+					let distance = to_object.length();
+					if distance > constraint_radius - radii[i] {
+						positions[i] = center + to_object / distance * (constraint_radius - radii[i]);
+					}
+				}
+				for (point, normal) in edges.clone() {
+					let distance = (positions[i] - point).dot(normal);
+					if distance < radii[i] {
+						positions[i] += normal * (radii[i] - distance);
+					}
+				}
+			}
+
+			for i in 0..positions.len() {
+				for j in 0..positions.len() {
+					if i == j || !self.objects.contains_index(i) || !self.objects.contains_index(j) {
+						continue;
+					}
+					if self.objects[i].sensor || self.objects[j].sensor {
+						continue;
+					}
+
+					let collision_axis = positions[i] - positions[j];
+					let distance = collision_axis.length();
+					let object_distance = radii[i] + radii[j];
+					if distance < object_distance {
+						let n = match distance > Fixed::ZERO {
+							true => collision_axis / distance,
+							false if i < j => FixedVec2::new(Fixed::ONE, Fixed::ZERO),
+							false => FixedVec2::new(-Fixed::ONE, Fixed::ZERO),
+						};
+						let push = n * (object_distance - distance);
+						let (share_i, share_j) = correction_shares(&self.objects[i], &self.objects[j]);
+						positions[i] += push * Fixed::from_f32(share_i);
+						positions[j] -= push * Fixed::from_f32(share_j);
+					}
+				}
+			}
+
+			for i in 0..positions.len() {
+				// the holes too, they were never anywhere
+				if self.objects.get(i).is_none_or(|object| object.kinematic) {
+					continue;
+				}
+				for (a, b, half_thickness) in segments.clone() {
+					let along = b - a;
+					let length_squared = along.dot(along);
+					let t = match length_squared > Fixed::ZERO {
+						true => ((positions[i] - a).dot(along) / length_squared).max(Fixed::ZERO).min(Fixed::ONE),
+						false => Fixed::ZERO,
+					};
+					let offset = positions[i] - (a + along * t);
+					let distance = offset.length();
+					let reach = radii[i] + half_thickness;
+					// right on the line is left alone, there's no telling which way to push
+					if distance > Fixed::ZERO && distance < reach {
+						positions[i] += offset / distance * (reach - distance);
+					}
+				}
+			}
+
+			for &id in self.solve_order.iter() {
+				let chain_link = &self.chain_links[id];
+				let axis = positions[chain_link.a] - positions[chain_link.b];
+				let distance = axis.length();
+				let n = axis / distance;
+				let stretch = Fixed::from_f32(chain_link.target_distance) - distance;
+				let push = n * match chain_link.spring {
+					Some(spring) => {
+						let velocity = (positions[chain_link.a] - last_positions[chain_link.a]) - (positions[chain_link.b] - last_positions[chain_link.b]);
+						stretch * Fixed::from_f32(spring.stiffness) - velocity.dot(n) * Fixed::from_f32(spring.damping)
+					}
+					None => stretch * link_stiffness,
+				};
+				let (share_a, share_b) = correction_shares(&self.objects[chain_link.a], &self.objects[chain_link.b]);
+				positions[chain_link.a] += push * Fixed::from_f32(share_a);
+				positions[chain_link.b] -= push * Fixed::from_f32(share_b);
+			}
+
+			for i in 0..positions.len() {
+				if self.objects.get(i).is_some_and(|object| object.kinematic) {
+					continue;
+				}
+
+				let velocity = (positions[i] - last_positions[i]) * keep_velocity;
+				last_positions[i] = positions[i];
+				positions[i] += velocity + (gravity + accelerations[i]) * (sub_delta * sub_delta);
+				accelerations[i] = FixedVec2::ZERO;
+			}
+		}
+
+		for (i, object) in self.objects.indexed_mut() {
+			object.position = positions[i].to_vec2();
+			object.last_position = last_positions[i].to_vec2();
+			object.acceleration = Vec2::ZERO;
+		}
+
+		self.break_links();
+		self.despawn_objects(delta);
+		self.stats.substeps = substeps;
+		self.stats.time += delta;
+	}
+
+	// compares what's in the sensors now to what was in them the update before
+	fn update_sensor_events(&mut self) {
+		self.sensor_overlaps.sort_unstable();
+		self.sensor_overlaps.dedup();
+
+		self.sensor_events.clear();
+		for &(sensor, object) in self.sensor_overlaps.iter() {
+			if self.last_sensor_overlaps.binary_search(&(sensor, object)).is_err() {
+				self.sensor_events.push(SensorEvent::Enter(sensor, object));
+			}
+		}
+		for &(sensor, object) in self.last_sensor_overlaps.iter() {
+			if self.sensor_overlaps.binary_search(&(sensor, object)).is_err() {
+				self.sensor_events.push(SensorEvent::Exit(sensor, object));
+			}
+		}
+	}
+
+	// the solve order matters, so keep the links sorted by priority, then id so ties keep
+	// their order without a stable sort (which allocates). it only rebuilds the order when
+	// links came or went, a group was switched or something changed a priority. links in a
+	// group that's off are left out of it
+	fn sort_chain_links(&mut self) {
+		let (chain_links, groups) = (&self.chain_links, &self.groups);
+		let enabled = |id: usize| chain_links.get(id).is_some_and(|chain_link| chain_link.is_enabled(groups));
+		let up_to_date = self.solve_order.len() == chain_links.iter().filter(|chain_link| chain_link.is_enabled(groups)).count()
+			&& self.solve_order.iter().all(|&id| enabled(id))
+			&& self.solve_order.is_sorted_by_key(|&id| (chain_links[id].priority, id));
+
+		if !up_to_date {
+			self.solve_order.clear();
+			self.solve_order.extend(chain_links.ids().filter(|&id| enabled(id)));
+			self.solve_order.sort_unstable_by_key(|&id| (chain_links[id].priority, id));
+		}
+	}
+
+	pub fn apply_gravity(&mut self) {
+		for object in self.objects.iter_mut() {
+			object.accelerate(self.gravity + self.wind);
+		}
+	}
+
+	pub fn apply_forces(&mut self, delta: f32) {
+		for force in self.forces.iter() {
+			force.apply(&mut self.objects, delta);
+		}
+	}
+
+	// every pair of magnets close enough pushes or pulls both ways the same, see Magnet
+	pub fn apply_magnets(&mut self) {
+		let magnets = &mut self.scratch.magnets;
+		magnets.clear();
+		magnets.extend(self.objects.indexed()
+			.filter(|(_, object)| object.magnet.is_some() && !object.sensor)
+			.map(|(i, _)| i));
+
+		for (n, &i) in magnets.iter().enumerate() {
+			for &j in magnets[n + 1..].iter() {
+				let (a, b) = (&self.objects[i], &self.objects[j]);
+				let (Some(magnet_a), Some(magnet_b)) = (a.magnet, b.magnet) else {
+					continue;
+				};
+				let reach = magnet_a.reach.max(magnet_b.reach);
+				let field = Magnet { reach, ..magnet_a }.field(b.position - a.position, a.radius + b.radius);
+				// on b, and a gets the same the other way
+				let force = field * magnet_b.pole.sign() * magnet_b.strength;
+				let (mass_a, mass_b) = (a.mass, b.mass);
+
+				self.objects[i].accelerate(-force / mass_a);
+				self.objects[j].accelerate(force / mass_b);
+			}
+		}
+	}
+
+	pub fn apply_n_body(&mut self) {
+		if let Some(n_body) = self.n_body {
+			n_body.apply(&mut self.objects, &mut self.scratch.quad_tree);
+		}
+	}
+
+	pub fn apply_vector_field(&mut self) {
+		if let Some(field) = &self.vector_field {
+			field.apply(&mut self.objects);
+		}
+	}
+
+	// the push every magnet adds up to at point on a north pole of strength 1, for drawing
+	// field lines
+	pub fn magnetic_field(&self, point: Vec2) -> Vec2 {
+		self.objects.iter()
+			.filter(|object| !object.sensor)
+			.filter_map(|object| Some(object.magnet?.field(point - object.position, object.radius)))
+			.fold(Vec2::ZERO, |total, field| total + field)
+	}
+
+	// every member steers by the others in its flock the broadphase finds around it, see Flock
+	pub fn apply_flocks(&mut self) {
+		if self.flocks.is_empty() {
+			return;
+		}
+		let sub_delta = self.sub_delta.max(f32::EPSILON);
+
+		for i in 0..self.objects.slot_count() {
+			let Some(object) = self.objects.get(i) else {
+				continue;
+			};
+			let Some(flock) = self.flocks.iter().find(|flock| flock.tag == object.tag).copied() else {
+				continue;
+			};
+			if object.kinematic {
+				continue;
+			}
+			let (position, velocity) = (object.position, (object.position - object.last_position) / sub_delta);
+
+			let mut neighbours = 0;
+			let (mut away, mut velocities, mut positions) = (Vec2::ZERO, Vec2::ZERO, Vec2::ZERO);
+			self.broadphase.query(position, flock.vision, &mut self.scratch.candidates);
+			for &j in self.scratch.candidates.iter() {
+				let Some(other) = self.objects.get(j).filter(|other| j != i && other.tag == flock.tag) else {
+					continue;
+				};
+				let offset = position - other.position;
+				let distance = offset.length();
+				if distance >= flock.vision {
+					continue;
+				}
+				neighbours += 1;
+				if distance > 0.0 {
+					away += offset / distance * (1.0 - distance / flock.vision);
+				}
+				velocities += (other.position - other.last_position) / sub_delta;
+				positions += other.position;
+			}
+
+			// it flies, and holds its speed along wherever it's heading
+			let mut steer = -(self.gravity + self.wind);
+			let speed = velocity.length();
+			if speed > 0.0 {
+				steer += velocity / speed * (flock.speed - speed) * flock.alignment;
+			}
+			if neighbours > 0 {
+				let count = neighbours as f32;
+				steer += away * flock.separation;
+				steer += (velocities / count - velocity) * flock.alignment;
+				steer += (positions / count - position) * flock.cohesion;
+			}
+			self.objects[i].accelerate(steer);
+		}
+	}
+
+	pub fn apply_constraint(&mut self) {
+		for (id, object) in self.objects.indexed_mut() {
+			if object.kinematic {
+				continue;
+			}
+
+			if let Some(overlap) = self.constraint.overlap(object.position, object.radius) {
+				push_out(id, object, overlap, self.impact_speed, self.sub_delta, &mut self.impacts, &mut self.contacts);
+				rub(object, overlap.normal, overlap.depth, object.friction.unwrap_or(self.friction));
+			}
+		}
+	}
+
+	// everything that was overlapping in the last substep of the last update, from the
+	// container and then the collision pass. for sounds, decals, game rules and the like, it's
+	// all been pushed apart by the time anyone sees it
+	pub fn contacts(&self) -> impl Iterator<Item = &Contact> {
+		self.contacts.iter()
+	}
+
+	// returns the deepest overlap it had to fix
+	pub fn solve_collisions(&mut self) -> f32 {
+		for object in self.objects.iter_mut() {
+			object.contacts = 0;
+		}
+
+		let mut max_penetration = 0.0_f32;
+
+		// a cell as wide as the biggest object in it as of last substep (anything new or grown
+		// since has it looking further until the next). it only starts over when that's grown
+		// past the cells or shrunk to under half of them, so spawning things one at a time
+		// doesn't keep rebuilding it, and the lookups below put everything back in
+		let cell_size = self.cell_size.unwrap_or_else(|| (self.broadphase.largest_radius() * 2.0).max(BROADPHASE_MIN_CELL_SIZE));
+		let current = self.broadphase.cell_size;
+		let rebuild = match self.cell_size {
+			Some(_) => cell_size != current,
+			None => cell_size > current || cell_size < current * 0.5,
+		};
+		if rebuild {
+			self.broadphase.resize(cell_size);
+		}
+
+		let slot_count = self.objects.slot_count();
+		for id in 0..slot_count {
+			// most things are still in the same cell as last substep, and those are left alone
+			match self.objects.get(id) {
+				Some(object) if self.broadphase.is_current(id, object.position, object.radius) => {}
+				Some(object) => {
+					self.broadphase.update(id, object.position, object.radius);
+				}
+				None if self.broadphase.contains(id) => self.broadphase.remove(id),
+				None => {}
+			}
+		}
+
+		let settings = PairSettings {
+			hook: self.pair_hook,
+			sub_delta: self.sub_delta,
+			impact_speed: self.impact_speed,
+			friction: self.friction,
+		};
+		let mut events = CollisionEvents {
+			contacts: core::mem::take(&mut self.contacts),
+			impacts: core::mem::take(&mut self.impacts),
+			sensor_overlaps: core::mem::take(&mut self.sensor_overlaps),
+			bumper_events: core::mem::take(&mut self.bumper_events),
+		};
+
+		#[cfg(feature = "parallel")]
+		let parallel = self.objects.len() >= PARALLEL_OBJECTS;
+		#[cfg(not(feature = "parallel"))]
+		let parallel = false;
+
+		if parallel {
+			#[cfg(feature = "parallel")]
+			{
+				let largest_radius = self.broadphase.largest_radius();
+				max_penetration = parallel::solve_collisions(&mut self.objects, cell_size, largest_radius, settings, &mut events, &mut self.scratch.parallel);
+			}
+		} else {
+			for i in 0..slot_count {
+				let Some(object) = self.objects.get(i) else {
+					continue;
+				};
+				// anything fixed is found from the other side of every pair that matters, so a
+				// scene that's mostly walls costs about as much as what's moving in it
+				if is_fixed(object) {
+					continue;
+				}
+				self.broadphase.query(object.position, object.radius, &mut self.scratch.candidates);
+
+				for &j in self.scratch.candidates.iter() {
+					if let Some((a, b)) = self.objects.pair_mut(i, j) {
+						if let Some(depth) = collide(i, j, a, b, settings, &mut events) {
+							max_penetration = max_penetration.max(depth);
+						}
+					}
+				}
+			}
+		}
+
+		self.contacts = events.contacts;
+		self.impacts = events.impacts;
+		self.sensor_overlaps = events.sensor_overlaps;
+		self.bumper_events = events.bumper_events;
+
+		// few enough that checking every one against every object is quicker than hashing them
+		for (id, object) in self.objects.indexed_mut() {
+			if object.kinematic {
+				continue;
+			}
+			for segment in self.segments.iter() {
+				if let Some(overlap) = segment.overlap(object.position, object.radius) {
+					max_penetration = max_penetration.max(overlap.depth);
+					object.contacts += 1;
+					object.pressure += overlap.depth;
+					push_out(id, object, overlap, self.impact_speed, self.sub_delta, &mut self.impacts, &mut self.contacts);
+					rub(object, overlap.normal, overlap.depth, object.friction.unwrap_or(self.friction));
+				}
+			}
+		}
+
+		max_penetration
+	}
+
+	pub fn apply_chain_links(&mut self) {
+		self.sort_chain_links();
+
+		for &id in self.solve_order.iter() {
+			let chain_link = &self.chain_links[id];
+			let axis = self.objects[chain_link.a].position - self.objects[chain_link.b].position;
+			let distance = axis.length();
+			// right on top of each other there's no telling which way apart is, so they go
+			// opposite ways along x like in collide()
+			let n = match distance > 0.0 {
+				true => axis / distance,
+				false => Vec2::X,
+			};
+			let delta = match chain_link.spring {
+				Some(spring) => {
+					let a = &self.objects[chain_link.a];
+					let b = &self.objects[chain_link.b];
+					let stretching = ((a.position - a.last_position) - (b.position - b.last_position)).dot(n);
+					(chain_link.target_distance - distance) * spring.stiffness - stretching * spring.damping
+				}
+				None => (chain_link.target_distance - distance) * self.link_stiffness,
+			};
+			let (share_a, share_b) = correction_shares(&self.objects[chain_link.a], &self.objects[chain_link.b]);
+			self.objects[chain_link.a].position += share_a * delta * n;
+			self.objects[chain_link.b].position -= share_b * delta * n;
+		}
+	}
+
+	// inserts segments - 1 new objects between the two objects and links them all up,
+	// spacing the links evenly over the current distance. false if they're the same object or
+	// either one isn't there
+	pub fn link_chain(&mut self, from_id: usize, to_id: usize, segments: usize) -> bool {
+		if from_id == to_id {
+			return false;
+		}
+		let (Some(from), Some(to)) = (self.objects.get(from_id), self.objects.get(to_id)) else {
+			return false;
+		};
+		let segments = segments.max(1);
+		let target_distance = from.position.distance(to.position) / segments as f32;
+		let radius = from.radius.min(to.radius);
+		let (from, to) = (from.position, to.position);
+
+		let mut last_id = from_id;
+		for i in 1..segments {
+			let color = self.random_color();
+			let id = self.objects.push(
+				VerletObject::new(
+					from.lerp(to, i as f32 / segments as f32),
+					radius,
+				).with_color(color)
+			).index;
+
+			self.chain_links.push(
+				ChainLink {
+					a: last_id,
+					b: id,
+					target_distance,
+					priority: 0,
+					spring: None,
+					max_strain: None,
+					group: None,
+				}
+			);
+			last_id = id;
+		}
+
+		self.chain_links.push(
+			ChainLink {
+				a: last_id,
+				b: to_id,
+				target_distance,
+				priority: 0,
+				spring: None,
+				max_strain: None,
+				group: None,
+			}
+		);
+		true
+	}
+
+	// kicks everything within radius away from center, falling off linearly with distance
+	pub fn explode(&mut self, center: Vec2, strength: f32, radius: f32) {
+		for object in self.objects.iter_mut() {
+			let offset = object.position - center;
+			let distance = offset.length();
+			if distance < radius && distance > 0.0 && !object.kinematic {
+				// pushing last_position back changes the velocity without teleporting the object
+				object.last_position -= offset / distance * strength * (1.0 - distance / radius);
+			}
+		}
+	}
+
+	// adds an object and hands back an id for it that's safe to hold onto. links and
+	// everything else still go by its index (id.index), which stays the same until it's gone
+	pub fn spawn(&mut self, object: VerletObject) -> ObjectId {
+		self.objects.push(object)
+	}
+
+	// None if it's already gone, see remove_object()
+	pub fn despawn(&mut self, id: ObjectId) -> Option<VerletObject> {
+		if !self.objects.contains(id) {
+			return None;
+		}
+		self.remove_object(id.index)
+	}
+
+	pub fn object(&self, id: ObjectId) -> Option<&VerletObject> {
+		self.objects.lookup(id)
+	}
+
+	pub fn object_mut(&mut self, id: ObjectId) -> Option<&mut VerletObject> {
+		self.objects.lookup_mut(id)
+	}
+
+	// for whatever's at this index right now
+	pub fn object_id(&self, index: usize) -> Option<ObjectId> {
+		self.objects.handle(index)
+	}
+
+	// links two objects that are both still there, as far apart as they are now
+	pub fn link(&mut self, a: ObjectId, b: ObjectId) -> Option<usize> {
+		if a == b {
+			return None;
+		}
+		let distance = self.object(a)?.position.distance(self.object(b)?.position);
+		Some(self.chain_links.push(ChainLink {
+			a: a.index,
+			b: b.index,
+			target_distance: distance,
+			priority: 0,
+			spring: None,
+			max_strain: None,
+			group: None,
+		}).index)
+	}
+
+	// takes the object out along with every link holding onto it. everything else keeps its id,
+	// and the freed slot goes to whatever gets added next
+	pub fn remove_object(&mut self, id: usize) -> Option<VerletObject> {
+		let object = self.objects.remove_at(id)?;
+		self.broadphase.remove(id);
+
+		self.chain_links.retain(|chain_link| chain_link.a != id && chain_link.b != id);
+		self.broken_links.retain(|chain_link| chain_link.a != id && chain_link.b != id);
+		self.attachments.retain(|attachment| attachment.object != id && attachment.anchor != id);
+		// hinges and rings it was part of stay in their lists, since flippers and players count
+		// them by index, but let go of it so they never pick up whatever moves into its slot.
+		// one that's lost its pivot or a whole body goes limp, and a ring with a gap in it too
+		for hinge in self.hinges.iter_mut() {
+			let world = hinge.a.is_empty();
+			hinge.a.retain(|&other| other != id);
+			hinge.b.retain(|&other| other != id);
+			if hinge.pivot == id || hinge.a.is_empty() != world {
+				hinge.a.clear();
+				hinge.b.clear();
+			}
+		}
+		for soft_body in self.soft_bodies.iter_mut() {
+			if soft_body.ids.contains(&id) {
+				soft_body.ids.clear();
+			}
+		}
+		for hierarchy in self.hierarchies.iter_mut() {
+			hierarchy.forget(id);
+		}
+		if let Some(drag) = &mut self.drag {
+			if drag.id == id {
+				self.drag = None;
+			} else {
+				drag.weights.retain(|&(weight_id, _)| weight_id != id);
+			}
+		}
+		self.touches.retain(|touch| touch.id != id);
+		for touch in self.touches.iter_mut() {
+			touch.weights.retain(|&(weight_id, _)| weight_id != id);
+		}
+
+		Some(object)
+	}
+
+	pub fn set_palette(&mut self, palette: Palette) {
+		self.palette = palette;
+		for object in self.objects.iter_mut() {
+			object.color = match &mut self.rng {
+				Some(rng) => palette.color_with(rng),
+				None => palette.color(),
+			};
+		}
+	}
+
+	// out of the palette, see rng
+	pub fn random_color(&mut self) -> Color {
+		match &mut self.rng {
+			Some(rng) => self.palette.color_with(rng),
+			None => self.palette.color(),
+		}
+	}
+
+	pub fn random_range(&mut self, low: f32, high: f32) -> f32 {
+		match &mut self.rng {
+			Some(rng) => rng.range(low, high),
+			None => gen_range(low, high),
+		}
+	}
+
+	pub fn objects_in_radius(&self, position: Vec2, radius: f32) -> Vec<usize> {
+		let mut result = vec![];
+
+		for (i, object) in self.objects.indexed() {
+			if object.position.distance(position) <= radius + object.radius {
+				result.push(i);
+			}
+		}
+
+		result
+	}
+
+	// the object the point is inside of, the closest one when it's in more than one
+	pub fn object_at(&self, position: Vec2) -> Option<usize> {
+		self.objects_in_radius(position, 0.0).into_iter()
+			.min_by(|&a, &b| self.objects[a].position.distance(position).total_cmp(&self.objects[b].position.distance(position)))
+	}
+
+	// links every pair in the selection whose surfaces are within radius of each other,
+	// so a loose blob of objects turns into one structure
+	pub fn glue_objects(&mut self, ids: &[usize], radius: f32) {
+		for (i, &a) in ids.iter().enumerate() {
+			for &b in ids.iter().skip(i + 1) {
+				let distance = self.objects[a].position.distance(self.objects[b].position);
+				if distance - self.objects[a].radius - self.objects[b].radius > radius {
+					continue;
+				}
+
+				let already_linked = self.chain_links.iter().any(|chain_link|
+					(chain_link.a == a && chain_link.b == b)
+					|| (chain_link.a == b && chain_link.b == a)
+				);
+				if already_linked {
+					continue;
+				}
+
+				self.chain_links.push(
+					ChainLink {
+						a,
+						b,
+						target_distance: distance,
+						priority: 0,
+						spring: None,
+						max_strain: None,
+						group: None,
+					}
+				);
+			}
+		}
+	}
+
+	// falloff_hops is how many links away from the grabbed object still get pulled along,
+	// with the pull fading out linearly the further away they are (0 only moves the object)
+	pub fn start_drag(&mut self, id: usize, target: Vec2, falloff_hops: usize) {
+		self.drag = Some(self.grab(id, target, falloff_hops));
+	}
+
+	// a drag that isn't started yet, for touches
+	pub fn grab(&self, id: usize, target: Vec2, falloff_hops: usize) -> Drag {
+		Drag {
+			id,
+			target,
+			weights: self.soft_selection(id, falloff_hops),
+		}
+	}
+
+	pub fn stop_drag(&mut self) {
+		self.drag = None;
+	}
+
+	// walks the link graph outwards from id, returning each reached object with its falloff weight
+	pub fn soft_selection(&self, id: usize, falloff_hops: usize) -> Vec<(usize, f32)> {
+		let mut hops = vec![usize::MAX; self.objects.slot_count()];
+		hops[id] = 0;
+
+		let mut result = vec![(id, 1.0)];
+		let mut frontier = vec![id];
+
+		for hop in 1..=falloff_hops {
+			let mut next_frontier = vec![];
+
+			for chain_link in self.chain_links.iter().filter(|chain_link| chain_link.is_enabled(&self.groups)) {
+				for (from, to) in [(chain_link.a, chain_link.b), (chain_link.b, chain_link.a)] {
+					if hops[to] == usize::MAX && frontier.contains(&from) {
+						hops[to] = hop;
+						next_frontier.push(to);
+						result.push((to, 1.0 - hop as f32 / (falloff_hops + 1) as f32));
+					}
+				}
+			}
+
+			frontier = next_frontier;
+		}
+
+		result
+	}
+
+	// attaches every object linked (however indirectly) to one of the anchors straight to the
+	// closest one, measured along the links at their target distances. the anchors should be
+	// held in place, the attachments only ever move the other end
+	pub fn attach_long_range(&mut self, anchors: &[usize]) {
+		self.attachments.clear();
+
+		// shortest rope distance from each anchor to everything else, relaxing over the links
+		// until nothing gets shorter. the graphs here are small and sparse, so it's quick
+		let mut best = vec![(f32::INFINITY, usize::MAX); self.objects.slot_count()];
+		for &anchor in anchors {
+			best[anchor] = (0.0, anchor);
+		}
+
+		let mut changed = true;
+		while changed {
+			changed = false;
+
+			for chain_link in self.chain_links.iter().filter(|chain_link| chain_link.is_enabled(&self.groups)) {
+				for (from, to) in [(chain_link.a, chain_link.b), (chain_link.b, chain_link.a)] {
+					let distance = best[from].0 + chain_link.target_distance;
+					if distance < best[to].0 {
+						best[to] = (distance, best[from].1);
+						changed = true;
+					}
+				}
+			}
+		}
+
+		for (object, &(max_distance, anchor)) in best.iter().enumerate() {
+			if anchor != usize::MAX && anchor != object {
+				self.attachments.push(LongRangeAttachment {
+					object,
+					anchor,
+					max_distance,
+				});
+			}
+		}
+	}
+
+	pub fn apply_attachments(&mut self) {
+		for attachment in self.attachments.iter() {
+			if self.objects[attachment.object].kinematic {
+				continue;
+			}
+
+			let anchor = self.objects[attachment.anchor].position;
+			let to_object = self.objects[attachment.object].position - anchor;
+			let distance = to_object.length();
+			// only pulls in, anything closer than the rope allows is free to move
+			if distance > attachment.max_distance {
+				self.objects[attachment.object].position = anchor + to_object / distance * attachment.max_distance;
+			}
+		}
+	}
+
+	// hinges the two bodies together around the pivot, resting at the angle they're at right
+	// now. None when any of them isn't there or b is empty
+	pub fn add_hinge(&mut self, pivot: usize, a: Vec<usize>, b: Vec<usize>, limits: Option<(f32, f32)>, motor: Option<Motor>) -> Option<usize> {
+		let mut hinge = Hinge { pivot, a, b, rest_angle: 0.0, limits, motor };
+		if hinge.a.iter().chain(hinge.b.iter()).any(|&id| !self.objects.contains_index(id)) {
+			return None;
+		}
+		hinge.rest_angle = self.hinge_angle(&hinge)?;
+		self.hinges.push(hinge);
+		Some(self.hinges.len() - 1)
+	}
+
+	// how far it's turned from rest, from -pi to pi
+	pub fn hinge_angle(&self, hinge: &Hinge) -> Option<f32> {
+		let pivot = self.objects.get(hinge.pivot)?.position;
+		let a = match hinge.a.first() {
+			Some(&id) => Some(self.objects.get(id)?.position),
+			None => None,
+		};
+		let b = self.objects.get(*hinge.b.first()?)?.position;
+		Some(hinge.angle(pivot, a, b))
+	}
+
+	pub fn apply_hinges(&mut self, sub_delta: f32) {
+		for i in 0..self.hinges.len() {
+			let Some(angle) = self.hinge_angle(&self.hinges[i]) else {
+				continue;
+			};
+			let hinge = &self.hinges[i];
+			let pivot = &self.objects[hinge.pivot];
+			let (center, last_center) = (pivot.position, pivot.last_position);
+			let (inertia_a, inertia_b) = (inertia(&self.objects, &hinge.a, center), inertia(&self.objects, &hinge.b, center));
+			// how much of a turn each side takes, the one that's harder to turn takes less and one
+			// that can't turn at all takes none
+			let (share_a, share_b) = match (inertia_a, inertia_b) {
+				(Some(a), Some(b)) => (b / (a + b), a / (a + b)),
+				(Some(_), None) => (1.0, 0.0),
+				(None, Some(_)) => (0.0, 1.0),
+				(None, None) => continue,
+			};
+
+			if let Some((min, max)) = hinge.limits {
+				let past = angle - angle.clamp(min, max);
+				if past != 0.0 {
+					turn(&mut self.objects, &hinge.a, center, past * share_a);
+					turn(&mut self.objects, &hinge.b, center, -past * share_b);
+				}
+			}
+
+			if let Some(motor) = hinge.motor {
+				// how fast b is turning against a this substep, from where everything was last
+				let b = &self.objects[hinge.b[0]];
+				let (a, last_a) = match hinge.a.first() {
+					Some(&id) => (self.objects[id].position - center, self.objects[id].last_position - last_center),
+					None => (Vec2::X, Vec2::X),
+				};
+				let now = a.angle_between(b.position - center);
+				let then = last_a.angle_between(b.last_position - last_center);
+				let spin = Vec2::from_angle(then).angle_between(Vec2::from_angle(now));
+
+				// the torque it'd take to get up to speed in one substep, as much of it as the motor has
+				let inertia = match (inertia_a, inertia_b) {
+					(Some(a), Some(b)) => a * b / (a + b),
+					(Some(a), None) | (None, Some(a)) => a,
+					(None, None) => continue,
+				};
+				// and no further than the limits, or it'd push past them again every substep
+				let mut target = motor.speed * sub_delta;
+				if let Some((min, max)) = hinge.limits {
+					let angle = angle.clamp(min, max);
+					target = target.clamp(min - angle, max - angle);
+				}
+				let wanted = (target - spin) / (sub_delta * sub_delta) * inertia;
+				let torque = wanted.clamp(-motor.torque, motor.torque);
+				for (ids, torque, inertia) in [(&hinge.a, -torque, inertia_a), (&hinge.b, torque, inertia_b)] {
+					let Some(inertia) = inertia else {
+						continue;
+					};
+					for &id in ids.iter() {
+						if let Some(object) = self.objects.get_mut(id) {
+							object.accelerate((object.position - center).perp() * (torque / inertia));
+						}
+					}
+				}
+			}
+		}
+	}
+
+	// fills the ring (ids in order around it, already linked) so it keeps the area it has
+	// right now. None when any of it isn't there or there's too little of it to have an area
+	pub fn add_soft_body(&mut self, ids: Vec<usize>, pressure: f32) -> Option<usize> {
+		if ids.len() < 3 {
+			return None;
+		}
+		let mut soft_body = SoftBody { ids, target_area: 0.0, pressure };
+		soft_body.target_area = soft_body.area(&self.objects)?;
+		self.soft_bodies.push(soft_body);
+		Some(self.soft_bodies.len() - 1)
+	}
+
+	// solves the objects (a cloth, a long chain, a soft body's ring) coarsely before their own
+	// links each substep, with proxies about stride links apart. it's built from the links
+	// between them as they are now, so a cloth should get one while it's still hanging straight,
+	// and links added later aren't part of it. None when any of them isn't there or the stride
+	// is under 2
+	pub fn add_hierarchy(&mut self, ids: Vec<usize>, stride: usize, iterations: usize) -> Option<usize> {
+		if stride < 2 || ids.iter().any(|&id| !self.objects.contains_index(id)) {
+			return None;
+		}
+		let mut hierarchy = Hierarchy::new(ids, stride, iterations);
+		hierarchy.build(&self.objects, &self.chain_links, &self.groups);
+		self.hierarchies.push(hierarchy);
+		Some(self.hierarchies.len() - 1)
+	}
+
+	pub fn apply_hierarchies(&mut self) {
+		for hierarchy in self.hierarchies.iter() {
+			hierarchy.solve(&mut self.objects, &self.chain_links, &self.groups, self.link_stiffness, &mut self.scratch.hierarchy);
+		}
+	}
+
+	// pushes every object in the ring straight out (or in) from its neighbours, which is the
+	// quickest way to change the area, the lighter ones further
+	pub fn apply_soft_bodies(&mut self) {
+		for soft_body in self.soft_bodies.iter() {
+			let Some(area) = soft_body.area(&self.objects) else {
+				continue;
+			};
+			let count = soft_body.ids.len();
+			let neighbours = |i: usize| (soft_body.ids[(i + count - 1) % count], soft_body.ids[(i + 1) % count]);
+			// how far the area moves when each object does, the other way round to the ring
+			let gradient = |objects: &Pool<VerletObject>, i: usize| {
+				let (before, after) = neighbours(i);
+				(objects[after].position - objects[before].position).perp() * -0.5
+			};
+
+			let mut total = 0.0;
+			for (i, &id) in soft_body.ids.iter().enumerate() {
+				let object = &self.objects[id];
+				if !object.kinematic {
+					total += gradient(&self.objects, i).length_squared() / object.mass.max(f32::EPSILON);
+				}
+			}
+			if total <= f32::EPSILON {
+				continue;
+			}
+
+			let scale = (soft_body.target_area - area) / total * soft_body.pressure;
+			for (i, &id) in soft_body.ids.iter().enumerate() {
+				let push = gradient(&self.objects, i) * scale;
+				let object = &mut self.objects[id];
+				if !object.kinematic {
+					object.position += push / object.mass.max(f32::EPSILON);
+				}
+			}
+		}
+	}
+
+	// returns the force the drag applied to the grabbed object, treating the correction
+	// as an acceleration over the substep (so it's per unit mass)
+	pub fn apply_drag(&mut self, delta: f32) -> Vec2 {
+		for touch in self.touches.iter() {
+			pull_drag(&mut self.objects, touch);
+		}
+
+		match &self.drag {
+			Some(drag) => pull_drag(&mut self.objects, drag) / (delta * delta),
+			None => Vec2::ZERO,
+		}
+	}
+
+	// half of the speed squared summed over every object, leaving mass out so it reads the same
+	// however the masses are set
+	pub fn kinetic_energy(&self) -> f32 {
+		self.objects.iter()
+			.map(|object| 0.5 * (object.position - object.last_position).length_squared())
+			.sum()
+	}
+
+	pub fn max_speed(&self) -> f32 {
+		self.objects.iter()
+			.map(|object| (object.position - object.last_position).length())
+			.fold(0.0, f32::max)
+	}
+
+	// how far each link is from its target distance, relative to that distance
+	pub fn link_strains(&self) -> Vec<f32> {
+		self.chain_links.iter().map(|chain_link| self.link_strain(chain_link)).collect()
+	}
+
+	pub fn link_strain(&self, chain_link: &ChainLink) -> f32 {
+		let distance = self.objects[chain_link.a].position.distance(self.objects[chain_link.b].position);
+		(distance - chain_link.target_distance).abs() / chain_link.target_distance.max(f32::EPSILON)
+	}
+
+	// the links strained past their max_strain come apart, one at a time so nothing has to
+	// be collected up first
+	fn break_links(&mut self) {
+		for id in 0..self.chain_links.slot_count() {
+			let Some(chain_link) = self.chain_links.get(id) else {
+				continue;
+			};
+			let strained = chain_link.max_strain.is_some_and(|max_strain| self.link_strain(chain_link) > max_strain);
+			if strained && chain_link.is_enabled(&self.groups) {
+				self.break_link(id);
+			}
+		}
+	}
+
+	// the group called name, made (switched on) when there isn't one yet
+	pub fn group(&mut self, name: &str) -> usize {
+		if let Some(group) = self.groups.iter().position(|group| group.name == name) {
+			return group;
+		}
+		self.groups.push(LinkGroup { name: name.into(), enabled: true });
+		self.groups.len() - 1
+	}
+
+	// false when there's no such group. links that come back on pull straight back to their
+	// length, however far apart their ends got while they were off
+	pub fn set_group_enabled(&mut self, group: usize, enabled: bool) -> bool {
+		let Some(group) = self.groups.get_mut(group) else {
+			return false;
+		};
+		group.enabled = enabled;
+		true
+	}
+
+	// counts fuses down, then sets off whatever's burned down or, going off on contact, was
+	// touching anything in the last substep
+	fn detonate_bombs(&mut self, delta: f32) {
+		for id in 0..self.objects.slot_count() {
+			let Some(bomb) = self.objects.get_mut(id).and_then(|object| object.bomb.as_mut()) else {
+				continue;
+			};
+			if let Some(fuse) = &mut bomb.fuse {
+				*fuse -= delta;
+			}
+			let (burned_down, on_contact) = (bomb.is_burned_down(), bomb.on_contact);
+			if burned_down || (on_contact && self.contacts.iter().any(|contact| contact.a == id || contact.b == Some(id))) {
+				self.detonate(id);
+			}
+		}
+	}
+
+	// sets a bomb off right away, fuse or not. false if there's no bomb there
+	pub fn detonate(&mut self, id: usize) -> bool {
+		let (Some(bomb), Some(handle)) = (self.objects.get(id).and_then(|object| object.bomb), self.objects.handle(id)) else {
+			return false;
+		};
+		let Some(object) = self.remove_object(id) else {
+			return false;
+		};
+		let position = object.position;
+
+		let mut links = 0;
+		if bomb.breaks_links {
+			for link in 0..self.chain_links.slot_count() {
+				let near = self.chain_links.get(link).is_some_and(|chain_link|
+					self.objects[chain_link.a].position.distance(position) < bomb.radius
+						|| self.objects[chain_link.b].position.distance(position) < bomb.radius
+				);
+				if near && self.break_link(link) {
+					links += 1;
+				}
+			}
+		}
+
+		// spread around the inside of where it was, already flying out before the blast
+		// catches them too
+		let size = object.radius * BOMB_DEBRIS_SIZE;
+		for i in 0..bomb.debris {
+			let direction = Vec2::from_angle(i as f32 / bomb.debris as f32 * core::f32::consts::TAU);
+			let mut piece = VerletObject::new(position + direction * (object.radius - size), size)
+				.with_color(object.color)
+				.with_lifetime(BOMB_DEBRIS_LIFETIME);
+			piece.last_position = piece.position - direction * BOMB_DEBRIS_SPEED;
+			self.objects.push(piece);
+		}
+
+		self.explode(position, bomb.strength, bomb.radius);
+		self.detonations.push(Detonation { object: handle, position, strength: bomb.strength, radius: bomb.radius, links });
+		true
+	}
+
+	// counts lifetimes down, then takes out whatever's run out of one or gone into a kill zone
+	// the same way remove_object() does
+	fn despawn_objects(&mut self, delta: f32) {
+		for id in 0..self.objects.slot_count() {
+			let Some(object) = self.objects.get_mut(id) else {
+				continue;
+			};
+			if let Some(lifetime) = &mut object.lifetime {
+				*lifetime -= delta;
+			}
+			let expired = object.lifetime.is_some_and(|lifetime| lifetime <= 0.0);
+			let killed = !object.kinematic && self.kill_zones.iter().any(|zone| zone.contains(object.position));
+			if expired || killed {
+				self.remove_object(id);
+			}
+		}
+	}
+
+	// takes a link out into broken_links, whether it breaks or not
+	pub fn break_link(&mut self, id: usize) -> bool {
+		let Some(chain_link) = self.chain_links.remove_at(id) else {
+			return false;
+		};
+		self.broken_links.push(chain_link);
+		true
+	}
+
+	// puts a broken link back (see broken_links) at the length it had, and returns its new
+	// id. it's gone for good if an end is
+	pub fn repair_link(&mut self, index: usize) -> Option<usize> {
+		if index >= self.broken_links.len() {
+			return None;
+		}
+		let chain_link = self.broken_links.remove(index);
+		let ends = self.objects.contains_index(chain_link.a) && self.objects.contains_index(chain_link.b);
+		ends.then(|| self.chain_links.push(chain_link).index)
+	}
+
+	// the deepest overlap between any two objects right now
+	pub fn max_penetration(&self) -> f32 {
+		let mut result = 0.0_f32;
+
+		for (i, a) in self.objects.iter().enumerate() {
+			for b in self.objects.iter().skip(i + 1) {
+				let penetration = a.radius + b.radius - a.position.distance(b.position);
+				result = result.max(penetration);
+			}
+		}
+
+		result
+	}
+
+	// swapping where everything is and where it was flips every implied velocity,
+	// so updating from here on runs the simulation backwards
+	pub fn reverse_time(&mut self) {
+		for object in self.objects.iter_mut() {
+			core::mem::swap(&mut object.position, &mut object.last_position);
+		}
+	}
+
+	pub fn update_positions(&mut self, delta: f32) {
+		for object in self.objects.iter_mut() {
+			if object.kinematic {
+				object.acceleration = Vec2::ZERO;
+				continue;
+			}
+			let damping = match &object.damping_curve {
+				Some(curve) => curve.damping(object.position.distance(object.last_position)),
+				None => object.damping.unwrap_or(self.damping),
+			};
+			object.update_position(delta, damping);
+		}
+	}
+}
+
+// moves everything the drag holds a step towards its target, returns how far the grabbed
+// object itself got pulled
+fn pull_drag(objects: &mut Pool<VerletObject>, drag: &Drag) -> Vec2 {
+	let pull = (drag.target - objects[drag.id].position) * DRAG_STIFFNESS;
+	for &(id, weight) in drag.weights.iter() {
+		if !objects[id].kinematic {
+			objects[id].position += pull * weight;
+		}
+	}
+	pull
+}
+
+// the settings collide() needs, copied out of Master so a pass can go on without it
+#[derive(Clone, Copy)]
+struct PairSettings {
+	hook: Option<PairHook>,
+	sub_delta: f32,
+	impact_speed: f32,
+	friction: f32,
+}
+
+// where a collision pass notes down what happened
+#[derive(Clone, Debug, Default)]
+struct CollisionEvents {
+	contacts: Vec<Contact>,
+	impacts: Vec<Impact>,
+	sensor_overlaps: Vec<(usize, usize)>,
+	bumper_events: Vec<BumperEvent>,
+}
+
+// pushes a (object i) and b (object j) apart if they overlap, bouncing, kicking and rubbing
+// as it goes. returns how far they overlapped, None if they didn't or they were left alone
+fn collide(i: usize, j: usize, a: &mut VerletObject, b: &mut VerletObject, settings: PairSettings, events: &mut CollisionEvents) -> Option<f32> {
+	let collision_axis = a.position - b.position;
+	let distance = collision_axis.length();
+	let object_distance = a.radius + b.radius;
+	if distance >= object_distance {
+		return None;
+	}
+
+	// sensors only take note, from their own side so each pair is only noted once
+	match (a.sensor, b.sensor) {
+		(true, false) => {
+			events.sensor_overlaps.push((i, j));
+			return None;
+		}
+		(false, false) => {}
+		_ => return None,
+	}
+
+	// right on top of each other there's no telling which way apart is, so they go
+	// opposite ways along x
+	let n = match distance > 0.0 {
+		true => collision_axis / distance,
+		false if i < j => Vec2::X,
+		false => -Vec2::X,
+	};
+	let delta = object_distance - distance;
+	let contact = Contact {
+		a: i,
+		b: Some(j),
+		point: b.position + n * b.radius,
+		normal: n,
+		depth: delta,
+	};
+
+	let push = match settings.hook.map_or(PairResponse::Default, |hook| hook(&contact, a, b)) {
+		PairResponse::Skip => return None,
+		PairResponse::Default => delta * n,
+		PairResponse::Custom(push) => push,
+	};
+
+	// every pair comes up twice, once from each side, except with something fixed which
+	// only looks from the other one
+	if i < j || is_fixed(b) {
+		events.contacts.push(contact);
+
+		let velocity = (a.position - a.last_position) - (b.position - b.last_position);
+		let speed = -velocity.dot(n) / settings.sub_delta;
+		if speed > settings.impact_speed {
+			record_impact(&mut events.impacts, Impact {
+				a: i,
+				b: Some(j),
+				point: b.position + n * b.radius,
+				normal: n,
+				speed,
+			});
+		}
+	}
+
+	let (share_i, share_j) = correction_shares(a, b);
+	let approach = a.velocity_towards(n) - b.velocity_towards(n);
+	a.position += share_i * push;
+	b.position -= share_j * push;
+
+	// pushing them apart already leaves them going apart as fast as they were
+	// overlapping, a bouncy one makes up the rest of the speed it came in with and
+	// a bumper adds its kick on top
+	let restitution = a.restitution.max(b.restitution);
+	let kick = (a.kick + b.kick) * settings.sub_delta;
+	if (restitution > 0.0 || kick > 0.0) && approach < 0.0 {
+		let apart = a.velocity_towards(n) - b.velocity_towards(n);
+		let bounce = -approach * restitution + kick - apart;
+		if bounce > 0.0 {
+			a.last_position -= n * (share_i * bounce);
+			b.last_position += n * (share_j * bounce);
+		}
+
+		if kick > 0.0 {
+			let (bumper, object, point, normal) = match a.kick > 0.0 {
+				true => (i, j, a.position - n * a.radius, -n),
+				false => (j, i, b.position + n * b.radius, n),
+			};
+			record_bumper_event(&mut events.bumper_events, BumperEvent { bumper, object, point, normal });
+		}
+	}
+
+	// friction takes back as much of how far they slid past each other this substep
+	// as they were pressed together, so a slow slide stops dead (and stays stopped,
+	// on a slope too) and a fast one only slows
+	let friction = a.friction.unwrap_or(settings.friction).min(b.friction.unwrap_or(settings.friction));
+	if friction > 0.0 {
+		let relative = (a.position - a.last_position) - (b.position - b.last_position);
+		let slide = relative - n * relative.dot(n);
+		let length = slide.length();
+		if length > 0.0 {
+			let stop = slide * (length.min(friction * push.length()) / length);
+			a.position -= stop * share_i;
+			b.position += stop * share_j;
+		}
+	}
+
+	a.contacts += 1;
+	b.contacts += 1;
+	a.pressure += share_i * push.length();
+	b.pressure += share_j * push.length();
+
+	Some(delta)
+}
+
+// can't be pushed and doesn't take note of anything, so these never look for what they're
+// touching. two of them touching is nothing to solve, and everything else looks for them
+fn is_fixed(object: &VerletObject) -> bool {
+	object.kinematic && !object.sensor
+}
+
+// how much of a correction between two objects each one takes, by inverse mass, so the
+// heavier one moves less. kinematic ones don't budge at all
+fn correction_shares(a: &VerletObject, b: &VerletObject) -> (f32, f32) {
+	match (a.kinematic, b.kinematic) {
+		(false, false) => {
+			let (inverse_a, inverse_b) = (1.0 / a.mass.max(f32::EPSILON), 1.0 / b.mass.max(f32::EPSILON));
+			let total = inverse_a + inverse_b;
+			(inverse_a / total, inverse_b / total)
+		}
+		(true, false) => (0.0, 1.0),
+		(false, true) => (1.0, 0.0),
+		(true, true) => (0.0, 0.0),
+	}
+}
+
+// how hard a body is to turn around the center, None when it can't turn at all (anything in
+// it is pinned, or it's the world)
+fn inertia(objects: &Pool<VerletObject>, ids: &[usize], center: Vec2) -> Option<f32> {
+	if ids.is_empty() {
+		return None;
+	}
+	let mut inertia = 0.0;
+	for object in ids.iter().filter_map(|&id| objects.get(id)) {
+		if object.kinematic {
+			return None;
+		}
+		inertia += object.mass * object.position.distance_squared(center);
+	}
+	Some(inertia.max(f32::EPSILON))
+}
+
+// turns every object in the body around the center, only where they are and not where they
+// were, so it keeps the speed it turned at like any other correction
+fn turn(objects: &mut Pool<VerletObject>, ids: &[usize], center: Vec2, angle: f32) {
+	let rotation = Vec2::from_angle(angle);
+	for &id in ids.iter() {
+		if let Some(object) = objects.get_mut(id) {
+			object.position = center + rotation.rotate(object.position - center);
+		}
+	}
+}
+
+// moves an object out of something that doesn't move, the container or a segment, noting
+// the contact and the impact if it hit hard enough
+fn push_out(id: usize, object: &mut VerletObject, overlap: Overlap, impact_speed: f32, sub_delta: f32, impacts: &mut Vec<Impact>, contacts: &mut Vec<Contact>) {
+	// where it touches once it's been pushed out
+	let point = overlap.position - overlap.normal * object.radius;
+
+	let speed = -(object.position - object.last_position).dot(overlap.normal) / sub_delta;
+	if speed > impact_speed {
+		record_impact(impacts, Impact {
+			a: id,
+			b: None,
+			point,
+			normal: overlap.normal,
+			speed,
+		});
+	}
+
+	contacts.push(Contact {
+		a: id,
+		b: None,
+		point,
+		normal: overlap.normal,
+		depth: overlap.depth,
+	});
+	let approach = object.velocity_towards(overlap.normal);
+	object.position = overlap.position;
+
+	// the same bounce as between two objects, with all of it on this one
+	if object.restitution > 0.0 && approach < 0.0 {
+		let bounce = -approach * object.restitution - object.velocity_towards(overlap.normal);
+		if bounce > 0.0 {
+			object.last_position -= overlap.normal * bounce;
+		}
+	}
+}
+
+// friction against something that doesn't move, the container or a segment, after push_out()
+// pressed it depth into it along normal
+fn rub(object: &mut VerletObject, normal: Vec2, depth: f32, friction: f32) {
+	if friction <= 0.0 {
+		return;
+	}
+	let velocity = object.position - object.last_position;
+	let slide = velocity - normal * velocity.dot(normal);
+	let length = slide.length();
+	if length > 0.0 {
+		object.position -= slide * (length.min(friction * depth) / length);
+	}
+}
+
+// one big hit usually takes a few substeps to push apart, so it only keeps the fastest
+// moment of each pair instead of one impact per substep
+fn record_impact(impacts: &mut Vec<Impact>, impact: Impact) {
+	match impacts.iter_mut().find(|other| other.a == impact.a && other.b == impact.b) {
+		Some(other) if other.speed < impact.speed => *other = impact,
+		Some(_) => {}
+		None => impacts.push(impact),
+	}
+}
+
+fn record_bumper_event(bumper_events: &mut Vec<BumperEvent>, event: BumperEvent) {
+	if !bumper_events.iter().any(|other| other.bumper == event.bumper && other.object == event.object) {
+		bumper_events.push(event);
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReversalPhase {
+	Forward,
+	Backward,
+	Done,
+}
+
+// runs forward for a window of frames from a snapshot, then backwards for the same number,
+// and measures how far everything ended up from where it started. verlet itself is exactly
+// reversible, so whatever error is left comes from collisions, constraints and rounding
+#[derive(Clone, Debug)]
+pub struct TimeReversal {
+	pub snapshot: Master,
+	pub window: usize,
+	pub frame: usize,
+	pub phase: ReversalPhase,
+	// mean distance from the snapshot positions once it's done
+	pub error: f32,
+}
+
+impl TimeReversal {
+	pub fn start(master: &Master, window: usize) -> Self {
+		Self {
+			snapshot: master.clone(),
+			window,
+			frame: 0,
+			phase: ReversalPhase::Forward,
+			error: 0.0,
+		}
+	}
+
+	// steps the master one frame, always with the same delta so the way back matches the way there
+	pub fn update(&mut self, master: &mut Master) {
+		if self.phase == ReversalPhase::Done {
+			return;
+		}
+
+		master.update(1.0);
+		self.frame += 1;
+
+		if self.frame < self.window {
+			return;
+		}
+
+		// this flips forward into backward, and at the end of the way back position is one step
+		// before the snapshot with last_position on it, so flipping again lines them back up
+		master.reverse_time();
+		self.frame = 0;
+
+		match self.phase {
+			ReversalPhase::Forward => self.phase = ReversalPhase::Backward,
+			_ => {
+				self.phase = ReversalPhase::Done;
+
+				// by id, and only what was there the whole way. anything spawned or despawned in
+				// between (or spawned into a slot that was emptied) has nothing to compare with
+				let (mut sum, mut count) = (0.0, 0);
+				for (id, before) in self.snapshot.objects.indexed() {
+					if let Some(after) = self.snapshot.objects.handle(id).and_then(|handle| master.objects.lookup(handle)) {
+						sum += after.position.distance(before.position);
+						count += 1;
+					}
+				}
+				self.error = sum / count.max(1) as f32;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::bomb::Bomb;
+	use crate::{generate_chain_links, generate_objects};
+
+	// the bridge with a pile dropped onto it, so there are links and collisions both
+	fn bridge_with_a_pile() -> Master {
+		let mut master = Master::new(generate_objects(), generate_chain_links());
+		for i in 0..30 {
+			master.objects.push(VerletObject::new(vec2(320.0 + (i % 6) as f32 * 25.0, 80.0 + (i / 6) as f32 * 25.0), 10.0));
+		}
+		master
+	}
+
+	fn bits(master: &Master) -> Vec<(usize, [u32; 4])> {
+		master.objects.indexed()
+			.map(|(id, object)| (id, [object.position.x, object.position.y, object.last_position.x, object.last_position.y].map(f32::to_bits)))
+			.collect()
+	}
+
+	#[test]
+	fn touching_something_pinned_is_one_contact_whichever_id_comes_first() {
+		for pinned_first in [true, false] {
+			let pinned = VerletObject::new(vec2(400.0, 300.0), 20.0).kinematic();
+			let ball = VerletObject::new(vec2(400.0, 270.0), 20.0);
+			let objects = match pinned_first {
+				true => vec![pinned, ball],
+				false => vec![ball, pinned],
+			};
+			let mut master = Master::new(objects, vec![]);
+			master.solve_collisions();
+			assert_eq!(master.contacts().count(), 1);
+			let ball = master.objects.iter().find(|object| !object.kinematic).unwrap();
+			assert!((ball.position.y - 260.0).abs() < 0.001);
+		}
+	}
+
+	#[test]
+	fn a_detonation_still_names_the_bomb_once_debris_has_its_slot() {
+		let mut master = Master::new(vec![], vec![]);
+		let mut bomb = Bomb::new(None, false);
+		bomb.debris = 4;
+		let id = master.spawn(VerletObject::new(vec2(400.0, 300.0), 20.0).bomb(bomb));
+		assert!(master.detonate(id.index));
+		let detonation = master.detonations[0];
+		assert_eq!(detonation.object, id);
+		assert!(master.objects.contains_index(id.index));
+		assert!(master.object(detonation.object).is_none());
+	}
+
+	fn reversal_error(mut master: Master) -> f32 {
+		let mut reversal = TimeReversal::start(&master, 60);
+		while reversal.phase != ReversalPhase::Done {
+			reversal.update(&mut master);
+		}
+		reversal.error
+	}
+
+	#[test]
+	fn time_reversal_only_compares_what_was_there_the_whole_way() {
+		// the bridge with a ball out of the way in front of it, gone halfway there
+		let mut objects = vec![VerletObject::new(vec2(100.0, 100.0), 10.0).with_lifetime(30.0)];
+		objects.extend(generate_objects());
+		let chain_links = generate_chain_links().into_iter()
+			.map(|chain_link| ChainLink { a: chain_link.a + 1, b: chain_link.b + 1, ..chain_link })
+			.collect();
+		let with_ball = reversal_error(Master::new(objects, chain_links));
+		let without = reversal_error(Master::new(generate_objects(), generate_chain_links()));
+		assert_eq!(with_ball, without);
+	}
+
+	#[test]
+	fn chains_need_two_objects_that_are_there() {
+		let mut master = Master::new(vec![VerletObject::new(vec2(100.0, 100.0), 10.0), VerletObject::new(vec2(200.0, 100.0), 10.0)], vec![]);
+		assert!(!master.link_chain(0, 0, 4));
+		master.remove_object(1);
+		assert!(!master.link_chain(0, 1, 4));
+		assert_eq!((master.objects.len(), master.chain_links.len()), (1, 0));
+	}
+
+	#[test]
+	fn fixed_point_runs_the_same_every_time() {
+		let run = |updates: usize| {
+			let mut master = bridge_with_a_pile();
+			master.fixed_point = true;
+			for _ in 0..updates {
+				master.update(1.0);
+			}
+			master
+		};
+		let (a, b) = (run(300), run(300));
+		assert_eq!(bits(&a), bits(&b));
+		// and it did go somewhere
+		assert_ne!(bits(&a), bits(&run(0)));
+	}
+
+	// spawning every few updates the way the demo does, with the size and color picked at
+	// random, so the picks change where everything goes
+	fn seeded_run(seed: u64, updates: usize) -> Master {
+		let mut master = bridge_with_a_pile();
+		master.rng = Some(Rng::new(seed));
+		for i in 0..updates {
+			if i % 10 == 0 {
+				let radius = master.random_range(5.0, 15.0);
+				let color = master.random_color();
+				master.spawn(VerletObject::new(vec2(400.0, 60.0), radius).with_color(color));
+			}
+			master.update(1.0);
+		}
+		master
+	}
+
+	#[test]
+	fn the_same_seed_gives_the_same_run() {
+		// the scene's own objects got their colors when they were made, before there was a seed
+		let scene = bridge_with_a_pile().objects.len();
+		let picks = |master: &Master| master.objects.iter().skip(scene).map(|object| (object.radius.to_bits(), object.color)).collect::<Vec<_>>();
+		let (a, b) = (seeded_run(3, 200), seeded_run(3, 200));
+		assert_eq!(bits(&a), bits(&b));
+		assert_eq!(picks(&a), picks(&b));
+
+		let other = seeded_run(4, 200);
+		assert_ne!(picks(&a), picks(&other));
+		assert_ne!(bits(&a), bits(&other));
+	}
+}
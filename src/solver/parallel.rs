@@ -0,0 +1,144 @@
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use super::{collide, is_fixed, record_bumper_event, record_impact, CollisionEvents, PairSettings};
+use crate::object::VerletObject;
+use crate::pool::{pair_mut, Pool};
+
+// how many broadphase cells across a tile is. bigger tiles are fewer, longer jobs
+const TILE_CELLS: f32 = 4.0;
+
+// the copies of one block of 3x3 tiles, the ones in the middle tile first, and what came of
+// solving them. blocks are kept around between calls with everything in them, so once there
+// have been enough of them big enough nothing here allocates
+#[derive(Clone, Debug, Default)]
+struct Block {
+	objects: Vec<(usize, VerletObject)>,
+	middle: usize,
+	xs: Vec<f32>,
+	order: Vec<usize>,
+	events: CollisionEvents,
+	max_penetration: f32,
+}
+
+impl Block {
+	// everything in the middle tile against everything in the block, one pair after another
+	// the same as on one thread
+	fn solve(&mut self, settings: PairSettings) {
+		let Self { objects, middle, xs, order, events, max_penetration } = self;
+		events.contacts.clear();
+		events.impacts.clear();
+		events.sensor_overlaps.clear();
+		events.bumper_events.clear();
+		*max_penetration = 0.0;
+
+		// sorted along x so each one only looks at the ones close enough on that axis. the
+		// positions shift a little as the block's solved, nowhere near enough to matter
+		xs.clear();
+		xs.extend(objects.iter().map(|(_, object)| object.position.x));
+		let largest_radius = objects.iter().map(|(_, object)| object.radius).fold(0.0, f32::max);
+		order.clear();
+		order.extend(0..objects.len());
+		order.sort_unstable_by(|&a, &b| xs[a].total_cmp(&xs[b]));
+
+		for p in 0..*middle {
+			// like on one thread, the other side finds the pairs with fixed ones
+			if is_fixed(&objects[p].1) {
+				continue;
+			}
+			let reach = objects[p].1.radius + largest_radius;
+			// p comes up in there too, which pair_mut() skips
+			let first = order.partition_point(|&q| xs[p] - xs[q] >= reach);
+			for &q in order[first..].iter().take_while(|&&q| xs[q] - xs[p] < reach) {
+				if let Some(((i, a), (j, b))) = pair_mut(objects, p, q) {
+					if let Some(depth) = collide(*i, *j, a, b, settings, events) {
+						*max_penetration = max_penetration.max(depth);
+					}
+				}
+			}
+		}
+	}
+}
+
+// what solve_collisions() works in, kept in Master's scratch between updates
+#[derive(Clone, Debug, Default)]
+pub(super) struct Scratch {
+	// where every object is, (id, tile)
+	tiles: Vec<(usize, (i32, i32))>,
+	// (the middle tile of its block, whether it's anywhere else in it, id)
+	members: Vec<((i32, i32), bool, usize)>,
+	// only the first few are used in any one color, the rest are waiting to be
+	blocks: Vec<Block>,
+}
+
+impl Scratch {
+	pub(super) fn reserve(&mut self, objects: usize) {
+		self.tiles.reserve(objects.saturating_sub(self.tiles.len()));
+		self.members.reserve(objects.saturating_sub(self.members.len()));
+	}
+}
+
+// the tile of this color (0 to 2 along the axis) the one at tile is in the block of
+fn owner(tile: i32, color: i32) -> i32 {
+	match (color - tile).rem_euclid(3) {
+		2 => tile - 1,
+		offset => tile + offset,
+	}
+}
+
+// solve_collisions() over as many threads as rayon has. the world's split into tiles with
+// 9 colors in a repeating 3x3 pattern, and a tile only ever touches objects in the block of
+// 3x3 tiles around it. the blocks around tiles of one color never overlap, so each of them
+// gets copied out, solved on its own and copied back, then it's the next color's turn
+pub(super) fn solve_collisions(objects: &mut Pool<VerletObject>, cell_size: f32, largest_radius: f32, settings: PairSettings, events: &mut CollisionEvents, scratch: &mut Scratch) -> f32 {
+	let Scratch { tiles, members, blocks } = scratch;
+	let tile_size = cell_size.max(largest_radius * 2.0) * TILE_CELLS;
+	tiles.clear();
+	tiles.extend(objects.indexed().map(|(id, object)| {
+		let tile = (object.position / tile_size).floor();
+		(id, (tile.x as i32, tile.y as i32))
+	}));
+
+	let mut max_penetration = 0.0_f32;
+	for color in 0..9 {
+		members.clear();
+		members.extend(tiles.iter().map(|&(id, (x, y))| {
+			let middle = (owner(x, color % 3), owner(y, color / 3));
+			(middle, middle != (x, y), id)
+		}));
+		members.par_sort_unstable();
+
+		// a block with nothing in its middle has nothing to solve
+		let mut used = 0;
+		for members in members.chunk_by(|a, b| a.0 == b.0).filter(|members| !members[0].1) {
+			if used == blocks.len() {
+				blocks.push(Block::default());
+			}
+			let block = &mut blocks[used];
+			block.objects.clear();
+			block.objects.extend(members.iter().map(|&(_, _, id)| (id, objects[id].clone())));
+			block.middle = members.iter().take_while(|member| !member.1).count();
+			used += 1;
+		}
+
+		blocks[..used].par_iter_mut().for_each(|block| block.solve(settings));
+
+		for block in blocks[..used].iter() {
+			for (id, object) in block.objects.iter() {
+				objects[*id] = object.clone();
+			}
+			max_penetration = max_penetration.max(block.max_penetration);
+			events.contacts.extend_from_slice(&block.events.contacts);
+			events.sensor_overlaps.extend_from_slice(&block.events.sensor_overlaps);
+			for &impact in block.events.impacts.iter() {
+				record_impact(&mut events.impacts, impact);
+			}
+			for &event in block.events.bumper_events.iter() {
+				record_bumper_event(&mut events.bumper_events, event);
+			}
+		}
+	}
+
+	max_penetration
+}
@@ -0,0 +1,107 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+
+use macroquad::prelude::*;
+use verlet_integration::{generate_chain_links, generate_objects, Master, VerletObject, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+pub const SWEEP_SUBSTEPS: [usize; 4] = [1, 2, 4, 8];
+pub const SWEEP_DAMPING: [f32; 3] = [0.0, 0.001, 0.01];
+pub const SWEEP_STIFFNESS: [f32; 3] = [0.25, 0.5, 1.0];
+pub const SWEEP_STEPS: usize = 600;
+
+// anything moving faster than this many pixels per frame has probably blown up
+pub const EXPLODED_SPEED: f32 = 50.0;
+
+// the rope bridge with a grid of balls dropped onto it, the same every run
+pub fn sweep_scene() -> Master {
+	let mut master = Master::new(generate_objects(), generate_chain_links());
+
+	for y in 0..4 {
+		for x in 0..12 {
+			master.objects.push(VerletObject::new(
+				vec2(WINDOW_WIDTH * 0.5 - 165.0 + x as f32 * 30.0, WINDOW_HEIGHT * 0.5 - 120.0 + y as f32 * 30.0),
+				12.0,
+			));
+		}
+	}
+
+	master
+}
+
+pub struct SweepResult {
+	pub substeps: usize,
+	pub damping: f32,
+	pub stiffness: f32,
+	pub kinetic_energy: f32,
+	pub max_speed: f32,
+	pub mean_link_strain: f32,
+	pub max_link_strain: f32,
+	pub max_penetration: f32,
+	pub exploded: bool,
+}
+
+pub fn run_configuration(substeps: usize, damping: f32, stiffness: f32, steps: usize) -> SweepResult {
+	let mut master = sweep_scene();
+	master.substeps = substeps;
+	master.damping = damping;
+	master.link_stiffness = stiffness;
+	// the point is to see how each setting holds up on its own
+	master.watchdog.enabled = false;
+
+	let mut exploded = false;
+	for _ in 0..steps {
+		master.update(1.0);
+
+		if master.max_speed() > EXPLODED_SPEED || master.objects.iter().any(|object| !object.position.is_finite()) {
+			exploded = true;
+			break;
+		}
+	}
+
+	let strains = master.link_strains();
+
+	SweepResult {
+		substeps,
+		damping,
+		stiffness,
+		kinetic_energy: master.kinetic_energy(),
+		max_speed: master.max_speed(),
+		mean_link_strain: strains.iter().sum::<f32>() / strains.len().max(1) as f32,
+		max_link_strain: strains.iter().copied().fold(0.0, f32::max),
+		max_penetration: master.max_penetration(),
+		exploded,
+	}
+}
+
+pub fn run(path: &str) -> io::Result<()> {
+	let mut csv = String::from("substeps,damping,stiffness,steps,kinetic_energy,max_speed,mean_link_strain,max_link_strain,max_penetration,exploded\n");
+
+	for substeps in SWEEP_SUBSTEPS {
+		for damping in SWEEP_DAMPING {
+			for stiffness in SWEEP_STIFFNESS {
+				let result = run_configuration(substeps, damping, stiffness, SWEEP_STEPS);
+				let _ = writeln!(
+					csv,
+					"{},{},{},{},{},{},{},{},{},{}",
+					result.substeps,
+					result.damping,
+					result.stiffness,
+					SWEEP_STEPS,
+					result.kinetic_energy,
+					result.max_speed,
+					result.mean_link_strain,
+					result.max_link_strain,
+					result.max_penetration,
+					result.exploded,
+				);
+				println!("substeps {} damping {} stiffness {} done", substeps, damping, stiffness);
+			}
+		}
+	}
+
+	fs::write(path, csv)?;
+	println!("wrote {}", path);
+
+	Ok(())
+}
@@ -0,0 +1,224 @@
+use std::io::{self, stdout, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{self, Color as TerminalColor};
+use crossterm::{cursor, execute, queue, terminal};
+use macroquad::prelude::*;
+
+use verlet_integration::{Constraint, Master, WINDOW_HEIGHT, WINDOW_WIDTH};
+
+use crate::scene::Timeline;
+use crate::{object_positions, spawn_object, Renderer, Spawner, View};
+
+pub const TERMINAL_FPS: f32 = 30.0;
+
+// each character cell is a 2x4 grid of braille dots, this is the bit for each dot
+const BRAILLE_DOTS: [[u8; 4]; 2] = [
+	[0x01, 0x02, 0x04, 0x40],
+	[0x08, 0x10, 0x20, 0x80],
+];
+
+pub struct TerminalRenderer {
+	pub out: Stdout,
+	pub columns: usize,
+	pub rows: usize,
+	pub cells: Vec<u8>,
+	pub colors: Vec<Option<Color>>,
+}
+
+impl TerminalRenderer {
+	pub fn new() -> Self {
+		Self {
+			out: stdout(),
+			columns: 0,
+			rows: 0,
+			cells: vec![],
+			colors: vec![],
+		}
+	}
+
+	fn resize(&mut self) {
+		let (columns, rows) = terminal::size().unwrap_or((80, 24));
+		self.columns = columns as usize;
+		// the last row is kept for the status line
+		self.rows = rows.saturating_sub(1) as usize;
+
+		self.cells.clear();
+		self.cells.resize(self.columns * self.rows, 0);
+		self.colors.clear();
+		self.colors.resize(self.columns * self.rows, None);
+	}
+
+	// a dot at a time, in dots
+	fn plot_line(&mut self, a: Vec2, b: Vec2) {
+		let steps = ((b - a).length() * 2.0).ceil().max(1.0) as usize;
+		for i in 0..steps {
+			let point = a.lerp(b, i as f32 / steps as f32);
+			self.plot(point.x as i32, point.y as i32, None);
+		}
+	}
+
+	fn plot(&mut self, x: i32, y: i32, color: Option<Color>) {
+		if x < 0 || y < 0 || x >= self.columns as i32 * 2 || y >= self.rows as i32 * 4 {
+			return;
+		}
+
+		let cell = (y / 4) as usize * self.columns + (x / 2) as usize;
+		self.cells[cell] |= BRAILLE_DOTS[(x % 2) as usize][(y % 4) as usize];
+		if color.is_some() {
+			self.colors[cell] = color;
+		}
+	}
+
+	fn write_frame(&mut self, master: &Master) -> io::Result<()> {
+		queue!(self.out, cursor::MoveTo(0, 0))?;
+
+		for row in 0..self.rows {
+			let mut last_color = None;
+			for column in 0..self.columns {
+				let cell = row * self.columns + column;
+				let color = self.colors[cell];
+				if color != last_color {
+					let color = color.unwrap_or(GRAY);
+					queue!(self.out, style::SetForegroundColor(TerminalColor::Rgb {
+						r: (color.r * 255.0) as u8,
+						g: (color.g * 255.0) as u8,
+						b: (color.b * 255.0) as u8,
+					}))?;
+					last_color = self.colors[cell];
+				}
+
+				let character = char::from_u32(0x2800 + self.cells[cell] as u32).unwrap_or(' ');
+				queue!(self.out, style::Print(character))?;
+			}
+			queue!(self.out, cursor::MoveToNextLine(1))?;
+		}
+
+		queue!(
+			self.out,
+			style::ResetColor,
+			terminal::Clear(terminal::ClearType::CurrentLine),
+			style::Print(format!("OBJECTS: {}  [space] spawn  [q] quit", master.objects.len())),
+		)?;
+
+		self.out.flush()
+	}
+}
+
+impl Default for TerminalRenderer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Renderer for TerminalRenderer {
+	fn draw(&mut self, master: &Master, positions: &[Vec2], view: &View) {
+		self.resize();
+		let colors = view.color_mode.colors(master);
+
+		// braille dots are roughly square, so one scale fits the whole window in
+		let scale = (self.columns as f32 * 2.0 / WINDOW_WIDTH).min(self.rows as f32 * 4.0 / WINDOW_HEIGHT);
+		let offset = vec2(
+			(self.columns as f32 * 2.0 - WINDOW_WIDTH * scale) * 0.5,
+			(self.rows as f32 * 4.0 - WINDOW_HEIGHT * scale) * 0.5,
+		);
+		let to_dots = |position: Vec2| position * scale + offset;
+
+		// the container's outline, and the segments
+		match &master.constraint {
+			Constraint::Circle { center, radius } => {
+				let (center, radius) = (to_dots(*center), radius * scale);
+				let steps = (radius * 8.0) as usize;
+				for i in 0..steps {
+					let angle = i as f32 / steps as f32 * std::f32::consts::TAU;
+					let point = center + Vec2::from_angle(angle) * radius;
+					self.plot(point.x as i32, point.y as i32, None);
+				}
+			}
+			Constraint::Aabb { min, max } => {
+				let corners = [*min, vec2(max.x, min.y), *max, vec2(min.x, max.y)].map(to_dots);
+				for i in 0..4 {
+					self.plot_line(corners[i], corners[(i + 1) % 4]);
+				}
+			}
+			Constraint::ConvexPolygon(points) => {
+				for i in 0..points.len() {
+					self.plot_line(to_dots(points[i]), to_dots(points[(i + 1) % points.len()]));
+				}
+			}
+			Constraint::None => {}
+		}
+		for segment in master.segments.iter() {
+			self.plot_line(to_dots(segment.a), to_dots(segment.b));
+		}
+
+		for (id, object) in master.objects.indexed() {
+			let color = colors[id];
+			let position = to_dots(positions[id]);
+			let radius = (object.radius * scale).max(0.5);
+			let extent = radius.ceil() as i32;
+
+			for y in -extent..=extent {
+				for x in -extent..=extent {
+					if vec2(x as f32, y as f32).length() <= radius {
+						self.plot(position.x as i32 + x, position.y as i32 + y, Some(color));
+					}
+				}
+			}
+		}
+
+		// a failed write just drops the frame, the next one redraws everything anyway
+		let _ = self.write_frame(master);
+	}
+}
+
+pub fn run(master: Master, timeline: Timeline) -> io::Result<()> {
+	let mut renderer = TerminalRenderer::new();
+	let view = View::default();
+
+	terminal::enable_raw_mode()?;
+	execute!(renderer.out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+	let result = run_loop(master, timeline, &mut renderer, &view);
+
+	execute!(renderer.out, style::ResetColor, cursor::Show, terminal::LeaveAlternateScreen)?;
+	terminal::disable_raw_mode()?;
+
+	result
+}
+
+fn run_loop(mut master: Master, mut timeline: Timeline, renderer: &mut TerminalRenderer, view: &View) -> io::Result<()> {
+	let mut spawner = Spawner::default();
+	let frame_time = Duration::from_secs_f32(1.0 / TERMINAL_FPS);
+	let mut last_frame = Instant::now();
+	let mut positions = vec![];
+
+	loop {
+		while event::poll(frame_time.saturating_sub(last_frame.elapsed()))? {
+			if let Event::Key(key) = event::read()? {
+				if key.kind != KeyEventKind::Press {
+					continue;
+				}
+
+				match key.code {
+					KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+					KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+					KeyCode::Char(' ') => {
+						spawn_object(&mut master);
+					}
+					_ => {}
+				}
+			}
+		}
+
+		// same units as delta_time(), 1.0 is one frame at 60 fps
+		let delta = last_frame.elapsed().as_secs_f32() * 60.0;
+		last_frame = Instant::now();
+
+		timeline.update(&mut master, &mut spawner);
+		master.update(delta);
+		object_positions(&master, &mut positions);
+		renderer.draw(&master, &positions, view);
+	}
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use verlet_integration::Master;
+
+use crate::camera::WorldCamera;
+use crate::Command;
+
+pub const TOUCH_GRAB_RADIUS: f32 = 40.0;
+pub const TOUCH_FALLOFF_HOPS: usize = 2;
+// a finger on empty space pulls in everything this close, harder the closer it is
+pub const ATTRACT_RADIUS: f32 = 150.0;
+pub const ATTRACT_STRENGTH: f32 = 1.5;
+// a touch that lets go this quickly without moving much is a tap, which spawns an object
+pub const TAP_FRAMES: f32 = 15.0;
+pub const TAP_DISTANCE: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug)]
+struct Finger {
+	start: Vec2,
+	frames: f32,
+	// the object it's dragging, if it came down on one
+	grabbed: Option<usize>,
+}
+
+// every finger on the screen is its own drag (or attractor, when it isn't on anything), so
+// things can be pulled around with more than one at once
+#[derive(Default)]
+pub struct TouchInput {
+	fingers: HashMap<u64, Finger>,
+}
+
+impl TouchInput {
+	pub fn update(&mut self, master: &mut Master, commands: &mut Vec<Command>, camera: &WorldCamera, delta: f32) {
+		let touches = touches();
+		if touches.is_empty() && self.fingers.is_empty() {
+			return;
+		}
+		// the fingers do their own thing now instead of pretending to be the mouse
+		simulate_mouse_with_touch(false);
+
+		for touch in touches.iter() {
+			let position = camera.screen_to_world(touch.position);
+			match touch.phase {
+				TouchPhase::Started => {
+					let grabbed = master.objects_in_radius(position, TOUCH_GRAB_RADIUS).into_iter()
+						.filter(|&id| master.touches.iter().all(|drag| drag.id != id))
+						.min_by(|&a, &b| {
+							let distance = |id: usize| master.objects[id].position.distance(position);
+							distance(a).total_cmp(&distance(b))
+						});
+					if let Some(id) = grabbed {
+						let drag = master.grab(id, position, TOUCH_FALLOFF_HOPS);
+						master.touches.push(drag);
+					}
+
+					self.fingers.insert(touch.id, Finger {
+						start: position,
+						frames: 0.0,
+						grabbed,
+					});
+				}
+				TouchPhase::Moved | TouchPhase::Stationary => {
+					let Some(finger) = self.fingers.get_mut(&touch.id) else {
+						continue;
+					};
+					finger.frames += delta;
+
+					match finger.grabbed {
+						Some(id) => {
+							if let Some(drag) = master.touches.iter_mut().find(|drag| drag.id == id) {
+								drag.target = position;
+							}
+						}
+						None => attract(master, position),
+					}
+				}
+				TouchPhase::Ended | TouchPhase::Cancelled => {
+					let Some(finger) = self.fingers.remove(&touch.id) else {
+						continue;
+					};
+
+					match finger.grabbed {
+						Some(id) => master.touches.retain(|drag| drag.id != id),
+						None if touch.phase == TouchPhase::Ended
+							&& finger.frames <= TAP_FRAMES
+							&& finger.start.distance(position) <= TAP_DISTANCE =>
+						{
+							commands.push(Command::Spawn { position, radius: None, tag: 0, velocity: Vec2::ZERO });
+						}
+						None => {}
+					}
+				}
+			}
+		}
+
+		// fingers whose object got removed out from under them
+		for finger in self.fingers.values_mut() {
+			if finger.grabbed.is_some_and(|id| master.touches.iter().all(|drag| drag.id != id)) {
+				finger.grabbed = None;
+			}
+		}
+	}
+}
+
+fn attract(master: &mut Master, position: Vec2) {
+	for object in master.objects.iter_mut() {
+		let to_finger = position - object.position;
+		let distance = to_finger.length();
+		if distance < ATTRACT_RADIUS && distance > 0.0 {
+			object.accelerate(to_finger / distance * ATTRACT_STRENGTH * (1.0 - distance / ATTRACT_RADIUS));
+		}
+	}
+}
@@ -0,0 +1,63 @@
+use std::collections::{HashMap, VecDeque};
+
+use macroquad::prelude::*;
+use verlet_integration::{Master, ObjectId};
+
+// how far along a trail fades out to nothing, from the object back
+const TRAIL_ALPHA: f32 = 0.6;
+
+// a streak behind everything with the tag, through where it's been over the last so many
+// frames, for seeing how a stream of them flows around things. one gets dropped once its
+// object's gone
+#[derive(Clone, Debug)]
+pub struct Trail {
+	pub tag: u32,
+	pub length: usize,
+	paths: HashMap<ObjectId, VecDeque<Vec2>>,
+	// the simulation's time it last took a point at, so it keeps a point a frame however
+	// many or few it runs each frame on screen
+	time: Option<f32>,
+}
+
+impl Trail {
+	pub fn new(tag: u32, length: usize) -> Self {
+		Self { tag, length, paths: HashMap::new(), time: None }
+	}
+
+	pub fn update(&mut self, master: &Master) {
+		if self.time == Some(master.stats.time) {
+			return;
+		}
+		self.time = Some(master.stats.time);
+
+		self.paths.retain(|&id, _| master.object(id).is_some_and(|object| object.tag == self.tag));
+		for (index, object) in master.objects.indexed().filter(|(_, object)| object.tag == self.tag) {
+			let Some(id) = master.object_id(index) else {
+				continue;
+			};
+			let path = self.paths.entry(id).or_default();
+			if path.len() >= self.length {
+				path.pop_front();
+			}
+			path.push_back(object.position);
+		}
+	}
+
+	// in each object's own color, up to where it's being drawn right now. zoom is the
+	// camera's, so they stay the same thickness on screen
+	pub fn draw(&self, master: &Master, zoom: f32) {
+		let thickness = 1.0 / zoom;
+		for (&id, path) in self.paths.iter() {
+			let Some(object) = master.object(id) else {
+				continue;
+			};
+			let color: Color = object.color.into();
+			let mut last = object.position;
+			for (i, &point) in path.iter().rev().enumerate() {
+				let alpha = TRAIL_ALPHA * (1.0 - i as f32 / self.length as f32);
+				draw_line(last.x, last.y, point.x, point.y, thickness, Color { a: alpha, ..color });
+				last = point;
+			}
+		}
+	}
+}
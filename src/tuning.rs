@@ -0,0 +1,89 @@
+use egui_macroquad::egui::{self, Slider};
+use macroquad::prelude::*;
+
+use verlet_integration::{Constraint, Master, GRAVITY, PHYSICS_SUBSTEPS};
+
+use crate::input::{Action, InputMap};
+use crate::{Spawner, SPAWN_SIZES};
+
+// how far each slider goes
+pub const TUNING_MAX_GRAVITY: f32 = 3.0;
+pub const TUNING_MAX_WIND: f32 = 1.0;
+pub const TUNING_MAX_SUBSTEPS: usize = 16;
+pub const TUNING_MAX_RADIUS: f32 = 1000.0;
+pub const TUNING_MAX_SIZE: f32 = 80.0;
+pub const TUNING_MAX_OBJECTS: usize = 5000;
+
+// sliders for the settings that would otherwise mean changing a constant and building it
+// again. they change the master and the spawner straight away rather than going out as
+// commands, so a recording starts from wherever they were and doesn't see them move
+#[derive(Clone, Debug, Default)]
+pub struct TuningPanel {
+	pub open: bool,
+	// whether the mouse or keyboard was on the window last frame, so it doesn't also spawn
+	// or grab something through it
+	busy: bool,
+}
+
+impl TuningPanel {
+	pub fn update(&mut self, input: &mut InputMap, master: &mut Master, spawner: &mut Spawner) {
+		if input.pressed(Action::Tuning) {
+			self.open = !self.open;
+		}
+		if !self.open {
+			self.busy = false;
+			return;
+		}
+
+		let mut open = self.open;
+		egui_macroquad::ui(|ctx| {
+			egui::Window::new("tuning").open(&mut open).show(ctx, |ui| {
+				ui.label(format!("{} objects", master.objects.len()));
+
+				ui.add(Slider::new(&mut master.gravity.x, -TUNING_MAX_GRAVITY..=TUNING_MAX_GRAVITY).text("gravity x"));
+				ui.add(Slider::new(&mut master.gravity.y, -TUNING_MAX_GRAVITY..=TUNING_MAX_GRAVITY).text("gravity y"));
+				ui.add(Slider::new(&mut master.wind.x, -TUNING_MAX_WIND..=TUNING_MAX_WIND).text("wind"));
+				ui.add(Slider::new(&mut master.substeps, 1..=TUNING_MAX_SUBSTEPS).text("substeps"));
+				ui.add(Slider::new(&mut master.damping, 0.0..=1.0).logarithmic(true).text("damping"));
+				ui.add(Slider::new(&mut master.friction, 0.0..=1.0).text("friction"));
+				if let Constraint::Circle { radius, .. } = &mut master.constraint {
+					ui.add(Slider::new(radius, 10.0..=TUNING_MAX_RADIUS).text("container radius"));
+				}
+
+				let (min, max) = &mut spawner.sizes;
+				ui.add(Slider::new(min, 1.0..=TUNING_MAX_SIZE).text("smallest spawn"));
+				ui.add(Slider::new(max, 1.0..=TUNING_MAX_SIZE).text("biggest spawn"));
+				*max = max.max(*min);
+
+				let mut capped = spawner.max_objects.is_some();
+				let mut cap = spawner.max_objects.unwrap_or(TUNING_MAX_OBJECTS / 5);
+				ui.horizontal(|ui| {
+					ui.checkbox(&mut capped, "most objects");
+					ui.add_enabled(capped, Slider::new(&mut cap, 1..=TUNING_MAX_OBJECTS));
+				});
+				spawner.max_objects = capped.then_some(cap);
+
+				if ui.button("defaults").clicked() {
+					master.gravity = vec2(0.0, GRAVITY);
+					master.wind = Vec2::ZERO;
+					master.substeps = PHYSICS_SUBSTEPS;
+					master.damping = 0.0;
+					master.friction = 0.0;
+					spawner.sizes = SPAWN_SIZES;
+					spawner.max_objects = None;
+				}
+			});
+			self.busy = ctx.wants_pointer_input() || ctx.wants_keyboard_input();
+		});
+		self.open = open;
+
+		input.suspended |= self.busy;
+	}
+
+	// on top of everything else, in screen space
+	pub fn draw(&self) {
+		if self.open {
+			egui_macroquad::draw();
+		}
+	}
+}
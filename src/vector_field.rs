@@ -0,0 +1,60 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use glam::{vec2, Vec2};
+
+use crate::object::VerletObject;
+use crate::pool::Pool;
+
+// a grid laid over the world from its top left corner, every cell accelerating whatever has
+// its middle in it the cell's way, in pixels a frame squared like gravity. they're stored a
+// row at a time, and everything outside the grid is left alone
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VectorField {
+	pub origin: Vec2,
+	pub cell_size: f32,
+	pub columns: usize,
+	pub rows: usize,
+	pub cells: Vec<Vec2>,
+}
+
+impl VectorField {
+	// pushing nothing anywhere yet
+	pub fn new(origin: Vec2, cell_size: f32, columns: usize, rows: usize) -> Self {
+		Self {
+			origin,
+			cell_size,
+			columns,
+			rows,
+			cells: vec![Vec2::ZERO; columns * rows],
+		}
+	}
+
+	// which one the point's in, if any
+	pub fn cell(&self, point: Vec2) -> Option<usize> {
+		let offset = (point - self.origin) / self.cell_size;
+		if offset.x < 0.0 || offset.y < 0.0 {
+			return None;
+		}
+		let (column, row) = (offset.x as usize, offset.y as usize);
+		(column < self.columns && row < self.rows).then_some(row * self.columns + column)
+	}
+
+	pub fn center(&self, cell: usize) -> Vec2 {
+		let (column, row) = (cell % self.columns, cell / self.columns);
+		self.origin + (vec2(column as f32, row as f32) + 0.5) * self.cell_size
+	}
+
+	pub fn at(&self, point: Vec2) -> Vec2 {
+		self.cell(point).map_or(Vec2::ZERO, |cell| self.cells[cell])
+	}
+
+	// sensors don't feel it, pinned objects don't move anyway
+	pub fn apply(&self, objects: &mut Pool<VerletObject>) {
+		for object in objects.iter_mut().filter(|object| !object.sensor) {
+			let acceleration = self.at(object.position);
+			object.accelerate(acceleration);
+		}
+	}
+}
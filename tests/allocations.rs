@@ -0,0 +1,60 @@
+// update() promises not to allocate once reserve() has made room, so everything any thread
+// allocates while it runs is counted. the count is shared, so the tests take turns
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use verlet_integration::{generate_chain_links, generate_cloth, generate_objects, vec2, Capacity, Master, VerletObject};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static TURN: Mutex<()> = Mutex::new(());
+
+unsafe impl GlobalAlloc for Counting {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, size: usize) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.realloc(pointer, layout, size) }
+	}
+
+	unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(pointer, layout) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations_while(master: &mut Master, updates: usize) -> usize {
+	let _turn = TURN.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+	let before = ALLOCATIONS.load(Ordering::Relaxed);
+	for _ in 0..updates {
+		master.update(1.0);
+	}
+	ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn updating_the_bridge_does_not_allocate() {
+	let mut master = Master::new(generate_objects(), generate_chain_links());
+	for i in 0..40 {
+		master.objects.push(VerletObject::new(vec2(300.0 + (i % 10) as f32 * 30.0, 100.0 + (i / 10) as f32 * 30.0), 12.0));
+	}
+	master.reserve(Capacity { objects: 200, chain_links: 200 });
+	assert_eq!(allocations_while(&mut master, 120), 0);
+}
+
+#[test]
+fn updating_a_cloth_with_a_hierarchy_does_not_allocate() {
+	let (objects, chain_links) = generate_cloth(vec2(300.0, 60.0), 24, 16, 16.0);
+	let mut master = Master::new(objects, chain_links);
+	let ids = master.objects.ids().collect();
+	master.add_hierarchy(ids, 4, 2).unwrap();
+	master.reserve(Capacity { objects: 500, chain_links: 2000 });
+	assert_eq!(allocations_while(&mut master, 120), 0);
+}